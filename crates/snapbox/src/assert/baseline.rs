@@ -0,0 +1,55 @@
+use crate::filter::{Filter as _, FilterNewlines};
+use crate::IntoData;
+
+/// Assert on how `actual` differs from a baseline, see [`Assert::diff_against`][super::Assert::diff_against]
+///
+/// Note: Requires feature flag `diff`
+#[derive(Clone, Debug)]
+pub struct DiffAgainst {
+    config: super::Assert,
+    baseline: crate::Data,
+}
+
+impl DiffAgainst {
+    pub(crate) fn new(config: super::Assert, baseline: crate::Data) -> Self {
+        Self { config, baseline }
+    }
+
+    /// Check if the diff from the baseline to `actual` is `expected_diff`
+    ///
+    /// The diff is in the unified format, with 3 lines of context:
+    /// ```text
+    /// --- baseline
+    /// +++ actual
+    /// @@ -1,2 +1,2 @@
+    ///  unchanged
+    /// -removed
+    /// +added
+    /// ```
+    /// When there are no differences, the diff is empty.
+    ///
+    /// `expected_diff` is matched like in [`Assert::eq`][super::Assert::eq], including patterns
+    /// and updating it with `SNAPSHOTS=overwrite`.
+    #[track_caller]
+    pub fn eq(&self, actual: impl IntoData, expected_diff: impl IntoData) {
+        let actual = actual.into_data();
+        let expected_diff = expected_diff.into_data();
+        match self.diff(actual) {
+            Ok(diff) => self.config.eq(diff, expected_diff),
+            Err(err) => err.panic(),
+        }
+    }
+
+    fn diff(&self, actual: crate::Data) -> crate::assert::Result<crate::Data> {
+        let render = |data: crate::Data, name: &str| {
+            let data = FilterNewlines.filter(data.coerce_to(crate::data::DataFormat::Text));
+            data.render()
+                .ok_or_else(|| crate::assert::Error::from(format!("{name} is not text: {data}")))
+        };
+        let baseline = render(self.baseline.clone(), "baseline")?;
+        let actual = render(actual, "actual")?;
+        Ok(crate::Data::text(crate::report::unified_diff(
+            &baseline, &actual,
+        )))
+    }
+}
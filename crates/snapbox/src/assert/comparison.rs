@@ -0,0 +1,130 @@
+/// The outcome of checking `actual` against `expected`, see [`Assert::compare`][super::Assert::compare]
+///
+/// This is intended for snapshot management tooling that needs to track assertions over time.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub(crate) label: Option<String>,
+    pub(crate) location: String,
+    pub(crate) actual: crate::Data,
+    pub(crate) expected: crate::Data,
+    pub(crate) is_match: bool,
+    pub(crate) bindings: Vec<(&'static str, String)>,
+    /// `(expected, actual)`, when both are text
+    #[cfg(feature = "diff")]
+    pub(crate) rendered: Option<(String, String)>,
+}
+
+impl Comparison {
+    /// The user-supplied [label][super::Assert::label]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Where the snapshot lives
+    ///
+    /// This is the source of `expected` if it has one, otherwise the caller's location.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Stable identifier for this assertion, derived from the [location][Self::location] and
+    /// [label][Self::label]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let comparison = snapbox::Assert::new()
+    ///     .label("greeting")
+    ///     .compare("hello", snapbox::str!["hello"]);
+    /// assert!(comparison.key().ends_with("#greeting"));
+    /// ```
+    pub fn key(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{}#{}", self.location, label),
+            None => self.location.clone(),
+        }
+    }
+
+    /// `actual`, after normalization
+    pub fn actual(&self) -> &crate::Data {
+        &self.actual
+    }
+
+    pub fn expected(&self) -> &crate::Data {
+        &self.expected
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.is_match
+    }
+
+    /// Metadata from [`Data::with_meta`][crate::Data::with_meta], preferring `expected`'s
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let expected = snapbox::Data::text("hello").with_meta("title", "Greeting");
+    /// let comparison = snapbox::Assert::new().compare("hello", expected);
+    /// assert_eq!(comparison.meta("title"), Some("Greeting"));
+    /// ```
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.expected.meta(key).or_else(|| self.actual.meta(key))
+    }
+
+    /// All metadata, see [`Comparison::meta`]
+    #[cfg(feature = "junit")]
+    pub(crate) fn meta_entries(&self) -> std::collections::BTreeMap<&str, &str> {
+        let mut entries = std::collections::BTreeMap::new();
+        for data in [&self.actual, &self.expected] {
+            for (key, value) in &data.meta {
+                entries.insert(key.as_str(), value.as_str());
+            }
+        }
+        entries
+    }
+
+    /// The text each redaction placeholder in `expected` matched in `actual`
+    ///
+    /// Only the first match for each placeholder is reported.
+    /// These can be fed into [`Redactions::insert_bindings`][crate::Redactions::insert_bindings]
+    /// to require later assertions to see the same values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut redactions = snapbox::Redactions::new();
+    /// redactions.insert("[NAME]", "world").unwrap();
+    /// let comparison = snapbox::Assert::new()
+    ///     .redact_with(redactions)
+    ///     .compare("Hello world!", snapbox::str!["Hello [NAME]!"]);
+    /// assert_eq!(comparison.bindings(), [("[NAME]", "world".to_owned())]);
+    /// ```
+    pub fn bindings(&self) -> &[(&'static str, String)] {
+        &self.bindings
+    }
+
+    /// Line-by-line diff of [`expected`][Self::expected] and [`actual`][Self::actual]
+    ///
+    /// This is the diff shown when an assertion fails.
+    /// Returns `None` if either can't be rendered as text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use snapbox::report::DiffOp;
+    ///
+    /// let comparison = snapbox::Assert::new().compare("a\nc\n", "a\nb\n");
+    /// let ops: Vec<_> = comparison.diff_ops().unwrap().collect();
+    /// assert_eq!(
+    ///     ops,
+    ///     [DiffOp::Equal("a\n"), DiffOp::Delete("b\n"), DiffOp::Insert("c\n")]
+    /// );
+    /// ```
+    ///
+    /// Note: Requires feature flag `diff`
+    #[cfg(feature = "diff")]
+    pub fn diff_ops(&self) -> Option<impl Iterator<Item = crate::report::DiffOp<'_>>> {
+        let (expected, actual) = self.rendered.as_ref()?;
+        Some(crate::report::diff_ops(expected, actual))
+    }
+}
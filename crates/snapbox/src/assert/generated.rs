@@ -0,0 +1,73 @@
+use crate::data::DataFormat;
+
+/// Load the `expected` cached for `entry`, calling `generate` when missing or out-of-date
+///
+/// The cache file starts with a header of the escaped `version_key` and the format's extension,
+/// each on their own line, followed by the data.
+pub(crate) fn load_or_generate(
+    dir: &std::path::Path,
+    entry: &str,
+    version_key: &str,
+    generate: impl FnOnce() -> crate::Data,
+) -> crate::assert::Result<crate::Data> {
+    let path = dir.join(file_name(entry));
+    let version_key = version_key.escape_default().to_string();
+    if let Some(data) = load(&path, &version_key) {
+        return Ok(data);
+    }
+
+    let data = generate();
+    let format = data.format();
+    if format == DataFormat::Error {
+        return Ok(data);
+    }
+    let mut content = format!("{version_key}\n{}\n", format.ext()).into_bytes();
+    content.extend(data.to_bytes()?);
+
+    // Write to a file unique to this call and rename it into place so concurrent tests never read
+    // a partially written entry.  When they race, the last one to finish wins.
+    static TMP_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let tmp = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, content)
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to write {}: {}", path.display(), e)
+    })?;
+
+    Ok(data)
+}
+
+fn load(path: &std::path::Path, version_key: &str) -> Option<crate::Data> {
+    let content = std::fs::read(path).ok()?;
+    let mut parts = content.splitn(3, |b| *b == b'\n');
+    if parts.next()? != version_key.as_bytes() {
+        return None;
+    }
+    let ext = std::str::from_utf8(parts.next()?).ok()?;
+    let data = parts.next()?.to_vec();
+    let format = match ext {
+        "bin" => DataFormat::Binary,
+        _ => DataFormat::from(std::path::Path::new(&format!("generated.{ext}"))),
+    };
+    Some(crate::Data::binary(data).coerce_to(format))
+}
+
+/// Turn an entry, like `tests/cli.rs:10:5#label`, into a file name
+fn file_name(entry: &str) -> String {
+    entry
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// The default cache directory, within the target directory
+pub(crate) fn default_dir() -> std::path::PathBuf {
+    crate::cmd::target_dir().join("snapbox-generated")
+}
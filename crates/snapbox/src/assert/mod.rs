@@ -1,5 +1,10 @@
 mod action;
+#[cfg(feature = "diff")]
+mod baseline;
+mod comparison;
 mod error;
+mod generated;
+mod usage;
 
 #[cfg(feature = "color")]
 use anstream::panic;
@@ -8,13 +13,17 @@ use anstream::stderr;
 #[cfg(not(feature = "color"))]
 use std::io::stderr;
 
-use crate::filter::{Filter as _, FilterNewlines, FilterPaths, NormalizeToExpected};
+use crate::filter::{Filter as _, FilterNewlines, NormalizeToExpected};
 use crate::IntoData;
 
 pub use action::Action;
 pub use action::DEFAULT_ACTION_ENV;
+#[cfg(feature = "diff")]
+pub use baseline::DiffAgainst;
+pub use comparison::Comparison;
 pub use error::Error;
 pub use error::Result;
+pub use usage::GoldenUsage;
 
 /// Snapshot assertion against a file's contents
 ///
@@ -32,8 +41,28 @@ pub use error::Result;
 pub struct Assert {
     pub(crate) action: Action,
     action_var: Option<String>,
-    normalize_paths: bool,
+    label: Option<String>,
+    path_separators: crate::filter::PathSeparators,
+    check_mode: bool,
+    sizes_first: bool,
     substitutions: crate::Redactions,
+    preprocessors: crate::filter::Preprocessors,
+    filters: crate::filter::FilterChain,
+    usage: Option<GoldenUsage>,
+    /// Built-in filters for both `actual` and `expected`, in the order they were enabled
+    normalizers: crate::filter::FilterChain,
+    dump_actual: Option<std::path::PathBuf>,
+    generated_dir: Option<std::path::PathBuf>,
+    #[cfg(feature = "diff")]
+    max_diff_lines: Option<usize>,
+    #[cfg(feature = "json")]
+    unordered_at: Vec<String>,
+    #[cfg(feature = "json")]
+    whole_numbers: bool,
+    trim_elide_anchors: bool,
+    case_insensitive: bool,
+    report_redactions: bool,
+    lint_patterns: bool,
     pub(crate) palette: crate::report::Palette,
 }
 
@@ -46,11 +75,33 @@ impl Assert {
     /// Check if a value is the same as an expected value
     ///
     /// By default [`filters`][crate::filter] are applied, including:
-    /// - `...` is a line-wildcard when on a line by itself
+    /// - `...` is a line-wildcard when on a line by itself, matching zero or more lines
+    /// - `...{2}`, `...{0,3}`, and `...{1,}` limit how many lines `...` matches
     /// - `[..]` is a character-wildcard when inside a line
+    /// - `[..>]` is `[..]` that matches as much as it can, resuming at the last occurrence of what
+    ///   follows it, like `[..>]/file` for `path/to/path/to/file`
+    /// - `[...]` is a character-wildcard that can span lines, like for wrapped text, up to the
+    ///   line matching the next expected line
+    /// - Lines between `<<<verbatim` and `>>>` must match exactly, without redactions
+    /// - Lines between `[UNORDERED]` and `[/UNORDERED]` may match in any order
+    /// - `[?] ` at the start of a line makes the rest of the line optional
+    /// - `[!CONTAINS] ` at the start of a line fails if any line contains the rest of the line
+    /// - `[!ONE_OF] A|B|C` matches a line against any one of the alternatives (`\|` for a
+    ///   literal `|`)
     /// - `[EXE]` matches `.exe` on Windows
+    /// - `[N:0..100]` matches an integer in a range (`..=` for an inclusive end)
+    /// - `[NUM:1.5±0.05]` and `[NUM:200~5%]` match a number within a tolerance
+    /// - `[ID:<name>]` matches any text, the same each time `name` is used
+    /// - `[SEMVER]` and `[SEMVER:^1.2]` match a semantic version (requires feature `semver`)
+    /// - `[regex:<regex>]` matches text against a regex (requires feature `regex`)
+    /// - `[literal:<text>]` matches `<text>` exactly, like `[literal:[..]]` or `[literal:...]`
     /// - `"{...}"` is a JSON value wildcard
+    /// - `"{number}"`, `"{string}"`, `"{bool}"`, `"{array}"`, and `"{object}"` match any JSON value
+    ///   of that type
+    /// - `"{regex:<pattern>}"` matches a JSON string against a regex (requires feature `regex`)
+    /// - `"{duration}"` matches a JSON number or `{"secs": ..., "nanos": ...}` duration
     /// - `"...": "{...}"` is a JSON key-value wildcard
+    /// - `{"$unordered": [...]}` matches a JSON array with the same elements in any order
     /// - `\` to `/`
     /// - Newlines
     ///
@@ -87,6 +138,85 @@ impl Assert {
         self.eq(actual, expected);
     }
 
+    /// Check how `actual` differs from `baseline`, rather than `actual` itself
+    ///
+    /// This is for reviewing intentional changes as diffs: the accepted diff is the snapshot.
+    /// See [`DiffAgainst::eq`] for the diff format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let baseline = "name = \"foo\"\nversion = \"1.0.0\"\n";
+    /// let actual = "name = \"foo\"\nversion = \"1.1.0\"\n";
+    /// Assert::new().diff_against(baseline).eq(
+    ///     actual,
+    ///     snapbox::str![[r#"
+    /// --- baseline
+    /// +++ actual
+    /// @@ -1,2 +1,2 @@
+    ///  name = "foo"
+    /// -version = "1.0.0"
+    /// +version = "1.1.[..]"
+    ///
+    /// "#]],
+    /// );
+    /// ```
+    ///
+    /// Note: Requires feature flag `diff`
+    #[cfg(feature = "diff")]
+    pub fn diff_against(&self, baseline: impl IntoData) -> DiffAgainst {
+        DiffAgainst::new(self.clone(), baseline.into_data())
+    }
+
+    /// Check if a value is the same as an expected value that is expensive to produce
+    ///
+    /// `generate` is called for `expected` and the result is cached, like when rendering with a
+    /// reference implementation.  The cached `expected` is reused across test runs until
+    /// `version_key` changes, so change it whenever `generate` would produce something different.
+    ///
+    /// Cache entries are per [`label`][Assert::label] and call site.  Tests that race to
+    /// generate the same entry each call `generate` and the last to finish is cached.
+    /// See [`Assert::generated_cache_dir`] for where they are stored.
+    ///
+    /// `expected` is matched like with [`Assert::eq`] but, as there is no snapshot, it is never
+    /// overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # fn render_reference() -> String { String::new() }
+    /// let actual = "<svg/>";
+    /// snapbox::Assert::new().eq_generated(actual, "reference-v2", || render_reference());
+    /// ```
+    #[track_caller]
+    pub fn eq_generated<E: IntoData>(
+        &self,
+        actual: impl IntoData,
+        version_key: &str,
+        generate: impl FnOnce() -> E,
+    ) {
+        let location = std::panic::Location::caller();
+        let entry = match &self.label {
+            Some(label) => format!("{location}#{label}"),
+            None => location.to_string(),
+        };
+        let dir = match &self.generated_dir {
+            Some(dir) => dir.clone(),
+            None => generated::default_dir(),
+        };
+        let expected =
+            match generated::load_or_generate(&dir, &entry, version_key, || generate().into_data())
+            {
+                Ok(expected) => expected,
+                Err(err) => err.panic(),
+            };
+        let actual = actual.into_data();
+        if let Err(err) = self.try_eq(Some(&"In-memory"), actual, expected) {
+            err.panic();
+        }
+    }
+
     pub fn try_eq(
         &self,
         actual_name: Option<&dyn std::fmt::Display>,
@@ -103,12 +233,526 @@ impl Assert {
             Action::Ignore | Action::Verify | Action::Overwrite => {}
         }
 
+        self.record_usage(&expected);
+        if self.lint_patterns {
+            self.check_patterns(&expected)?;
+        }
+        let (actual, expected) = self.prepare(actual, expected);
+        let report = self
+            .report_redactions
+            .then(|| self.redaction_report(&actual, &expected));
+        let actual = self.normalize_to_expected(actual, &expected);
+
+        let mut tolerance = String::new();
+        if let Some((diff_lines, max_diff_lines)) = self.tolerated_diff_lines(&actual, &expected) {
+            if diff_lines <= max_diff_lines {
+                return Ok(());
+            }
+            tolerance =
+                format!("{diff_lines} lines differ, more than the {max_diff_lines} allowed\n");
+        }
+
+        self.do_action(actual_name, actual, expected, &|actual, expected| {
+            actual == expected
+        })
+        .map_err(|err| {
+            let report = report.unwrap_or_default();
+            Error::new(format_args!("{tolerance}{err}{report}"))
+        })
+    }
+
+    /// Check that `actual` is empty once normalized, like for `OutputAssert::stderr_empty`
+    ///
+    /// There is nothing to overwrite, so [`Action::Overwrite`] fails like [`Action::Verify`].
+    pub(crate) fn try_empty(
+        &self,
+        actual_name: &dyn std::fmt::Display,
+        actual: crate::Data,
+    ) -> Result<()> {
+        match self.action {
+            Action::Skip => {
+                return Ok(());
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        let (actual, _expected) = self.normalize(actual, crate::Data::new());
+        let rendered = actual.render().unwrap_or_else(|| actual.to_string());
+        if rendered.is_empty() {
+            return Ok(());
+        }
+        let err = Error::new(format_args!(
+            "Expected {} {actual_name}, was\n```\n{}\n```",
+            self.palette.info("empty"),
+            self.palette.error(rendered)
+        ));
+        match self.action {
+            Action::Ignore => {
+                use std::io::Write;
+
+                let _ = writeln!(
+                    stderr(),
+                    "{}: {}",
+                    self.palette.warn("Ignoring failure"),
+                    err
+                );
+                Ok(())
+            }
+            _ => Err(err),
+        }
+    }
+
+    /// The number of differing lines and the [`Assert::max_diff_lines`] allowed, if it applies
+    ///
+    /// The tolerance is only for verifying so overwriting still updates the snapshot.
+    #[cfg(feature = "diff")]
+    fn tolerated_diff_lines(
+        &self,
+        actual: &crate::Data,
+        expected: &crate::Data,
+    ) -> Option<(usize, usize)> {
+        let max_diff_lines = self.max_diff_lines?;
+        if self.action != Action::Verify || actual == expected {
+            return None;
+        }
+        let (expected, actual) = (expected.render()?, actual.render()?);
+        let diff_lines = crate::report::count_diff_lines(&expected, &actual);
+        Some((diff_lines, max_diff_lines))
+    }
+
+    #[cfg(not(feature = "diff"))]
+    fn tolerated_diff_lines(
+        &self,
+        _actual: &crate::Data,
+        _expected: &crate::Data,
+    ) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Check a value against an expected value, reporting the outcome rather than acting on it
+    ///
+    /// `actual` is normalized as with [`Assert::eq`] but the [`Action`] is not applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let comparison = Assert::new()
+    ///     .label("greeting")
+    ///     .compare("Hello world", "Hello [..]");
+    /// assert!(comparison.is_match());
+    /// assert_eq!(comparison.label(), Some("greeting"));
+    /// ```
+    #[track_caller]
+    pub fn compare(&self, actual: impl IntoData, expected: impl IntoData) -> Comparison {
+        let expected = expected.into_data();
+        let actual = actual.into_data();
+        let location = match expected.source() {
+            Some(source) => source.to_string(),
+            None => std::panic::Location::caller().to_string(),
+        };
+
+        self.record_usage(&expected);
+        let (actual, expected) = self.prepare(actual, expected);
+        let bindings = if expected.filters.is_redaction_set() {
+            match (actual.render(), expected.render()) {
+                (Some(actual), Some(expected)) => crate::filter::capture_str_to_redactions(
+                    &actual,
+                    &expected,
+                    &self.substitutions,
+                ),
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+        let actual = self.normalize_to_expected(actual, &expected);
+        let is_match = actual == expected;
+        #[cfg(feature = "diff")]
+        let rendered = expected.render().zip(actual.render());
+        Comparison {
+            label: self.label.clone(),
+            location,
+            actual,
+            expected,
+            is_match,
+            bindings,
+            #[cfg(feature = "diff")]
+            rendered,
+        }
+    }
+
+    /// Check if a value is equivalent to an expected value, according to `is_eq`
+    ///
+    /// `actual` is normalized against `expected` as with [`Assert::eq`] and `is_eq` is called
+    /// with `(actual, expected)` to decide whether they match.
+    /// On failure, the standard diff is reported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let actual = "HELLO WORLD";
+    /// let expected = "hello world";
+    /// Assert::new().eq_with(actual, expected, |actual, expected| {
+    ///     actual.to_string().to_lowercase() == expected.to_string().to_lowercase()
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn eq_with(
+        &self,
+        actual: impl IntoData,
+        expected: impl IntoData,
+        is_eq: impl Fn(&crate::Data, &crate::Data) -> bool,
+    ) {
+        let expected = expected.into_data();
+        let actual = actual.into_data();
+        if let Err(err) = self.try_eq_with(Some(&"In-memory"), actual, expected, &is_eq) {
+            err.panic();
+        }
+    }
+
+    pub fn try_eq_with(
+        &self,
+        actual_name: Option<&dyn std::fmt::Display>,
+        actual: crate::Data,
+        expected: crate::Data,
+        is_eq: &dyn Fn(&crate::Data, &crate::Data) -> bool,
+    ) -> Result<()> {
+        if expected.source().is_none() && actual.source().is_some() {
+            panic!("received `(actual, expected)`, expected `(expected, actual)`");
+        }
+        match self.action {
+            Action::Skip => {
+                return Ok(());
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        self.record_usage(&expected);
         let (actual, expected) = self.normalize(actual, expected);
 
-        self.do_action(actual_name, actual, expected)
+        self.do_action(actual_name, actual, expected, is_eq)
+    }
+
+    /// Check that `expected` appears somewhere in `actual`
+    ///
+    /// This is for checking one message within a large log without matching the rest of it.
+    /// `expected` is matched against a run of whole lines in `actual`, so use `[..]` to match
+    /// part of a line.  The same [`filters`][crate::filter] apply as for [`Assert::eq`],
+    /// including redactions.
+    ///
+    /// As `actual` isn't a snapshot of `expected`, failures are never overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let log = "\
+    /// Compiling foo v0.1.0
+    /// warning: unused variable: `x`
+    /// Finished in 1.2s
+    /// ";
+    /// Assert::new().contains(log, "warning: unused variable: [..]\n");
+    /// ```
+    #[track_caller]
+    pub fn contains(&self, actual: impl IntoData, expected: impl IntoData) {
+        let expected = expected.into_data();
+        let actual = actual.into_data();
+        if let Err(err) = self.try_contains(Some(&"In-memory"), actual, expected) {
+            err.panic();
+        }
+    }
+
+    /// Check that `expected` appears somewhere in `actual`, see [`Assert::contains`]
+    pub fn try_contains(
+        &self,
+        actual_name: Option<&dyn std::fmt::Display>,
+        actual: crate::Data,
+        expected: crate::Data,
+    ) -> Result<()> {
+        if expected.source().is_none() && actual.source().is_some() {
+            panic!("received `(actual, expected)`, expected `(expected, actual)`");
+        }
+        match self.action {
+            Action::Skip => {
+                return Ok(());
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        self.record_usage(&expected);
+        let (actual, expected) = self.prepare(actual, expected);
+        let (Some(mut actual), Some(expected_text)) = (actual.render(), expected.render()) else {
+            return Err(Error::new(format_args!(
+                "{} is not text",
+                actual_name.unwrap_or(&"actual")
+            )));
+        };
+
+        let found = if expected.filters.is_redaction_set() {
+            if !actual.ends_with('\n') {
+                actual.push('\n');
+            }
+            let pattern_with = |text: &str| {
+                let terminator = if text.is_empty() || text.ends_with('\n') {
+                    ""
+                } else {
+                    "\n"
+                };
+                let mut pattern = crate::Data::text(format!("{text}{terminator}...\n"));
+                pattern.filters = expected.filters;
+                pattern
+            };
+            let matches = |actual: &str, pattern: &crate::Data| {
+                let normalized = self.normalize_to_expected(crate::Data::text(actual), pattern);
+                normalized.render() == pattern.render()
+            };
+            // Try starting at each line as an earlier, partial match doesn't rule out a later one.
+            // Lines that match one line each are checked first, looking at only as many lines, so
+            // the rest of `actual` is only matched against from a few starts.
+            let (bounded, bounded_lines, rest) = crate::filter::split_bounded_lines(&expected_text);
+            let leading = pattern_with(bounded);
+            let pattern = pattern_with(&expected_text);
+            let starts: Vec<_> = std::iter::once(0)
+                .chain(actual.match_indices('\n').map(|(index, _)| index + 1))
+                .filter(|start| *start < actual.len())
+                .collect();
+            // When starting with `...`, later starts only see less of `actual`
+            let elided = bounded_lines == 0
+                && rest
+                    .and_then(|rest| rest.lines().next())
+                    .map(crate::filter::is_line_elide)
+                    .unwrap_or(false);
+            let starts = if elided { &starts[..1] } else { &starts[..] };
+            starts.iter().enumerate().any(|(index, start)| {
+                let end = starts
+                    .get(index + bounded_lines)
+                    .copied()
+                    .unwrap_or(actual.len());
+                if !matches(&actual[*start..end], &leading) {
+                    return false;
+                }
+                rest.is_none() || matches(&actual[*start..], &pattern)
+            })
+        } else {
+            actual.contains(&expected_text)
+        };
+        if found {
+            return Ok(());
+        }
+
+        let err = Error::new(format_args!(
+            "{} does not contain:\n{}",
+            actual_name.unwrap_or(&"actual"),
+            self.palette.error(expected_text.trim_end())
+        ));
+        match self.action {
+            Action::Skip => unreachable!("Bailed out earlier"),
+            Action::Ignore => {
+                use std::io::Write;
+
+                let _ = writeln!(
+                    stderr(),
+                    "{}: {}",
+                    self.palette.warn("Ignoring failure"),
+                    err
+                );
+                Ok(())
+            }
+            // Nothing to overwrite
+            Action::Verify | Action::Overwrite => Err(err),
+        }
+    }
+
+    /// Check that the lines of a value are sorted
+    ///
+    /// Lines are compared lexicographically.  See [`Assert::is_sorted_by`] for a custom order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let actual = "apple\nbanana\ncherry\n";
+    /// Assert::new().is_sorted(actual);
+    /// ```
+    #[track_caller]
+    pub fn is_sorted(&self, actual: impl IntoData) {
+        self.is_sorted_by(actual, |left, right| left.cmp(right));
+    }
+
+    /// Check that the lines of a value are sorted according to `compare`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::Assert;
+    /// let actual = "2\n10\n100\n";
+    /// Assert::new().is_sorted_by(actual, |left, right| {
+    ///     let left = left.parse::<usize>().unwrap();
+    ///     let right = right.parse::<usize>().unwrap();
+    ///     left.cmp(&right)
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn is_sorted_by(
+        &self,
+        actual: impl IntoData,
+        compare: impl Fn(&str, &str) -> std::cmp::Ordering,
+    ) {
+        let actual = actual.into_data();
+        if let Err(err) = self.try_is_sorted_by(Some(&"In-memory"), actual, &compare) {
+            err.panic();
+        }
+    }
+
+    /// Check that the lines of `actual` are sorted according to `compare`, see [`Assert::is_sorted_by`]
+    pub fn try_is_sorted_by(
+        &self,
+        actual_name: Option<&dyn std::fmt::Display>,
+        actual: crate::Data,
+        compare: &dyn Fn(&str, &str) -> std::cmp::Ordering,
+    ) -> Result<()> {
+        match self.action {
+            Action::Skip => {
+                return Ok(());
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        let actual = FilterNewlines.filter(actual.coerce_to(crate::data::DataFormat::Text));
+        let Some(actual) = actual.render() else {
+            return Err(Error::new(format_args!(
+                "{} is not text",
+                actual_name.unwrap_or(&"actual")
+            )));
+        };
+        let lines: Vec<_> = actual.lines().collect();
+        let Some(index) = lines
+            .windows(2)
+            .position(|pair| compare(pair[0], pair[1]) == std::cmp::Ordering::Greater)
+        else {
+            return Ok(());
+        };
+
+        let err = Error::new(format_args!(
+            "{}: lines {} and {} are out of order\n{:>4} | {}\n{:>4} | {}",
+            actual_name.unwrap_or(&"actual"),
+            index + 1,
+            index + 2,
+            index + 1,
+            self.palette.error(lines[index]),
+            index + 2,
+            self.palette.error(lines[index + 1]),
+        ));
+        match self.action {
+            Action::Skip => unreachable!("Bailed out earlier"),
+            Action::Ignore => {
+                use std::io::Write;
+
+                let _ = writeln!(
+                    stderr(),
+                    "{}: {}",
+                    self.palette.warn("Ignoring failure"),
+                    err
+                );
+                Ok(())
+            }
+            // Nothing to overwrite
+            Action::Verify | Action::Overwrite => Err(err),
+        }
+    }
+
+    /// Check that `produce` gives the same output each of `runs` times
+    ///
+    /// This is for hunting flaky tests.  Each run is normalized, including [redactions][Assert::redact_with],
+    /// and compared to the first run.  The first run that differs is reported with a diff.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new().is_deterministic(10, || "Hello world!");
+    /// ```
+    #[track_caller]
+    pub fn is_deterministic<D: IntoData>(&self, runs: usize, mut produce: impl FnMut() -> D) {
+        if let Err(err) = self.try_is_deterministic(runs, &mut || produce().into_data()) {
+            err.panic();
+        }
+    }
+
+    pub fn try_is_deterministic(
+        &self,
+        runs: usize,
+        produce: &mut dyn FnMut() -> crate::Data,
+    ) -> Result<()> {
+        match self.action {
+            Action::Skip => {
+                return Ok(());
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        let Some(first) = (runs > 0).then(&mut *produce) else {
+            return Ok(());
+        };
+        // Run 1 stands in for `expected`, with redactions applied so they act as placeholders
+        let (first, _) = self.prepare(first.clone(), first);
+        let first = crate::filter::NormalizeRedactions {
+            redactions: &self.substitutions,
+            case_insensitive: self.case_insensitive,
+        }
+        .filter(first);
+        let Some((index, run, first)) = (2..=runs)
+            .map(|index| {
+                let (run, first) = self.normalize(produce(), first.clone());
+                (index, run, first)
+            })
+            .find(|(_, run, first)| run != first)
+        else {
+            return Ok(());
+        };
+
+        let mut buf = format!("run {index} of {runs} differs from run 1\n");
+        crate::report::write_diff(
+            &mut buf,
+            &first,
+            &run,
+            Some(&"run 1"),
+            Some(&format_args!("run {index}")),
+            self.palette,
+        )
+        .map_err(|e| e.to_string())?;
+        let err = Error::from(buf);
+        match self.action {
+            Action::Skip => unreachable!("Bailed out earlier"),
+            Action::Ignore => {
+                use std::io::Write;
+
+                let _ = writeln!(
+                    stderr(),
+                    "{}: {}",
+                    self.palette.warn("Ignoring failure"),
+                    err
+                );
+                Ok(())
+            }
+            // Nothing to overwrite
+            Action::Verify | Action::Overwrite => Err(err),
+        }
     }
 
     pub fn normalize(
+        &self,
+        actual: crate::Data,
+        expected: crate::Data,
+    ) -> (crate::Data, crate::Data) {
+        let (actual, expected) = self.prepare(actual, expected);
+        let actual = self.normalize_to_expected(actual, &expected);
+        (actual, expected)
+    }
+
+    /// Normalization that doesn't depend on the content of `expected`
+    fn prepare(
         &self,
         mut actual: crate::Data,
         mut expected: crate::Data,
@@ -117,17 +761,50 @@ impl Assert {
             expected = FilterNewlines.filter(expected);
         }
 
+        actual = self.preprocessors.apply(expected.against_format(), actual);
+
         // On `expected` being an error, make a best guess
         actual = actual.coerce_to(expected.against_format());
         actual = actual.coerce_to(expected.intended_format());
 
-        if self.normalize_paths && expected.filters.is_paths_set() {
-            actual = FilterPaths.filter(actual);
+        if expected.filters.is_paths_set() {
+            actual = self.path_separators.filter(actual);
         }
         if expected.filters.is_newlines_set() {
             actual = FilterNewlines.filter(actual);
         }
+        expected = self.normalizers.apply(expected);
+        actual = self.normalizers.apply(actual);
+        actual = self.filters.apply(actual);
+
+        (actual, expected)
+    }
+
+    fn check_patterns(&self, expected: &crate::Data) -> Result<()> {
+        use std::fmt::Write as _;
 
+        if !expected.filters.is_redaction_set() {
+            return Ok(());
+        }
+        let Some(pattern) = expected.render() else {
+            return Ok(());
+        };
+        let Err(errors) = crate::filter::lint_pattern(&pattern, &self.substitutions) else {
+            return Ok(());
+        };
+        let mut message = String::new();
+        if let Some(source) = expected.source() {
+            let _ = writeln!(&mut message, "Malformed patterns in {source}:");
+        } else {
+            let _ = writeln!(&mut message, "Malformed patterns in expected:");
+        }
+        for error in errors {
+            let _ = writeln!(&mut message, "  {error}");
+        }
+        Err(Error::new(message))
+    }
+
+    fn normalize_to_expected(&self, actual: crate::Data, expected: &crate::Data) -> crate::Data {
         let mut normalize = NormalizeToExpected::new();
         if expected.filters.is_redaction_set() {
             normalize = normalize.redact_with(&self.substitutions);
@@ -135,9 +812,87 @@ impl Assert {
         if expected.filters.is_unordered_set() {
             normalize = normalize.unordered();
         }
-        actual = normalize.normalize(actual, &expected);
+        #[cfg(feature = "json")]
+        for pointer in &self.unordered_at {
+            normalize = normalize.unordered_at(pointer);
+        }
+        #[cfg(feature = "json")]
+        if self.whole_numbers {
+            normalize = normalize.equate_whole_numbers();
+        }
+        if self.trim_elide_anchors {
+            normalize = normalize.trim_elide_anchors();
+        }
+        if self.case_insensitive {
+            normalize = normalize.case_insensitive();
+        }
+        normalize.normalize(actual, expected)
+    }
+
+    /// Describe what each redaction placeholder matched in `actual`
+    fn redaction_report(&self, actual: &crate::Data, expected: &crate::Data) -> String {
+        use std::fmt::Write as _;
+
+        if !expected.filters.is_redaction_set() {
+            return String::new();
+        }
+        let (Some(actual), Some(expected)) = (actual.render(), expected.render()) else {
+            return String::new();
+        };
+        let mut matches =
+            crate::filter::capture_str_to_redactions(&actual, &expected, &self.substitutions)
+                .into_iter()
+                .map(|(placeholder, matched)| (placeholder, Some(matched)))
+                .collect::<Vec<_>>();
+        for (placeholder, matched) in self.substitutions.first_matches(&actual) {
+            if matches.iter().all(|(existing, _)| *existing != placeholder) {
+                matches.push((placeholder, matched));
+            }
+        }
+        if matches.is_empty() {
+            return String::new();
+        }
+        matches.sort_by_key(|(placeholder, _)| *placeholder);
+
+        let mut report = String::new();
+        let _ = writeln!(&mut report, "Redactions:");
+        for (placeholder, matched) in matches {
+            match matched {
+                Some(_) if self.substitutions.is_secret(placeholder) => {
+                    let _ = writeln!(
+                        &mut report,
+                        "  {}: {}",
+                        self.palette.info(placeholder),
+                        self.palette.hint("<hidden>")
+                    );
+                }
+                Some(matched) => {
+                    let _ = writeln!(
+                        &mut report,
+                        "  {}: {matched:?}",
+                        self.palette.info(placeholder)
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        &mut report,
+                        "  {}: {}",
+                        self.palette.info(placeholder),
+                        self.palette.hint("no match")
+                    );
+                }
+            }
+        }
+        report
+    }
 
-        (actual, expected)
+    fn record_usage(&self, expected: &crate::Data) {
+        if let (Some(usage), Some(path)) = (
+            self.usage.as_ref(),
+            expected.source().and_then(|source| source.as_path()),
+        ) {
+            usage.record(path);
+        }
     }
 
     fn do_action(
@@ -145,11 +900,17 @@ impl Assert {
         actual_name: Option<&dyn std::fmt::Display>,
         actual: crate::Data,
         expected: crate::Data,
+        is_eq: &dyn Fn(&crate::Data, &crate::Data) -> bool,
     ) -> Result<()> {
-        let result = self.try_verify(actual_name, &actual, &expected);
-        let Err(err) = result else {
+        let result = self.try_verify(actual_name, &actual, &expected, is_eq);
+        let Err(mut err) = result else {
             return Ok(());
         };
+        if let Some(path) = self.dump_actual.as_deref() {
+            if let Err(message) = actual.write_to_path(path) {
+                err = Error::new(format_args!("{err}Dump failed: {message}"));
+            }
+        }
         match self.action {
             Action::Skip => unreachable!("Bailed out earlier"),
             Action::Ignore => {
@@ -181,7 +942,17 @@ impl Assert {
                     if let Err(message) = actual.write_to(source) {
                         Err(Error::new(format_args!("{err}Update failed: {message}")))
                     } else {
-                        let _ = writeln!(stderr(), "{}: {}", self.palette.warn("Fixing"), err);
+                        if let Some(label) = self.label.as_deref() {
+                            let _ = writeln!(
+                                stderr(),
+                                "{} {}: {}",
+                                self.palette.warn("Fixing"),
+                                self.palette.info(label),
+                                err
+                            );
+                        } else {
+                            let _ = writeln!(stderr(), "{}: {}", self.palette.warn("Fixing"), err);
+                        }
                         Ok(())
                     }
                 } else {
@@ -196,8 +967,9 @@ impl Assert {
         actual_name: Option<&dyn std::fmt::Display>,
         actual: &crate::Data,
         expected: &crate::Data,
+        is_eq: &dyn Fn(&crate::Data, &crate::Data) -> bool,
     ) -> Result<()> {
-        if actual != expected {
+        if !is_eq(actual, expected) {
             let mut buf = String::new();
             crate::report::write_diff(
                 &mut buf,
@@ -238,8 +1010,14 @@ impl Assert {
             Action::Ignore | Action::Verify | Action::Overwrite => {}
         }
 
-        let checks: Vec<_> =
-            crate::dir::PathDiff::subset_eq_iter_inner(expected_root, actual_root).collect();
+        self.warn_unsupported_check_mode();
+        let checks: Vec<_> = crate::dir::PathDiff::subset_eq_iter_inner(
+            expected_root,
+            actual_root,
+            self.check_mode,
+            self.sizes_first,
+        )
+        .collect();
         self.verify(checks);
     }
 
@@ -267,16 +1045,91 @@ impl Assert {
             Action::Ignore | Action::Verify | Action::Overwrite => {}
         }
 
+        self.warn_unsupported_check_mode();
         let checks: Vec<_> = crate::dir::PathDiff::subset_matches_iter_inner(
             expected_root,
             actual_root,
             &self.substitutions,
-            self.normalize_paths,
+            self.path_separators,
+            self.check_mode,
+        )
+        .collect();
+        self.verify(checks);
+    }
+
+    /// Check `actual_root` against the contents of the `expected_archive` tarball
+    ///
+    /// Note: Requires feature flag `tar`
+    #[cfg(feature = "tar")]
+    #[track_caller]
+    pub fn subset_eq_tar(
+        &self,
+        expected_archive: impl Into<std::path::PathBuf>,
+        actual_root: impl Into<std::path::PathBuf>,
+    ) {
+        match self.action {
+            Action::Skip => {
+                return;
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        self.warn_unsupported_check_mode();
+        let checks: Vec<_> = crate::dir::PathDiff::subset_tar_iter_inner(
+            expected_archive.into(),
+            actual_root.into(),
+            None,
+            crate::filter::PathSeparators::Never,
+            self.check_mode,
+            self.sizes_first,
+        )
+        .collect();
+        self.verify(checks);
+    }
+
+    /// Check `actual_root` against the patterns in the `pattern_archive` tarball
+    ///
+    /// Note: Requires feature flag `tar`
+    #[cfg(feature = "tar")]
+    #[track_caller]
+    pub fn subset_matches_tar(
+        &self,
+        pattern_archive: impl Into<std::path::PathBuf>,
+        actual_root: impl Into<std::path::PathBuf>,
+    ) {
+        match self.action {
+            Action::Skip => {
+                return;
+            }
+            Action::Ignore | Action::Verify | Action::Overwrite => {}
+        }
+
+        self.warn_unsupported_check_mode();
+        let checks: Vec<_> = crate::dir::PathDiff::subset_tar_iter_inner(
+            pattern_archive.into(),
+            actual_root.into(),
+            Some(&self.substitutions),
+            self.path_separators,
+            self.check_mode,
+            false,
         )
         .collect();
         self.verify(checks);
     }
 
+    fn warn_unsupported_check_mode(&self) {
+        #[cfg(not(unix))]
+        if self.check_mode {
+            use std::io::Write;
+
+            let _ = writeln!(
+                stderr(),
+                "{}: permission bits are only checked on Unix",
+                self.palette.warn("Ignoring `check_mode`")
+            );
+        }
+    }
+
     #[track_caller]
     fn verify(
         &self,
@@ -407,17 +1260,485 @@ impl Assert {
         self
     }
 
+    /// Add the standard redactions for directories, like `[ROOT]` and `[HOME]`
+    ///
+    /// See [`Redactions::insert_dirs_preset`][crate::Redactions::insert_dirs_preset].  `[EXE]` is
+    /// already included by default.
+    ///
+    /// ```rust
+    /// let path = std::env::temp_dir().join("output.txt");
+    /// snapbox::Assert::new()
+    ///     .with_standard_redactions()
+    ///     .eq(path.display().to_string(), "[TEMP]/output.txt");
+    /// ```
+    pub fn with_standard_redactions(mut self) -> Self {
+        self.substitutions
+            .insert_dirs_preset()
+            .expect("directory placeholders are valid");
+        self
+    }
+
+    /// Layer `extra` [`Redactions`][crate::Redactions] on top of the current ones
+    ///
+    /// Unlike [`Assert::redact_with`], the existing redactions are kept.  This is for
+    /// test-local placeholders:
+    ///
+    /// ```rust
+    /// let mut extra = snapbox::Redactions::new();
+    /// extra.insert("[NAME]", "alice").unwrap();
+    /// snapbox::Assert::new()
+    ///     .redact_also(extra)
+    ///     .eq("Hello alice", "Hello [NAME]");
+    /// ```
+    pub fn redact_also(mut self, extra: crate::Redactions) -> Self {
+        self.substitutions.merge(extra);
+        self
+    }
+
+    /// On failure, report what each redaction placeholder matched in `actual`
+    ///
+    /// This is for debugging why a placeholder didn't redact something, or redacted too much.
+    /// Placeholders in `expected` report the text they matched; others report the first text they
+    /// would redact in `actual`.  Values from [`Redactions::insert_env`][crate::Redactions::insert_env]
+    /// are reported as `<hidden>`.
+    ///
+    /// ```text
+    /// Redactions:
+    ///   [ROOT]: "/home/ci/project"
+    ///   [TMP]: no match
+    /// ```
+    pub fn report_redactions(mut self, yes: bool) -> Self {
+        self.report_redactions = yes;
+        self
+    }
+
+    /// Fail when `expected` has malformed patterns, like an unknown placeholder
+    ///
+    /// Malformed patterns match literally, so a typo like `[EXEC]` otherwise shows up as a
+    /// mismatch.  See [`lint_pattern`][crate::filter::lint_pattern] for what is reported.
+    ///
+    /// ```rust
+    /// let assert = snapbox::Assert::new().lint_patterns(true);
+    /// let result = assert.try_eq(None, "Hello world".into(), "Hello [NAME]".into());
+    /// assert!(result.is_err());
+    /// ```
+    pub fn lint_patterns(mut self, yes: bool) -> Self {
+        self.lint_patterns = yes;
+        self
+    }
+
     /// Override the default [`Redactions`][crate::Redactions]
     #[deprecated(since = "0.6.2", note = "Replaced with `Assert::redact_with`")]
     pub fn substitutions(self, substitutions: crate::Redactions) -> Self {
         self.redact_with(substitutions)
     }
 
+    /// Identify this assertion to snapshot management tooling
+    ///
+    /// The label is included in [`Comparison`]s and when updating snapshots.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Tolerate comments and trailing commas when parsing `actual` as json
+    ///
+    /// This is opt-in as it accepts output that is not valid json.
+    /// See [`FilterLenientJson`][crate::filter::FilterLenientJson] for what is accepted.
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn lenient_json(self) -> Self {
+        self.preprocess(
+            crate::data::DataFormat::Json,
+            crate::filter::FilterLenientJson,
+        )
+        .preprocess(
+            crate::data::DataFormat::JsonLines,
+            crate::filter::FilterLenientJson,
+        )
+    }
+
+    /// Compare the json arrays at `pointer` without regard to order
+    ///
+    /// `pointer` is a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) where a `*` segment
+    /// matches any key or index.  Arrays elsewhere are still compared positionally.
+    /// See [`NormalizeToExpected::unordered_at`][crate::filter::NormalizeToExpected::unordered_at].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::prelude::*;
+    /// let actual = serde_json::json!({"tags": ["b", "a"], "steps": [1, 2]});
+    /// let expected = serde_json::json!({"tags": ["a", "b"], "steps": [1, 2]});
+    /// snapbox::Assert::new()
+    ///     .unordered_at("/tags")
+    ///     .eq(actual.into_json(), expected.into_json());
+    /// ```
+    ///
+    /// Panics if `pointer` isn't empty and doesn't start with `/`.
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn unordered_at(mut self, pointer: impl Into<String>) -> Self {
+        let pointer = pointer.into();
+        crate::filter::JsonPointer::parse_or_panic(&pointer);
+        self.unordered_at.push(pointer);
+        self
+    }
+
+    /// Treat json integers and whole-valued floats, like `1` and `1.0`, as equal
+    ///
+    /// The default is to compare them exactly so a change in how a value is computed isn't hidden.
+    /// See [`NormalizeToExpected::equate_whole_numbers`][crate::filter::NormalizeToExpected::equate_whole_numbers].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use snapbox::prelude::*;
+    /// let actual = serde_json::json!({"version": 1.0});
+    /// let expected = serde_json::json!({"version": 1});
+    /// snapbox::Assert::new()
+    ///     .equate_whole_numbers(true)
+    ///     .eq(actual.into_json(), expected.into_json());
+    /// ```
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn equate_whole_numbers(mut self, yes: bool) -> Self {
+        self.whole_numbers = yes;
+        self
+    }
+
+    /// Ignore trailing whitespace on the line after a `...` when finding where the elide ends
+    ///
+    /// See [`NormalizeToExpected::trim_elide_anchors`][crate::filter::NormalizeToExpected::trim_elide_anchors].
+    /// This has no effect on [unordered][crate::Data::unordered] data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let actual = "header\nskipped\nfooter   \n";
+    /// snapbox::Assert::new()
+    ///     .trim_elide_anchors(true)
+    ///     .eq(actual, snapbox::str![[r#"
+    /// header
+    /// ...
+    /// footer
+    ///
+    /// "#]]);
+    /// ```
+    pub fn trim_elide_anchors(mut self, yes: bool) -> Self {
+        self.trim_elide_anchors = yes;
+        self
+    }
+
+    /// Compare lines ignoring ASCII case
+    ///
+    /// See [`NormalizeToExpected::case_insensitive`][crate::filter::NormalizeToExpected::case_insensitive].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let actual = "Compiling foo (c:\\Users\\Me\\foo)\n";
+    /// snapbox::Assert::new()
+    ///     .case_insensitive(true)
+    ///     .eq(actual, snapbox::str![[r#"
+    /// Compiling foo (C:/users/[..]/foo)
+    ///
+    /// "#]]);
+    /// ```
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Ignore differences in the quotes around tokens, like `'x'` vs `"x"`
+    ///
+    /// This applies to both `actual` and `expected`.
+    /// See [`FilterQuotes`][crate::filter::FilterQuotes] for which quotes are rewritten.
+    /// Pass `None` to compare quotes as-is again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .normalize_quotes(Some(snapbox::filter::FilterQuotes::new()))
+    ///     .eq("say 'hi'", "say \"hi\"");
+    /// ```
+    pub fn normalize_quotes(mut self, quotes: Option<crate::filter::FilterQuotes>) -> Self {
+        if let Some(quotes) = quotes {
+            self.normalizers.set(quotes);
+        } else {
+            self.normalizers.remove::<crate::filter::FilterQuotes>();
+        }
+        self
+    }
+
+    /// Ignore differences in the amount of spaces and tabs, like from column-aligned tables
+    ///
+    /// This applies to both `actual` and `expected`.
+    /// See [`FilterWhitespace`][crate::filter::FilterWhitespace].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .collapse_whitespace(true)
+    ///     .eq("name     size\nfoo.txt  10\n", "name size\nfoo.txt 10\n");
+    /// ```
+    pub fn collapse_whitespace(mut self, yes: bool) -> Self {
+        if yes {
+            self.normalizers.set(crate::filter::FilterWhitespace);
+        } else {
+            self.normalizers.remove::<crate::filter::FilterWhitespace>();
+        }
+        self
+    }
+
+    /// Ignore spaces and tabs at the end of lines, like padding that editors strip from snapshots
+    ///
+    /// This applies to both `actual` and `expected` when comparing; snapshot files are only
+    /// rewritten on a mismatch, so trailing whitespace in a matching snapshot is left as-is.
+    /// See [`FilterTrailingWhitespace`][crate::filter::FilterTrailingWhitespace].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .ignore_trailing_whitespace(true)
+    ///     .eq("name  size  \nfoo   10    \n", "name  size\nfoo   10\n");
+    /// ```
+    pub fn ignore_trailing_whitespace(mut self, yes: bool) -> Self {
+        if yes {
+            self.normalizers
+                .set(crate::filter::FilterTrailingWhitespace);
+        } else {
+            self.normalizers
+                .remove::<crate::filter::FilterTrailingWhitespace>();
+        }
+        self
+    }
+
+    /// Ignore the order of lines, like from hash map iteration or parallel jobs
+    ///
+    /// This applies to both `actual` and `expected`.
+    /// See [`SortLines`][crate::filter::SortLines].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .sort_lines(true)
+    ///     .eq("job 2 done\njob 1 done\n", "job 1 done\njob 2 done\n");
+    /// ```
+    pub fn sort_lines(mut self, yes: bool) -> Self {
+        if yes {
+            self.normalizers.set(crate::filter::SortLines);
+        } else {
+            self.normalizers.remove::<crate::filter::SortLines>();
+        }
+        self
+    }
+
+    /// Truncate long lines, like minified JSON, before comparing and rendering diffs
+    ///
+    /// This applies to both `actual` and `expected`.
+    /// See [`TruncateLines`][crate::filter::TruncateLines].
+    /// Pass `None` to compare whole lines again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .truncate_lines(Some(snapbox::filter::TruncateLines::new(10)))
+    ///     .eq("token: aGVsbG8gd29ybGQ=\n", "token: aGV[TRUNC]\n");
+    /// ```
+    pub fn truncate_lines(mut self, truncate: Option<crate::filter::TruncateLines>) -> Self {
+        if let Some(truncate) = truncate {
+            self.normalizers.set(truncate);
+        } else {
+            self.normalizers.remove::<crate::filter::TruncateLines>();
+        }
+        self
+    }
+
+    /// Ignore differences in how Unicode text is encoded, like from macOS file names
+    ///
+    /// This applies to both `actual` and `expected`.
+    /// See [`NormalizeUnicode`][crate::filter::NormalizeUnicode].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .normalize_unicode(snapbox::filter::NormalizeUnicode::Nfc)
+    ///     .eq("cafe\u{301}.txt", "caf\u{e9}.txt");
+    /// ```
+    ///
+    /// Note: Requires feature flag `unicode-normalization`
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize_unicode(mut self, form: crate::filter::NormalizeUnicode) -> Self {
+        self.normalizers.set(form);
+        self
+    }
+
+    /// Write the normalized `actual` to `path` on a mismatch
+    ///
+    /// This is for collecting CI artifacts to diff against the golden with external tools.
+    /// Redactions are applied, so the file is what the golden would be updated to.
+    /// This is independent of the [`Action`]; snapshots are still updated on
+    /// [`Action::Overwrite`].
+    pub fn dump_actual_to(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.dump_actual = Some(path.into());
+        self
+    }
+
+    /// Where [`Assert::eq_generated`] caches `expected`
+    ///
+    /// The default is `snapbox-generated` in the target directory.
+    pub fn generated_cache_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.generated_dir = Some(dir.into());
+        self
+    }
+
+    /// Tolerate up to `max` differing lines
+    ///
+    /// Lines are compared after normalization, so lines matched by `[..]` or `...` don't count.
+    /// A changed line counts once and a `...` that didn't match counts as one line.
+    /// When more lines differ, the count is reported along with the diff.
+    ///
+    /// This only applies to [`Assert::eq`] with [`Action::Verify`], so [`Action::Overwrite`] still
+    /// updates snapshots and custom comparisons, like [`Assert::eq_with`], are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .max_diff_lines(1)
+    ///     .eq("one\ntwo\nthree\n", "one\n2\nthree\n");
+    /// ```
+    ///
+    /// Note: Requires feature flag `diff`
+    #[cfg(feature = "diff")]
+    pub fn max_diff_lines(mut self, max: usize) -> Self {
+        self.max_diff_lines = Some(max);
+        self
+    }
+
+    /// Record which golden files this compares against
+    ///
+    /// This is opt-in so tooling can find goldens that were never exercised by a run.
+    pub fn track_usage(mut self, usage: &GoldenUsage) -> Self {
+        self.usage = Some(usage.clone());
+        self
+    }
+
     /// Specify whether text should have path separators normalized
     ///
     /// The default is normalized
     pub fn normalize_paths(mut self, yes: bool) -> Self {
-        self.normalize_paths = yes;
+        self.path_separators = if yes {
+            crate::filter::PathSeparators::Everywhere
+        } else {
+            crate::filter::PathSeparators::Never
+        };
+        self
+    }
+
+    /// Specify where path separators are normalized, see [`PathSeparators`][crate::filter::PathSeparators]
+    ///
+    /// The default is [everywhere][crate::filter::PathSeparators::Everywhere].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .path_separators(snapbox::filter::PathSeparators::InQuotes)
+    ///     .eq(
+    ///         r#"\\server\share "C:\\Users\\me""#,
+    ///         r#"\\server\share "C:/Users/me""#,
+    ///     );
+    /// ```
+    pub fn path_separators(mut self, separators: crate::filter::PathSeparators) -> Self {
+        self.path_separators = separators;
+        self
+    }
+
+    /// Specify whether [directory assertions][Assert::subset_eq] compare Unix executable bits
+    ///
+    /// The default is to ignore them.  Only the executable bits are compared as those are all
+    /// that git tracks; the rest depend on the umask of whoever checked out the files.  A mismatch
+    /// is reported as a [`PathDiff::Failure`][crate::dir::PathDiff::Failure], so it isn't
+    /// overwritten.
+    ///
+    /// Other platforms don't have permission bits, so this is ignored with a warning.
+    pub fn check_mode(mut self, yes: bool) -> Self {
+        self.check_mode = yes;
+        self
+    }
+
+    /// Specify whether [`Assert::subset_eq`] compares file sizes before content
+    ///
+    /// This speeds up checking large trees of mostly unchanged files: files of the same size are
+    /// compared byte-for-byte and, when identical, skip parsing and normalization.  Everything
+    /// else gets the full comparison, so files that only differ in newlines, like `\r\n` vs
+    /// `\n`, still match.  The default is to always do the full comparison.
+    ///
+    /// This is ignored by [`Assert::subset_matches`].
+    pub fn compare_sizes_first(mut self, yes: bool) -> Self {
+        self.sizes_first = yes;
+        self
+    }
+
+    /// Transform `actual` before it is parsed as `format`
+    ///
+    /// When `expected` is `format`, `filter` is applied to `actual` before anything else,
+    /// including parsing `actual` as `format`, path and newline normalization, and redactions.
+    /// Multiple filters for the same format are applied in the order they were added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "json")] {
+    /// # use snapbox::prelude::*;
+    /// # use snapbox::str;
+    /// let actual = "{\"name\": \"snapbox\" // the crate\n}";
+    /// snapbox::Assert::new()
+    ///     .preprocess(
+    ///         snapbox::data::DataFormat::Json,
+    ///         snapbox::filter::FilterJsonComments,
+    ///     )
+    ///     .eq(actual, str![[r#"{"name": "snapbox"}"#]].is_json());
+    /// # }
+    /// ```
+    pub fn preprocess(
+        mut self,
+        format: crate::data::DataFormat,
+        filter: impl crate::filter::Filter + Send + Sync + 'static,
+    ) -> Self {
+        self.preprocessors
+            .insert(format, std::sync::Arc::new(filter));
+        self
+    }
+
+    /// Apply `filter` to `actual` before matching it against `expected`'s patterns
+    ///
+    /// Filters run after the built-in normalization, like for paths and newlines, and before
+    /// redactions, in the order they were added.  As redactions see the filtered `actual`, a
+    /// filter that rewrites text, like [`FilterAbsolutePaths`][crate::filter::FilterAbsolutePaths],
+    /// keeps redactions from matching what it rewrote.  For filtering before `actual` is parsed,
+    /// see [`Assert::preprocess`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// snapbox::Assert::new()
+    ///     .filter(snapbox::filter::StripAnsi::new())
+    ///     .eq("\x1b[1mbold\x1b[0m", "bold");
+    /// ```
+    pub fn filter(mut self, filter: impl crate::filter::Filter + Send + Sync + 'static) -> Self {
+        self.filters.push(std::sync::Arc::new(filter));
         self
     }
 }
@@ -437,8 +1758,27 @@ impl Default for Assert {
         Self {
             action: Default::default(),
             action_var: Default::default(),
-            normalize_paths: true,
+            label: Default::default(),
+            path_separators: crate::filter::PathSeparators::Everywhere,
+            check_mode: false,
+            sizes_first: false,
             substitutions: Default::default(),
+            preprocessors: Default::default(),
+            filters: Default::default(),
+            usage: Default::default(),
+            normalizers: Default::default(),
+            dump_actual: Default::default(),
+            generated_dir: Default::default(),
+            #[cfg(feature = "diff")]
+            max_diff_lines: Default::default(),
+            #[cfg(feature = "json")]
+            unordered_at: Default::default(),
+            #[cfg(feature = "json")]
+            whole_numbers: false,
+            trim_elide_anchors: false,
+            case_insensitive: false,
+            report_redactions: false,
+            lint_patterns: false,
             palette: crate::report::Palette::color(),
         }
         .redact_with(crate::Redactions::with_exe())
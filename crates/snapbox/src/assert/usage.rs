@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Record which golden files were compared, see [`Assert::track_usage`][super::Assert::track_usage]
+///
+/// Clones share the same record, so one `GoldenUsage` can be handed to every [`Assert`][super::Assert]
+/// in a run and inspected at the end to find goldens that were never exercised.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// let usage = snapbox::assert::GoldenUsage::new();
+/// snapbox::Assert::new()
+///     .track_usage(&usage)
+///     .eq("Hello", snapbox::file!["hello.txt"]);
+/// assert!(usage.is_used("tests/hello.txt"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GoldenUsage {
+    used: Arc<Mutex<BTreeSet<PathBuf>>>,
+}
+
+impl GoldenUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record(&self, path: &Path) {
+        let path = normalize(path);
+        self.used
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(path);
+    }
+
+    /// Whether `path` was compared against
+    pub fn is_used(&self, path: impl AsRef<Path>) -> bool {
+        let path = normalize(path.as_ref());
+        self.used
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .contains(&path)
+    }
+
+    /// All goldens that were compared against
+    pub fn used(&self) -> Vec<PathBuf> {
+        self.used
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Files under `root` that were never compared against
+    ///
+    /// Note: Requires feature flag `dir`
+    #[cfg(feature = "dir")]
+    pub fn unused_in(&self, root: impl AsRef<Path>) -> Vec<PathBuf> {
+        let root = root.as_ref();
+        crate::dir::Walk::new(root)
+            .filter_map(Result::ok)
+            .filter(|path| path.is_file() && !self.is_used(path))
+            .collect()
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
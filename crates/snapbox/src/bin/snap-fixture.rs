@@ -14,6 +14,29 @@ fn run() -> Result<(), Box<dyn Error>> {
         eprintln!("{text}");
     }
 
+    if env::var("color_if_tty").as_deref() == Ok("1") {
+        if is_tty() {
+            println!("\x1b[32mgreen\x1b[0m");
+        } else {
+            println!("green");
+        }
+    }
+
+    #[cfg(all(feature = "cmd", unix))]
+    if env::var("echo_tty_size").as_deref() == Ok("1") {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: `size` outlives the call
+        unsafe {
+            libc::ioctl(1, libc::TIOCGWINSZ as _, &mut size);
+        }
+        println!("{}x{}", size.ws_col, size.ws_row);
+    }
+
     if env::var("echo_large").as_deref() == Ok("1") {
         for i in 0..(128 * 1024) {
             println!("{i}");
@@ -49,6 +72,17 @@ fn run() -> Result<(), Box<dyn Error>> {
     process::exit(code);
 }
 
+#[cfg(all(feature = "cmd", unix))]
+fn is_tty() -> bool {
+    // SAFETY: no memory is passed
+    unsafe { libc::isatty(1) == 1 }
+}
+
+#[cfg(not(all(feature = "cmd", unix)))]
+fn is_tty() -> bool {
+    false
+}
+
 fn main() {
     let code = match run() {
         Ok(_) => 0,
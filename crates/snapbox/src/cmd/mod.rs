@@ -3,8 +3,12 @@
 #[cfg(feature = "color")]
 use anstream::panic;
 
+use crate::filter::{Filter as _, FilterNewlines};
 use crate::IntoData;
 
+#[cfg(all(feature = "pty", unix))]
+mod pty;
+
 /// Process spawning for testing of non-interactive commands
 #[derive(Debug)]
 pub struct Command {
@@ -12,6 +16,8 @@ pub struct Command {
     stdin: Option<crate::Data>,
     timeout: Option<std::time::Duration>,
     _stderr_to_stdout: bool,
+    #[cfg(feature = "pty")]
+    tty: Option<(u16, u16)>,
     config: crate::Assert,
 }
 
@@ -23,6 +29,8 @@ impl Command {
             stdin: None,
             timeout: None,
             _stderr_to_stdout: false,
+            #[cfg(feature = "pty")]
+            tty: None,
             config: crate::Assert::new().action_env(crate::assert::DEFAULT_ACTION_ENV),
         }
     }
@@ -34,6 +42,8 @@ impl Command {
             stdin: None,
             timeout: None,
             _stderr_to_stdout: false,
+            #[cfg(feature = "pty")]
+            tty: None,
             config: crate::Assert::new().action_env(crate::assert::DEFAULT_ACTION_ENV),
         }
     }
@@ -274,8 +284,50 @@ impl Command {
         self._stderr_to_stdout = true;
         self
     }
+
+    /// Run the command under a pseudo-terminal
+    ///
+    /// This captures how a command renders when attached to a terminal, like colors or prompts.
+    /// A terminal has a single output stream, so `stderr` is merged into `stdout`.
+    /// [`Command::stdin`] is typed into the terminal followed by end-of-file; note that the
+    /// terminal echoes it into the output.
+    ///
+    /// The terminal is 80 columns by 24 rows, see [`Command::tty_size`].
+    ///
+    /// This is only supported on Unix; elsewhere running the command fails.
+    ///
+    /// ```rust,no_run
+    /// use snapbox::cmd::Command;
+    ///
+    /// Command::new("ls")
+    ///     .arg("--color=auto")
+    ///     .tty(true)
+    ///     .assert()
+    ///     .success();
+    /// ```
+    ///
+    /// Note: Requires feature flag `pty`
+    #[cfg(feature = "pty")]
+    pub fn tty(mut self, yes: bool) -> Self {
+        self.tty = yes.then(|| self.tty.unwrap_or(DEFAULT_TTY_SIZE));
+        self
+    }
+
+    /// Run the command under a pseudo-terminal of `columns` by `rows`
+    ///
+    /// See [`Command::tty`].
+    ///
+    /// Note: Requires feature flag `pty`
+    #[cfg(feature = "pty")]
+    pub fn tty_size(mut self, columns: u16, rows: u16) -> Self {
+        self.tty = Some((columns, rows));
+        self
+    }
 }
 
+#[cfg(feature = "pty")]
+const DEFAULT_TTY_SIZE: (u16, u16) = (80, 24);
+
 /// # Run Command
 impl Command {
     /// Run the command and assert on the results
@@ -300,9 +352,138 @@ impl Command {
         }
     }
 
+    /// Run the command, checking each line of `stdout` against `expected` as it is written
+    ///
+    /// Unlike [`OutputAssert::stdout_eq`], this fails at the first line that doesn't match,
+    /// killing the command rather than waiting for it to finish.  Once all of `stdout` matched,
+    /// the [`OutputAssert`] is returned for further checks.
+    ///
+    /// `expected` supports a subset of the patterns of [`OutputAssert::stdout_eq`]:
+    /// - `...` is a line-wildcard when on a line by itself, skipping lines until the next line of
+    ///   `expected` matches
+    /// - `[..]` is a character-wildcard when inside a line
+    /// - [`Redactions`][crate::Redactions] and `[?] ` optional lines
+    ///
+    /// `<<<verbatim` regions and path normalization are not supported.  `stderr` is always
+    /// captured separately and the [`Command::timeout`] only applies once `stdout` is closed.
+    ///
+    /// ```rust,no_run
+    /// use snapbox::cmd::Command;
+    /// use snapbox::cmd::cargo_bin;
+    ///
+    /// Command::new(cargo_bin("snap-fixture"))
+    ///     .env("stdout", "Compiling foo\nRunning foo\nfinished in 2s")
+    ///     .stream_stdout_eq("Compiling foo\n...\nfinished in [..]\n")
+    ///     .success();
+    /// ```
+    #[track_caller]
+    pub fn stream_stdout_eq(self, expected: impl IntoData) -> OutputAssert {
+        let config = self.config.clone();
+        match self.stream_stdout(expected.into_data()) {
+            Ok(output) => OutputAssert::new(output).with_assert(config),
+            Err(err) => err.panic(),
+        }
+    }
+
+    fn stream_stdout(
+        mut self,
+        expected: crate::Data,
+    ) -> crate::assert::Result<std::process::Output> {
+        use std::io::BufRead as _;
+
+        let expected = FilterNewlines.filter(expected.coerce_to(crate::data::DataFormat::Text));
+        let Some(expected) = expected.render() else {
+            return Err(format!("Expected stdout is not text: {expected}").into());
+        };
+        let mut matcher = crate::filter::StreamMatcher::new(&expected, self.config.redactions());
+
+        self.cmd.stdin(std::process::Stdio::piped());
+        self.cmd.stdout(std::process::Stdio::piped());
+        self.cmd.stderr(std::process::Stdio::piped());
+        let mut child = self
+            .cmd
+            .spawn()
+            .map_err(|e| format!("Failed to spawn: {e}"))?;
+
+        let stdin = self
+            .stdin
+            .as_ref()
+            .map(|d| d.to_bytes())
+            .transpose()
+            .map_err(|e| format!("Failed to read stdin: {e}"))?;
+        let stdin = stdin.and_then(|i| {
+            child.stdin.take().map(|mut stdin| {
+                std::thread::spawn(move || std::io::Write::write_all(&mut stdin, &i))
+            })
+        });
+        let stderr = child.stderr.take().map(threaded_read);
+        let mut reader = std::io::BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        let palette = self.config.palette;
+        let mut stdout = Vec::new();
+        let mut line = Vec::new();
+        let mut line_num = 0;
+        loop {
+            line.clear();
+            let len = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| format!("Failed to read stdout: {e}"))?;
+            if len == 0 {
+                break;
+            }
+            line_num += 1;
+            stdout.extend_from_slice(&line);
+
+            let actual = String::from_utf8_lossy(&line);
+            let actual = actual.trim_end_matches(['\n', '\r']);
+            if let Err(expected) = matcher.push(actual) {
+                let _ = child.kill();
+                let _ = child.wait();
+                let message = match expected {
+                    Some(expected) => format!(
+                        "stdout line {line_num} does not match\nexpected: {}\nactual:   {}",
+                        palette.info(expected),
+                        palette.error(actual)
+                    ),
+                    None => format!(
+                        "stdout line {line_num} is unexpected\nactual:   {}",
+                        palette.error(actual)
+                    ),
+                };
+                return Err(message.into());
+            }
+        }
+        if let Err(expected) = matcher.finish() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "stdout ended after {line_num} lines\nexpected: {}",
+                palette.info(expected)
+            )
+            .into());
+        }
+
+        // Finish writing stdin before waiting, because waiting drops stdin.
+        stdin.and_then(|t| t.join().unwrap().ok());
+        let status = wait(child, self.timeout).map_err(|e| e.to_string())?;
+        let stderr = stderr
+            .and_then(|t| t.join().unwrap().ok())
+            .unwrap_or_default();
+
+        Ok(std::process::Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
     /// Run the command and capture the `Output`
     #[cfg(feature = "cmd")]
     pub fn output(self) -> Result<std::process::Output, std::io::Error> {
+        #[cfg(feature = "pty")]
+        if let Some(size) = self.tty {
+            return self.tty_output(size);
+        }
         if self._stderr_to_stdout {
             self.single_output()
         } else {
@@ -346,6 +527,25 @@ impl Command {
         })
     }
 
+    #[cfg(all(feature = "pty", unix))]
+    fn tty_output(self, size: (u16, u16)) -> Result<std::process::Output, std::io::Error> {
+        let stdin = self
+            .stdin
+            .as_ref()
+            .map(|d| d.to_bytes())
+            .transpose()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e))?;
+        pty::output(self.cmd, stdin, size, self.timeout)
+    }
+
+    #[cfg(all(feature = "pty", not(unix)))]
+    fn tty_output(self, _size: (u16, u16)) -> Result<std::process::Output, std::io::Error> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "`Command::tty` is only supported on Unix",
+        ))
+    }
+
     fn split_output(mut self) -> Result<std::process::Output, std::io::Error> {
         self.cmd.stdin(std::process::Stdio::piped());
         self.cmd.stdout(std::process::Stdio::piped());
@@ -466,6 +666,27 @@ impl OutputAssert {
         self
     }
 
+    /// Layer `extra` [`Redactions`][crate::Redactions] on top of the ones for these assertions
+    ///
+    /// The [`Assert`][crate::Assert] this was created with is left untouched.
+    ///
+    /// ```rust,no_run
+    /// use snapbox::cmd::Command;
+    /// use snapbox::cmd::cargo_bin;
+    ///
+    /// let mut extra = snapbox::Redactions::new();
+    /// extra.insert("[NAME]", "alice").unwrap();
+    /// let assert = Command::new(cargo_bin("snap-fixture"))
+    ///     .env("stdout", "Hello alice")
+    ///     .assert()
+    ///     .redact_also(extra)
+    ///     .stdout_eq("Hello [NAME]");
+    /// ```
+    pub fn redact_also(mut self, extra: crate::Redactions) -> Self {
+        self.config = self.config.redact_also(extra);
+        self
+    }
+
     /// Access the contained [`Output`].
     ///
     /// [`Output`]: std::process::Output
@@ -708,6 +929,32 @@ impl OutputAssert {
         self
     }
 
+    /// Ensure the command wrote nothing to `stderr`.
+    ///
+    /// `stderr` is normalized like with [`OutputAssert::stderr_eq`] before checking, so
+    /// [redactions][crate::Assert::redact_with] and
+    /// [preprocessors][crate::Assert::preprocess] can be used to tolerate known output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use snapbox::cmd::Command;
+    /// use snapbox::cmd::cargo_bin;
+    ///
+    /// let assert = Command::new(cargo_bin("snap-fixture"))
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .stderr_empty();
+    /// ```
+    #[track_caller]
+    pub fn stderr_empty(self) -> Self {
+        let actual = self.output.stderr.as_slice().into_data();
+        if let Err(err) = self.config.try_empty(&"stderr", actual) {
+            err.panic();
+        }
+        self
+    }
+
     fn write_stdout(&self, writer: &mut dyn std::fmt::Write) -> Result<(), std::fmt::Error> {
         if !self.output.stdout.is_empty() {
             writeln!(writer, "stdout:")?;
@@ -849,16 +1096,92 @@ pub use snapbox_macros::cargo_bin;
 
 /// Look up the path to a cargo-built binary within an integration test.
 ///
+/// If the binary can't be found, this returns where it is expected to be.  See
+/// [`try_cargo_bin`] to report an error instead.
+///
 /// **NOTE:** Prefer [`cargo_bin!`] as this makes assumptions about cargo
 pub fn cargo_bin(name: &str) -> std::path::PathBuf {
-    let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+    try_cargo_bin(name).unwrap_or_else(|_| target_dir().join(bin_file_name(name)))
+}
+
+/// Look up the path to a cargo-built binary within an integration test, reporting if it is missing
+///
+/// The binary is looked for in:
+/// - `CARGO_BIN_EXE_<name>`, if set at runtime
+/// - The target directory of the running test
+///
+/// A binary built with another profile, like `release` for a `debug` test, is not used as it
+/// may be stale; the error points it out instead.  Results are cached for the rest of the
+/// process.
+///
+/// **NOTE:** Prefer [`cargo_bin!`] as this makes assumptions about cargo
+///
+/// ```rust,no_run
+/// let path = snapbox::cmd::try_cargo_bin("snap-fixture").unwrap();
+/// ```
+pub fn try_cargo_bin(name: &str) -> Result<std::path::PathBuf, crate::assert::Error> {
+    static CACHE: std::sync::Mutex<Vec<(String, std::path::PathBuf)>> =
+        std::sync::Mutex::new(Vec::new());
+
+    let mut cache = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((_, path)) = cache.iter().find(|(cached, _)| cached == name) {
+        return Ok(path.clone());
+    }
+
+    let candidates = cargo_bin_candidates(name);
+    let Some(path) = candidates.iter().find(|path| path.is_file()) else {
+        let mut message = format!(
+            "Could not find binary `{name}`; is it a `[[bin]]` target of this package?\nLooked in:"
+        );
+        for candidate in &candidates {
+            message.push_str(&format!("\n  {}", candidate.display()));
+        }
+        for other in other_profile_bins(name) {
+            message.push_str(&format!(
+                "\nFound `{}` built with another profile; build `{name}` with this test's profile",
+                other.display()
+            ));
+        }
+        return Err(message.into());
+    };
+    cache.push((name.to_owned(), path.clone()));
+    Ok(path.clone())
+}
+
+fn cargo_bin_candidates(name: &str) -> Vec<std::path::PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(path) = std::env::var_os(format!("CARGO_BIN_EXE_{name}")) {
+        candidates.push(path.into());
+    }
+    candidates.push(target_dir().join(bin_file_name(name)));
+    candidates
+}
+
+/// Copies of `name` in the `debug` and `release` target directories next to the running test's
+fn other_profile_bins(name: &str) -> Vec<std::path::PathBuf> {
+    let file_name = bin_file_name(name);
     let target_dir = target_dir();
-    target_dir.join(file_name)
+    let Some(target_root) = target_dir.parent() else {
+        return Vec::new();
+    };
+    ["debug", "release"]
+        .into_iter()
+        .map(|profile| target_root.join(profile))
+        .filter(|dir| *dir != target_dir)
+        .map(|dir| dir.join(&file_name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn bin_file_name(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
 }
 
 // Adapted from
 // https://github.com/rust-lang/cargo/blob/485670b3983b52289a2f353d589c57fae2f60f82/tests/testsuite/support/mod.rs#L507
-fn target_dir() -> std::path::PathBuf {
+pub(crate) fn target_dir() -> std::path::PathBuf {
     std::env::current_exe()
         .ok()
         .map(|mut path| {
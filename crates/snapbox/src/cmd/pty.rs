@@ -0,0 +1,145 @@
+//! Run a command under a pseudo-terminal
+
+use std::fs::File;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::os::unix::fs::OpenOptionsExt as _;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::FromRawFd as _;
+use std::os::unix::process::CommandExt as _;
+
+pub(super) fn output(
+    mut cmd: std::process::Command,
+    stdin: Option<Vec<u8>>,
+    size: (u16, u16),
+    timeout: Option<std::time::Duration>,
+) -> std::io::Result<std::process::Output> {
+    let (master, slave) = open(size)?;
+    cmd.stdin(slave.try_clone()?);
+    cmd.stdout(slave.try_clone()?);
+    cmd.stderr(slave);
+    // SAFETY: only async-signal-safe functions are called between `fork` and `exec`
+    unsafe {
+        cmd.pre_exec(|| {
+            // Make the terminal the controlling terminal of a new session, like a shell would
+            if libc::setsid() < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let child = cmd.spawn()?;
+    // Avoid a deadlock! The terminal only reports EOF once all handles to the `slave` are
+    // closed, including those inside the Command object.
+    drop(cmd);
+
+    // Write on another thread so a command that doesn't read its input is still subject to the
+    // timeout.  On a timeout, the writer is left blocked rather than joined.
+    if let Some(stdin) = stdin {
+        let mut writer = master.try_clone()?;
+        std::thread::spawn(move || -> std::io::Result<()> {
+            writer.write_all(&stdin)?;
+            // End-of-file is only recognized at the start of a line
+            if !stdin.ends_with(b"\n") {
+                writer.write_all(EOF)?;
+            }
+            writer.write_all(EOF)
+        });
+    }
+    let output = std::thread::spawn(move || read_to_eof(master));
+
+    let status = super::wait(child, timeout)?;
+    let stdout = output.join().unwrap()?;
+
+    Ok(std::process::Output {
+        status,
+        stdout,
+        stderr: Default::default(),
+    })
+}
+
+/// `VEOF` (`^D`)
+const EOF: &[u8] = b"\x04";
+
+/// Open a pseudo-terminal of `(columns, rows)`, returning the master and slave ends
+fn open(size: (u16, u16)) -> std::io::Result<(File, File)> {
+    // SAFETY: ownership of the returned fd is immediately transferred to a `File`
+    let master = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY) };
+    if master < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: `master` is an open fd that nothing else owns
+    let master = unsafe { File::from_raw_fd(master) };
+    let fd = master.as_raw_fd();
+
+    // SAFETY: `fd` is open for the duration of these calls
+    unsafe {
+        // Don't leak the master into the child
+        check(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+        check(libc::grantpt(fd))?;
+        check(libc::unlockpt(fd))?;
+    }
+    let slave_path = slave_path(fd)?;
+    let slave = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY)
+        .open(slave_path)?;
+
+    let (columns, rows) = size;
+    let size = libc::winsize {
+        ws_row: rows,
+        ws_col: columns,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: `fd` is open and `size` outlives the call
+    unsafe {
+        check(libc::ioctl(fd, libc::TIOCSWINSZ as _, &size))?;
+    }
+
+    Ok((master, slave))
+}
+
+fn slave_path(master: libc::c_int) -> std::io::Result<std::path::PathBuf> {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    // `ptsname` returns a static buffer
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    // SAFETY: `master` is open and the result is copied before the lock is released
+    let name = unsafe { libc::ptsname(master) };
+    if name.is_null() {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: `ptsname` returns a nul-terminated string
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Ok(std::ffi::OsStr::from_bytes(name.to_bytes()).into())
+}
+
+fn check(result: libc::c_int) -> std::io::Result<()> {
+    if result < 0 {
+        Err(std::io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn read_to_eof(mut master: File) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        match master.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => output.extend_from_slice(&buffer[..len]),
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            // Linux reports `EIO` once the slave is closed
+            Err(err) if err.raw_os_error() == Some(libc::EIO) => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(output)
+}
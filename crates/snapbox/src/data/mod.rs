@@ -9,9 +9,11 @@ mod tests;
 
 pub use format::DataFormat;
 pub use source::DataSource;
+pub use source::FileSource;
 pub use source::Inline;
 #[doc(hidden)]
 pub use source::Position;
+pub use source::SnapshotSource;
 
 use filters::FilterSet;
 
@@ -124,6 +126,13 @@ pub trait IntoData: Sized {
         self.into_data().is(format)
     }
 
+    /// Use [`format`][DataFormat] if the data can be parsed as it, otherwise leave it as-is
+    ///
+    /// See [`Data::with_format_hint`]
+    fn with_format_hint(self, format: DataFormat) -> Data {
+        self.into_data().with_format_hint(format)
+    }
+
     /// Initialize as json or [`Error`][DataFormat::Error]
     ///
     /// This is generally used for `expected` data
@@ -344,6 +353,38 @@ macro_rules! file {
     }};
 }
 
+/// Declare an expected value for an assert from a file in the conventional snapshot location
+///
+/// The file is `snapshots/<module path>__<name>.<ext>`, relative to the source file the macro is
+/// run from, with `::` in the module path replaced by `__`.  The extension is from the format,
+/// defaulting to `txt`.
+///
+/// Like [`file!`], a missing file is created when updating snapshots with `SNAPSHOTS=overwrite`.
+///
+/// Output type: [`Data`]
+///
+/// ```
+/// # #[cfg(feature = "json")] {
+/// # use snapbox::snapshot;
+/// snapshot!("help");  // `snapshots/<crate>__<module>__help.txt`
+/// snapshot!("config": Json);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! snapshot {
+    [$name:literal] => {{
+        let dir = $crate::utils::current_dir!();
+        let path = $crate::data::generate_named_snapshot_path(&dir, ::std::module_path!(), $name, None);
+        $crate::Data::read_from(&path, None)
+    }};
+    [$name:literal : $type:ident] => {{
+        let format = $crate::data::DataFormat:: $type;
+        let dir = $crate::utils::current_dir!();
+        let path = $crate::data::generate_named_snapshot_path(&dir, ::std::module_path!(), $name, Some(format));
+        $crate::Data::read_from(&path, Some(format))
+    }};
+}
+
 /// Declare an expected value from within Rust source
 ///
 /// Output type: [`Inline`], see [`IntoData`] for operations
@@ -382,6 +423,7 @@ pub struct Data {
     pub(crate) inner: DataInner,
     pub(crate) source: Option<DataSource>,
     pub(crate) filters: FilterSet,
+    pub(crate) meta: std::collections::BTreeMap<String, String>,
 }
 
 #[derive(Clone, Debug)]
@@ -418,17 +460,37 @@ impl Data {
         Self::with_inner(DataInner::Text(raw.into()))
     }
 
+    /// Mark the data as json (structural comparison)
+    ///
+    /// This takes an already-parsed value, avoiding a serialize / parse round-trip.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "json")] {
+    /// use snapbox::prelude::*;
+    ///
+    /// let value = serde_json::json!({"hello": "world"});
+    /// let actual = snapbox::Data::json(value);
+    /// snapbox::assert_data_eq!(actual, snapbox::str![[r#"{"hello": "[..]"}"#]].is_json());
+    /// # }
+    /// ```
     #[cfg(feature = "json")]
+    #[doc(alias = "from_json_value")]
     pub fn json(raw: impl Into<serde_json::Value>) -> Self {
         Self::with_inner(DataInner::Json(raw.into()))
     }
 
+    /// Mark the data as [json lines](https://jsonlines.org/) (structural comparison)
+    ///
+    /// Like [`Data::json`], this takes already-parsed values.
     #[cfg(feature = "json")]
+    #[doc(alias = "from_jsonlines_value")]
     pub fn jsonlines(raw: impl Into<Vec<serde_json::Value>>) -> Self {
         Self::with_inner(DataInner::JsonLines(serde_json::Value::Array(raw.into())))
     }
 
-    fn error(raw: impl Into<crate::assert::Error>, intended: DataFormat) -> Self {
+    pub(crate) fn error(raw: impl Into<crate::assert::Error>, intended: DataFormat) -> Self {
         Self::with_inner(DataInner::Error(DataError {
             error: raw.into(),
             intended,
@@ -449,6 +511,20 @@ impl Data {
         }
     }
 
+    /// Load `expected` data stored as `key` in a [`SnapshotSource`]
+    ///
+    /// When updating snapshots, the new value is [stored][SnapshotSource::store] back in `source`.
+    pub fn read_from_source(source: std::sync::Arc<dyn SnapshotSource>, key: &str) -> Self {
+        match source.load(key) {
+            Ok(data) => data.with_source(DataSource::custom(source, key)),
+            Err(err) => Self::error(
+                format!("Failed to read {key}: {err}"),
+                DataFormat::from(std::path::Path::new(key)),
+            )
+            .with_source(DataSource::custom(source, key)),
+        }
+    }
+
     /// Remove default [`filters`][crate::filter] from this `expected` result
     pub fn raw(mut self) -> Self {
         self.filters = FilterSet::empty().newlines();
@@ -460,6 +536,28 @@ impl Data {
         self.filters = self.filters.unordered();
         self
     }
+
+    /// Attach metadata, like a title, for custom reporting
+    ///
+    /// Metadata is carried through [filters][crate::filter] and into
+    /// [`Comparison`][crate::assert::Comparison]s but is not compared.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let expected = snapbox::Data::text("Hello world!").with_meta("title", "Greeting");
+    /// assert_eq!(expected.meta("title"), Some("Greeting"));
+    /// assert_eq!(expected, snapbox::Data::text("Hello world!"));
+    /// ```
+    pub fn with_meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.meta.insert(key.into(), value.into());
+        self
+    }
+
+    /// Metadata attached with [`Data::with_meta`]
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(key).map(String::as_str)
+    }
 }
 
 /// # Assertion frameworks operations
@@ -471,6 +569,7 @@ impl Data {
             inner,
             source: None,
             filters: FilterSet::new(),
+            meta: Default::default(),
         }
     }
 
@@ -490,8 +589,18 @@ impl Data {
     ) -> crate::assert::Result<Self> {
         let data =
             std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let data = Self::from_bytes_for(data, path, data_format);
+        Ok(data.with_path(path))
+    }
+
+    /// Interpret `data` as if it were read from `path`, without tracking it as the source
+    pub(crate) fn from_bytes_for(
+        data: Vec<u8>,
+        path: &std::path::Path,
+        data_format: Option<DataFormat>,
+    ) -> Self {
         let data = Self::binary(data);
-        let data = match data_format {
+        match data_format {
             Some(df) => data.is(df),
             None => {
                 let inferred_format = DataFormat::from(path);
@@ -506,8 +615,7 @@ impl Data {
                     _ => data.coerce_to(DataFormat::Text),
                 }
             }
-        };
-        Ok(data.with_path(path))
+        }
     }
 
     /// Overwrite a snapshot
@@ -517,6 +625,10 @@ impl Data {
             source::DataSourceInner::Inline(p) => runtime::get()
                 .write(self, p)
                 .map_err(|err| err.to_string().into()),
+            source::DataSourceInner::Custom(c) => c
+                .source
+                .store(&c.key, self.clone())
+                .map_err(|err| format!("Failed to write {}: {}", c.key, err).into()),
         }
     }
 
@@ -568,6 +680,7 @@ impl Data {
     /// This is generally used for `expected` data
     pub fn is(self, format: DataFormat) -> Self {
         let filters = self.filters;
+        let meta = self.meta.clone();
         let source = self.source.clone();
         match self.try_is(format) {
             Ok(new) => new,
@@ -580,15 +693,55 @@ impl Data {
                     inner,
                     source,
                     filters,
+                    meta,
                 }
             }
         }
     }
 
+    /// Use [`format`][DataFormat] if the data can be parsed as it, otherwise leave it as-is
+    ///
+    /// Unlike [`Data::is`], this is not an error if parsing fails.  Only untyped data,
+    /// [`Text`][DataFormat::Text] and [`Binary`][DataFormat::Binary], is affected so a hint never
+    /// overrides a format that was chosen explicitly.
+    ///
+    /// The format is decided by, in order of precedence:
+    /// 1. [`Data::is`] or the type of constructor, like [`Data::json`]
+    /// 2. This hint
+    /// 3. The file extension, for data read from a file
+    /// 4. Text, for in-memory data
+    ///
+    /// During comparison, `actual` is then [coerced][Data::coerce_to] to the format of `expected`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "json")] {
+    /// use snapbox::data::DataFormat;
+    ///
+    /// let data = snapbox::Data::text(r#"{"hello": "world"}"#).with_format_hint(DataFormat::Json);
+    /// assert_eq!(data.format(), DataFormat::Json);
+    ///
+    /// let data = snapbox::Data::text("hello world").with_format_hint(DataFormat::Json);
+    /// assert_eq!(data.format(), DataFormat::Text);
+    /// # }
+    /// ```
+    pub fn with_format_hint(self, format: DataFormat) -> Self {
+        match self.format() {
+            DataFormat::Text | DataFormat::Binary => {}
+            _ => return self,
+        }
+        match self.clone().try_is(format) {
+            Ok(data) => data,
+            Err(_) => self,
+        }
+    }
+
     fn try_is(self, format: DataFormat) -> crate::assert::Result<Self> {
         let original = self.format();
         let source = self.source;
         let filters = self.filters;
+        let meta = self.meta;
         let inner = match (self.inner, format) {
             (DataInner::Error(inner), _) => DataInner::Error(inner),
             (DataInner::Binary(inner), DataFormat::Binary) => DataInner::Binary(inner),
@@ -635,6 +788,7 @@ impl Data {
             inner,
             source,
             filters,
+            meta,
         })
     }
 
@@ -667,6 +821,7 @@ impl Data {
     pub fn coerce_to(self, format: DataFormat) -> Self {
         let source = self.source;
         let filters = self.filters;
+        let meta = self.meta;
         let inner = match (self.inner, format) {
             (DataInner::Error(inner), _) => DataInner::Error(inner),
             (inner, DataFormat::Error) => inner,
@@ -756,6 +911,48 @@ impl Data {
             inner,
             source,
             filters,
+            meta,
+        }
+    }
+
+    /// Reduce json to its shape, ignoring the concrete values
+    ///
+    /// Each value is replaced with the name of its type: `"null"`, `"boolean"`, `"number"`, or
+    /// `"string"`.  Objects keep their keys and arrays are reduced to the distinct sketches of
+    /// their elements, in the order first seen, so a heterogeneous array lists each shape.
+    /// For json lines, each line is sketched separately.
+    ///
+    /// This is for contract tests that care about the keys and types of output but not values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let actual = snapbox::Data::text(r#"{"name": "snapbox", "tags": ["a", 1, "b"]}"#);
+    /// let expected = snapbox::Data::json(serde_json::json!({
+    ///     "name": "string",
+    ///     "tags": ["string", "number"],
+    /// }));
+    /// assert_eq!(actual.to_json_schema_sketch(), expected);
+    /// ```
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn to_json_schema_sketch(&self) -> Self {
+        let data = if self.format() == DataFormat::JsonLines {
+            self.clone()
+        } else {
+            self.clone().coerce_to(DataFormat::Json)
+        };
+        match data.inner {
+            DataInner::Error(err) => Self::with_inner(DataInner::Error(err)),
+            DataInner::Json(value) => Self::json(json_schema_sketch(&value)),
+            DataInner::JsonLines(serde_json::Value::Array(lines)) => {
+                Self::jsonlines(lines.iter().map(json_schema_sketch).collect::<Vec<_>>())
+            }
+            _ => Self::error(
+                format!("cannot sketch {:?} as json", self.format()),
+                DataFormat::Json,
+            ),
         }
     }
 
@@ -884,6 +1081,34 @@ fn parse_jsonlines(text: &str) -> Result<Vec<serde_json::Value>, serde_json::Err
     Ok(lines)
 }
 
+#[cfg(feature = "json")]
+fn json_schema_sketch(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => Value::String("null".to_owned()),
+        Value::Bool(_) => Value::String("boolean".to_owned()),
+        Value::Number(_) => Value::String("number".to_owned()),
+        Value::String(_) => Value::String("string".to_owned()),
+        Value::Array(elements) => {
+            let mut sketches = Vec::new();
+            for element in elements {
+                let sketch = json_schema_sketch(element);
+                if !sketches.contains(&sketch) {
+                    sketches.push(sketch);
+                }
+            }
+            Value::Array(sketches)
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| (key.clone(), json_schema_sketch(field)))
+                .collect(),
+        ),
+    }
+}
+
 #[cfg(feature = "term-svg")]
 fn term_svg_body(svg: &str) -> Option<&str> {
     let (_header, body, _footer) = split_term_svg(svg)?;
@@ -1000,6 +1225,19 @@ pub fn generate_snapshot_path(fn_path: &str, format: Option<DataFormat>) -> std:
     path.into()
 }
 
+#[doc(hidden)]
+pub fn generate_named_snapshot_path(
+    dir: &std::path::Path,
+    module_path: &str,
+    name: &str,
+    format: Option<DataFormat>,
+) -> std::path::PathBuf {
+    let module_path_normalized = module_path.replace("::", "__");
+    let ext = format.unwrap_or(DataFormat::Text).ext();
+    dir.join("snapshots")
+        .join(format!("{module_path_normalized}__{name}.{ext}"))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
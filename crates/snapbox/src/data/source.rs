@@ -7,7 +7,9 @@ pub struct DataSource {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum DataSourceInner {
     Path(std::path::PathBuf),
-    Inline(Inline),
+    // Boxed to keep `Data` small as it is held by value in enums like `PathDiff`
+    Inline(Box<Inline>),
+    Custom(Box<Custom>),
 }
 
 impl DataSource {
@@ -17,6 +19,16 @@ impl DataSource {
         }
     }
 
+    /// Snapshot stored as `key` in a [`SnapshotSource`]
+    pub fn custom(source: std::sync::Arc<dyn SnapshotSource>, key: impl Into<String>) -> Self {
+        Self {
+            inner: DataSourceInner::Custom(Box::new(Custom {
+                source,
+                key: key.into(),
+            })),
+        }
+    }
+
     pub fn is_path(&self) -> bool {
         self.as_path().is_some()
     }
@@ -55,7 +67,7 @@ impl From<std::path::PathBuf> for DataSource {
 impl From<Inline> for DataSource {
     fn from(inline: Inline) -> Self {
         Self {
-            inner: DataSourceInner::Inline(inline),
+            inner: DataSourceInner::Inline(Box::new(inline)),
         }
     }
 }
@@ -65,10 +77,67 @@ impl std::fmt::Display for DataSource {
         match &self.inner {
             DataSourceInner::Path(value) => crate::dir::display_relpath(value).fmt(f),
             DataSourceInner::Inline(value) => value.fmt(f),
+            DataSourceInner::Custom(value) => value.key.fmt(f),
         }
     }
 }
 
+/// Storage for snapshots outside of the local filesystem, like a shared artifact store
+///
+/// Use with [`Data::read_from_source`][crate::Data::read_from_source].  When updating snapshots,
+/// [`store`][SnapshotSource::store] is called with the new value.
+///
+/// Snapshots in local files are read with [`Data::read_from`][crate::Data::read_from] and don't
+/// go through a `SnapshotSource`.  [`FileSource`] implements this for files, like to fall back
+/// to the filesystem from another source.
+pub trait SnapshotSource: std::fmt::Debug + Send + Sync {
+    /// Read the snapshot for `key`
+    fn load(&self, key: &str) -> std::io::Result<crate::Data>;
+
+    /// Overwrite the snapshot for `key`
+    fn store(&self, key: &str, data: crate::Data) -> std::io::Result<()>;
+}
+
+/// [`SnapshotSource`] for files, with keys being paths relative to a root directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSource {
+    root: std::path::PathBuf,
+}
+
+impl FileSource {
+    /// Snapshots in `root`, with keys relative to it
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl SnapshotSource for FileSource {
+    fn load(&self, key: &str) -> std::io::Result<crate::Data> {
+        let path = self.root.join(key);
+        let data = std::fs::read(&path)?;
+        Ok(crate::Data::from_bytes_for(data, &path, None))
+    }
+
+    fn store(&self, key: &str, data: crate::Data) -> std::io::Result<()> {
+        data.write_to_path(&self.root.join(key))
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Custom {
+    pub(crate) source: std::sync::Arc<dyn SnapshotSource>,
+    pub(crate) key: String,
+}
+
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&self.source, &other.source) && self.key == other.key
+    }
+}
+
+impl Eq for Custom {}
+
 /// Output of [`str!`][crate::str!]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Inline {
@@ -91,6 +91,26 @@ fn json_to_bytes_render() {
     assert_eq!(bytes, rendered);
 }
 
+#[test]
+#[cfg(feature = "json")]
+fn json_from_value_matches_structurally() {
+    let actual = Data::json(json!({"b": 2, "a": [1, "hello"]}));
+    let expected = Data::text(r#"{"a": [1, "[..]"], "b": 2}"#).is(DataFormat::Json);
+    let (actual, expected) = crate::Assert::new().normalize(actual, expected);
+    assert_eq!(DataFormat::Json, actual.format());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn jsonlines_from_value_matches_structurally() {
+    let actual = Data::jsonlines(vec![json!({"id": 1}), json!({"id": 2})]);
+    let expected = Data::text("{\"id\": 1}\n{\"id\": 2}\n").is(DataFormat::JsonLines);
+    let (actual, expected) = crate::Assert::new().normalize(actual, expected);
+    assert_eq!(DataFormat::JsonLines, actual.format());
+    assert_eq!(actual, expected);
+}
+
 // Tests for checking all types are coercible to each other and
 // for when the coercion should fail
 #[test]
@@ -280,3 +300,81 @@ world",
         assert_eq!(expected, actual);
     }
 }
+
+#[test]
+#[cfg(feature = "json")]
+fn json_schema_sketch() {
+    let d = Data::json(json!({
+        "name": "snapbox",
+        "version": 1,
+        "published": true,
+        "license": null,
+        "authors": [
+            {"name": "Ed", "email": "ed@example.com"},
+            {"name": "Jo", "email": "jo@example.com"},
+        ],
+        "keywords": ["snapshot", 5, "test"],
+        "empty": [],
+    }));
+    let sketch = d.to_json_schema_sketch();
+    assert_eq!(
+        sketch,
+        Data::json(json!({
+            "name": "string",
+            "version": "number",
+            "published": "boolean",
+            "license": "null",
+            "authors": [{"name": "string", "email": "string"}],
+            "keywords": ["string", "number"],
+            "empty": [],
+        }))
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_schema_sketch_jsonlines() {
+    let d = Data::jsonlines(vec![json!({"id": 1}), json!({"id": "one"})]);
+    let sketch = d.to_json_schema_sketch();
+    assert_eq!(
+        sketch,
+        Data::jsonlines(vec![json!({"id": "number"}), json!({"id": "string"})])
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_schema_sketch_not_json() {
+    let sketch = Data::text("test").to_json_schema_sketch();
+    assert_eq!(DataFormat::Error, sketch.format());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn format_hint_steers_ambiguous_text() {
+    let hinted = Data::text("[1, 2]").with_format_hint(DataFormat::Json);
+    assert_eq!(hinted, Data::json(json!([1, 2])));
+
+    let hinted = Data::text("[1, 2] items").with_format_hint(DataFormat::Json);
+    assert_eq!(hinted, Data::text("[1, 2] items"));
+
+    let hinted = Data::binary(b"42".to_vec()).with_format_hint(DataFormat::Json);
+    assert_eq!(hinted, Data::json(json!(42)));
+
+    // Explicit formats take precedence
+    let hinted = Data::json(json!("42")).with_format_hint(DataFormat::Text);
+    assert_eq!(hinted, Data::json(json!("42")));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn format_hint_compares_structurally() {
+    let actual = Data::text(r#"{"b": 1, "a": [2]}"#);
+    let expected =
+        Data::text("{\n  \"a\": [2],\n  \"b\": 1\n}\n").with_format_hint(DataFormat::Json);
+    crate::Assert::new().eq(actual, expected);
+
+    let actual = Data::text("b: 1");
+    let expected = Data::text("b: 1").with_format_hint(DataFormat::Json);
+    crate::Assert::new().eq(actual, expected);
+}
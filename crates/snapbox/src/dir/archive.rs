@@ -0,0 +1,233 @@
+use std::io::Read as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::FileType;
+use super::PathDiff;
+use crate::filter::{Filter as _, FilterNewlines, NormalizeToExpected};
+
+impl PathDiff {
+    /// Report differences between `actual_root` and the contents of the `pattern_archive` tarball
+    ///
+    /// Entries are read one at a time; the archive is never extracted to disk.
+    ///
+    /// Note: Requires feature flag `tar`
+    pub fn subset_eq_tar_iter(
+        pattern_archive: impl Into<PathBuf>,
+        actual_root: impl Into<PathBuf>,
+    ) -> impl Iterator<Item = Result<(PathBuf, PathBuf), Self>> {
+        let pattern_archive = pattern_archive.into();
+        let actual_root = actual_root.into();
+        Self::subset_tar_iter_inner(
+            pattern_archive,
+            actual_root,
+            None,
+            crate::filter::PathSeparators::Never,
+            false,
+            false,
+        )
+    }
+
+    /// Like [`PathDiff::subset_eq_tar_iter`] but file contents are matched as patterns
+    ///
+    /// Note: Requires feature flag `tar`
+    pub fn subset_matches_tar_iter(
+        pattern_archive: impl Into<PathBuf>,
+        actual_root: impl Into<PathBuf>,
+        substitutions: &crate::Redactions,
+    ) -> impl Iterator<Item = Result<(PathBuf, PathBuf), Self>> {
+        let pattern_archive = pattern_archive.into();
+        let actual_root = actual_root.into();
+        Self::subset_tar_iter_inner(
+            pattern_archive,
+            actual_root,
+            Some(substitutions),
+            crate::filter::PathSeparators::Everywhere,
+            false,
+            false,
+        )
+    }
+
+    pub(crate) fn subset_tar_iter_inner(
+        expected_archive: PathBuf,
+        actual_root: PathBuf,
+        substitutions: Option<&crate::Redactions>,
+        path_separators: crate::filter::PathSeparators,
+        check_mode: bool,
+        sizes_first: bool,
+    ) -> std::vec::IntoIter<Result<(PathBuf, PathBuf), Self>> {
+        let read_failure = |e: std::io::Error| {
+            Self::Failure(format!("Failed to read {}: {}", expected_archive.display(), e).into())
+        };
+
+        let file = match std::fs::File::open(&expected_archive) {
+            Ok(file) => file,
+            Err(err) => return vec![Err(read_failure(err))].into_iter(),
+        };
+        let mut archive = tar::Archive::new(file);
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(err) => return vec![Err(read_failure(err))].into_iter(),
+        };
+
+        let mut checks = Vec::new();
+        for entry in entries {
+            let check = entry.map_err(read_failure).and_then(|entry| {
+                Self::check_tar_entry(
+                    &expected_archive,
+                    &actual_root,
+                    entry,
+                    substitutions,
+                    path_separators,
+                    check_mode,
+                    sizes_first,
+                )
+            });
+            match check {
+                Ok(Some(paths)) => checks.push(Ok(paths)),
+                Ok(None) => {}
+                Err(diff) => checks.push(Err(diff)),
+            }
+        }
+        checks.into_iter()
+    }
+
+    fn check_tar_entry<R: std::io::Read>(
+        expected_archive: &Path,
+        actual_root: &Path,
+        mut entry: tar::Entry<'_, R>,
+        substitutions: Option<&crate::Redactions>,
+        path_separators: crate::filter::PathSeparators,
+        check_mode: bool,
+        sizes_first: bool,
+    ) -> Result<Option<(PathBuf, PathBuf)>, Self> {
+        let raw_path = entry.path().map_err(|e| {
+            Self::Failure(format!("Failed to read {}: {}", expected_archive.display(), e).into())
+        })?;
+        let Some(rel) = entry_rel_path(&raw_path) else {
+            return Err(Self::Failure(
+                format!(
+                    "{}: entry `{}` escapes the archive root",
+                    expected_archive.display(),
+                    raw_path.display()
+                )
+                .into(),
+            ));
+        };
+        if rel.as_os_str().is_empty() {
+            // The archive root itself
+            return Ok(None);
+        }
+        let expected_path = expected_archive.join(&rel);
+        let actual_path = actual_root.join(&rel);
+
+        let entry_type = entry.header().entry_type();
+        let expected_type = if entry_type.is_dir() {
+            FileType::Dir
+        } else if entry_type.is_file() || entry_type.is_contiguous() {
+            FileType::File
+        } else if entry_type.is_symlink() {
+            FileType::Symlink
+        } else {
+            // Metadata entries, hard links, devices, etc
+            return Ok(None);
+        };
+        let actual_type = FileType::from_path(&actual_path);
+        if expected_type != actual_type {
+            return Err(Self::TypeMismatch {
+                expected_path,
+                actual_path,
+                expected_type,
+                actual_type,
+            });
+        }
+        if check_mode && matches!(expected_type, FileType::File | FileType::Dir) {
+            let expected_mode = entry.header().mode().map_err(|e| {
+                Self::Failure(format!("Failed to read {}: {}", expected_path.display(), e).into())
+            })?;
+            Self::check_exec_bits(&expected_path, expected_mode, &actual_path)?;
+        }
+
+        match expected_type {
+            FileType::Symlink => {
+                let expected_target = entry
+                    .link_name()
+                    .ok()
+                    .and_then(|target| target.map(|t| t.into_owned()));
+                let actual_target = std::fs::read_link(&actual_path).ok();
+                if expected_target != actual_target {
+                    return Err(Self::LinkMismatch {
+                        expected_path,
+                        actual_path,
+                        expected_target: expected_target.unwrap_or_default(),
+                        actual_target: actual_target.unwrap_or_default(),
+                    });
+                }
+            }
+            FileType::File => {
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).map_err(|e| {
+                    Self::Failure(
+                        format!("Failed to read {}: {}", expected_path.display(), e).into(),
+                    )
+                })?;
+                if sizes_first && is_identical(&content, &actual_path) {
+                    return Ok(Some((expected_path, actual_path)));
+                }
+                let expected =
+                    FilterNewlines.filter(crate::Data::from_bytes_for(content, &rel, None));
+
+                let mut actual =
+                    crate::Data::try_read_from(&actual_path, None).map_err(Self::Failure)?;
+                actual = actual.coerce_to(expected.intended_format());
+                actual = path_separators.filter(actual);
+                actual = FilterNewlines.filter(actual);
+                if let Some(substitutions) = substitutions {
+                    actual = NormalizeToExpected::new()
+                        .redact_with(substitutions)
+                        .normalize(actual, &expected);
+                }
+
+                if expected != actual {
+                    return Err(Self::ContentMismatch {
+                        expected_path,
+                        actual_path,
+                        expected_content: expected,
+                        actual_content: actual,
+                    });
+                }
+            }
+            FileType::Dir | FileType::Unknown | FileType::Missing => {}
+        }
+
+        Ok(Some((expected_path, actual_path)))
+    }
+}
+
+/// Whether `actual_path` is byte-for-byte identical to `expected`, checking its size first
+fn is_identical(expected: &[u8], actual_path: &Path) -> bool {
+    match std::fs::metadata(actual_path) {
+        Ok(metadata) if metadata.len() == expected.len() as u64 => {}
+        _ => return false,
+    }
+    std::fs::read(actual_path)
+        .map(|actual| actual == expected)
+        .unwrap_or(false)
+}
+
+/// Normalize an archive entry's path to be relative to the archive root
+///
+/// Returns `None` if the path would escape the root
+fn entry_rel_path(path: &Path) -> Option<PathBuf> {
+    let mut rel = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => rel.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir
+            | std::path::Component::RootDir
+            | std::path::Component::Prefix(_) => return None,
+        }
+    }
+    Some(rel)
+}
@@ -1,5 +1,5 @@
 #[cfg(feature = "dir")]
-use crate::filter::{Filter as _, FilterNewlines, FilterPaths, NormalizeToExpected};
+use crate::filter::{Filter as _, FilterNewlines, NormalizeToExpected};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PathDiff {
@@ -35,13 +35,15 @@ impl PathDiff {
     ) -> impl Iterator<Item = Result<(std::path::PathBuf, std::path::PathBuf), Self>> {
         let pattern_root = pattern_root.into();
         let actual_root = actual_root.into();
-        Self::subset_eq_iter_inner(pattern_root, actual_root)
+        Self::subset_eq_iter_inner(pattern_root, actual_root, false, false)
     }
 
     #[cfg(feature = "dir")]
     pub(crate) fn subset_eq_iter_inner(
         expected_root: std::path::PathBuf,
         actual_root: std::path::PathBuf,
+        check_mode: bool,
+        sizes_first: bool,
     ) -> impl Iterator<Item = Result<(std::path::PathBuf, std::path::PathBuf), Self>> {
         let walker = crate::dir::Walk::new(&expected_root);
         walker.map(move |r| {
@@ -74,6 +76,10 @@ impl PathDiff {
                     }
                 }
                 FileType::File => {
+                    if sizes_first && Self::is_identical(&expected_path, &actual_path) {
+                        return Ok((expected_path, actual_path));
+                    }
+
                     let mut actual =
                         crate::Data::try_read_from(&actual_path, None).map_err(Self::Failure)?;
 
@@ -93,6 +99,9 @@ impl PathDiff {
                 }
                 FileType::Dir | FileType::Unknown | FileType::Missing => {}
             }
+            if check_mode && matches!(expected_type, FileType::File | FileType::Dir) {
+                Self::check_mode(&expected_path, &actual_path)?;
+            }
 
             Ok((expected_path, actual_path))
         })
@@ -109,7 +118,13 @@ impl PathDiff {
     ) -> impl Iterator<Item = Result<(std::path::PathBuf, std::path::PathBuf), Self>> + '_ {
         let pattern_root = pattern_root.into();
         let actual_root = actual_root.into();
-        Self::subset_matches_iter_inner(pattern_root, actual_root, substitutions, true)
+        Self::subset_matches_iter_inner(
+            pattern_root,
+            actual_root,
+            substitutions,
+            crate::filter::PathSeparators::Everywhere,
+            false,
+        )
     }
 
     #[cfg(feature = "dir")]
@@ -117,7 +132,8 @@ impl PathDiff {
         expected_root: std::path::PathBuf,
         actual_root: std::path::PathBuf,
         substitutions: &crate::Redactions,
-        normalize_paths: bool,
+        path_separators: crate::filter::PathSeparators,
+        check_mode: bool,
     ) -> impl Iterator<Item = Result<(std::path::PathBuf, std::path::PathBuf), Self>> + '_ {
         let walker = crate::dir::Walk::new(&expected_root);
         walker.map(move |r| {
@@ -157,9 +173,7 @@ impl PathDiff {
                         FilterNewlines.filter(crate::Data::read_from(&expected_path, None));
 
                     actual = actual.coerce_to(expected.intended_format());
-                    if normalize_paths {
-                        actual = FilterPaths.filter(actual);
-                    }
+                    actual = path_separators.filter(actual);
                     actual = NormalizeToExpected::new()
                         .redact_with(substitutions)
                         .normalize(FilterNewlines.filter(actual), &expected);
@@ -175,10 +189,93 @@ impl PathDiff {
                 }
                 FileType::Dir | FileType::Unknown | FileType::Missing => {}
             }
+            if check_mode && matches!(expected_type, FileType::File | FileType::Dir) {
+                Self::check_mode(&expected_path, &actual_path)?;
+            }
 
             Ok((expected_path, actual_path))
         })
     }
+
+    /// Whether the files are byte-for-byte identical, checking their sizes first
+    ///
+    /// Files that differ may still match after normalization, so this only lets identical files
+    /// skip the full comparison.
+    #[cfg(feature = "dir")]
+    fn is_identical(expected_path: &std::path::Path, actual_path: &std::path::Path) -> bool {
+        let size = |path: &std::path::Path| std::fs::metadata(path).map(|metadata| metadata.len());
+        match (size(expected_path), size(actual_path)) {
+            (Ok(expected_size), Ok(actual_size)) if expected_size == actual_size => {}
+            _ => return false,
+        }
+        match (std::fs::read(expected_path), std::fs::read(actual_path)) {
+            (Ok(expected), Ok(actual)) => expected == actual,
+            _ => false,
+        }
+    }
+
+    #[cfg(all(feature = "dir", unix))]
+    fn check_mode(
+        expected_path: &std::path::Path,
+        actual_path: &std::path::Path,
+    ) -> Result<(), Self> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let expected_mode = std::fs::metadata(expected_path)
+            .map(|metadata| metadata.permissions().mode())
+            .map_err(|e| {
+                Self::Failure(format!("Failed to read {}: {}", expected_path.display(), e).into())
+            })?;
+        Self::check_exec_bits(expected_path, expected_mode, actual_path)
+    }
+
+    /// Compare the executable bits of `actual_path` against `expected_mode`
+    #[cfg(all(feature = "dir", unix))]
+    pub(crate) fn check_exec_bits(
+        expected_path: &std::path::Path,
+        expected_mode: u32,
+        actual_path: &std::path::Path,
+    ) -> Result<(), Self> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let expected_mode = expected_mode & 0o777;
+        let actual_mode = std::fs::metadata(actual_path)
+            .map(|metadata| metadata.permissions().mode() & 0o777)
+            .map_err(|e| {
+                Self::Failure(format!("Failed to read {}: {}", actual_path.display(), e).into())
+            })?;
+        // Only the executable bits are tracked by git, the rest depend on the umask
+        const EXECUTABLE: u32 = 0o111;
+        if expected_mode & EXECUTABLE != actual_mode & EXECUTABLE {
+            return Err(Self::Failure(
+                format!(
+                    "{}: Expected mode {expected_mode:o}, was {actual_mode:o}",
+                    expected_path.display()
+                )
+                .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Permission bits are only checked on Unix
+    #[cfg(all(feature = "dir", not(unix)))]
+    fn check_mode(
+        _expected_path: &std::path::Path,
+        _actual_path: &std::path::Path,
+    ) -> Result<(), Self> {
+        Ok(())
+    }
+
+    /// Permission bits are only checked on Unix
+    #[cfg(all(feature = "dir", not(unix)))]
+    pub(crate) fn check_exec_bits(
+        _expected_path: &std::path::Path,
+        _expected_mode: u32,
+        _actual_path: &std::path::Path,
+    ) -> Result<(), Self> {
+        Ok(())
+    }
 }
 
 impl PathDiff {
@@ -300,7 +397,18 @@ impl PathDiff {
                 actual_path: _,
                 expected_content,
                 actual_content,
-            } => actual_content.write_to(expected_content.source().unwrap()),
+            } => {
+                let Some(source) = expected_content.source() else {
+                    return Err(format!(
+                        "{} cannot be overwritten",
+                        self.expected_path()
+                            .expect("always present for content mismatches")
+                            .display()
+                    )
+                    .into());
+                };
+                actual_content.write_to(source)
+            }
         }
     }
 }
@@ -1,5 +1,7 @@
 //! Initialize working directories and assert on how they've changed
 
+#[cfg(feature = "tar")]
+mod archive;
 mod diff;
 mod fixture;
 mod ops;
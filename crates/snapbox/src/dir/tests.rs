@@ -34,3 +34,302 @@ fn file_type_detect_missing() {
     let actual = FileType::from_path(path);
     assert_eq!(actual, FileType::Missing);
 }
+
+#[cfg(feature = "tar")]
+fn build_tar(path: &std::path::Path, files: &[(&str, Option<&str>)]) {
+    let file = std::fs::File::create(path).unwrap();
+    let mut builder = tar::Builder::new(file);
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        match content {
+            Some(content) => {
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                builder
+                    .append_data(&mut header, name, content.as_bytes())
+                    .unwrap();
+            }
+            None => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_size(0);
+                header.set_mode(0o755);
+                builder
+                    .append_data(&mut header, name, std::io::empty())
+                    .unwrap();
+            }
+        }
+    }
+    builder.finish().unwrap();
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn subset_eq_tar_reports_differing_file() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive = temp.path().join("golden.tar");
+    build_tar(
+        &archive,
+        &[
+            ("./", None),
+            ("./a.txt", Some("hello\n")),
+            ("./sub/", None),
+            ("./sub/b.txt", Some("moon\n")),
+        ],
+    );
+    let actual_root = temp.path().join("actual");
+    std::fs::create_dir_all(actual_root.join("sub")).unwrap();
+    std::fs::write(actual_root.join("a.txt"), "hello\r\n").unwrap();
+    std::fs::write(actual_root.join("sub/b.txt"), "world\n").unwrap();
+
+    let checks: Vec<_> = PathDiff::subset_eq_tar_iter(&archive, &actual_root).collect();
+    assert_eq!(checks.len(), 3, "{checks:?}");
+    assert_eq!(
+        checks[0],
+        Ok((archive.join("a.txt"), actual_root.join("a.txt")))
+    );
+    assert_eq!(
+        checks[1],
+        Ok((archive.join("sub"), actual_root.join("sub")))
+    );
+    match &checks[2] {
+        Err(PathDiff::ContentMismatch {
+            expected_path,
+            actual_path,
+            expected_content,
+            actual_content,
+        }) => {
+            assert_eq!(expected_path, &archive.join("sub").join("b.txt"));
+            assert_eq!(actual_path, &actual_root.join("sub").join("b.txt"));
+            assert_eq!(expected_content, &crate::Data::text("moon\n"));
+            assert_eq!(actual_content, &crate::Data::text("world\n"));
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn subset_matches_tar_redacts() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive = temp.path().join("golden.tar");
+    build_tar(&archive, &[("a.txt", Some("hello [..]\n")), ("sub/", None)]);
+    let actual_root = temp.path().join("actual");
+    std::fs::create_dir_all(actual_root.join("sub")).unwrap();
+    std::fs::write(actual_root.join("a.txt"), "hello world\n").unwrap();
+
+    crate::Assert::new()
+        .action(crate::assert::Action::Verify)
+        .subset_matches_tar(&archive, &actual_root);
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn subset_eq_tar_missing_dir() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive = temp.path().join("golden.tar");
+    build_tar(&archive, &[("sub/", None)]);
+    let actual_root = temp.path().join("actual");
+    std::fs::create_dir_all(&actual_root).unwrap();
+
+    let checks: Vec<_> = PathDiff::subset_eq_tar_iter(&archive, &actual_root).collect();
+    assert_eq!(
+        checks,
+        vec![Err(PathDiff::TypeMismatch {
+            expected_path: archive.join("sub"),
+            actual_path: actual_root.join("sub"),
+            expected_type: FileType::Dir,
+            actual_type: FileType::Missing,
+        })]
+    );
+}
+
+#[test]
+#[cfg(all(feature = "dir", unix))]
+fn subset_eq_check_mode_reports_exec_bit() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let temp = tempfile::tempdir().unwrap();
+    let expected_root = temp.path().join("expected");
+    let actual_root = temp.path().join("actual");
+    for (root, run_mode, data_mode) in
+        [(&expected_root, 0o644, 0o644), (&actual_root, 0o755, 0o664)]
+    {
+        std::fs::create_dir_all(root).unwrap();
+        for (name, mode) in [("run.sh", run_mode), ("data.txt", data_mode)] {
+            let path = root.join(name);
+            std::fs::write(&path, "echo hello\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+    }
+
+    let checks: Vec<_> =
+        PathDiff::subset_eq_iter_inner(expected_root.clone(), actual_root.clone(), false, false)
+            .collect();
+    assert!(checks.iter().all(Result::is_ok), "{checks:?}");
+
+    let diffs: Vec<_> =
+        PathDiff::subset_eq_iter_inner(expected_root.clone(), actual_root.clone(), true, false)
+            .filter_map(Result::err)
+            .collect();
+    assert_eq!(
+        diffs,
+        vec![PathDiff::Failure(
+            format!(
+                "{}: Expected mode 644, was 755",
+                expected_root.join("run.sh").display()
+            )
+            .into()
+        )]
+    );
+}
+
+#[test]
+#[cfg(feature = "dir")]
+fn subset_eq_sizes_first() {
+    let temp = tempfile::tempdir().unwrap();
+    let expected_root = temp.path().join("expected");
+    let actual_root = temp.path().join("actual");
+    let files = [
+        ("same.txt", "hello\n", "hello\n"),
+        ("newlines.txt", "hello\n", "hello\r\n"),
+        ("long.txt", "hello\n", "hello world\n"),
+        ("short.txt", "hello\n", "howdy\n"),
+    ];
+    for (name, expected, actual) in files {
+        for (root, content) in [(&expected_root, expected), (&actual_root, actual)] {
+            std::fs::create_dir_all(root).unwrap();
+            std::fs::write(root.join(name), content).unwrap();
+        }
+    }
+
+    let mut diffs: Vec<_> =
+        PathDiff::subset_eq_iter_inner(expected_root.clone(), actual_root.clone(), false, true)
+            .filter_map(Result::err)
+            .collect();
+    diffs.sort_by(|left, right| left.expected_path().cmp(&right.expected_path()));
+    // Sizes only skip comparing identical files, other files are still normalized
+    let paths: Vec<_> = diffs.iter().map(PathDiff::expected_path).collect();
+    assert_eq!(
+        paths,
+        [
+            Some(expected_root.join("long.txt").as_path()),
+            Some(expected_root.join("short.txt").as_path()),
+        ],
+        "{diffs:?}"
+    );
+    assert!(
+        diffs
+            .iter()
+            .all(|diff| matches!(diff, PathDiff::ContentMismatch { .. })),
+        "{diffs:?}"
+    );
+}
+
+#[test]
+#[cfg(all(feature = "tar", unix))]
+fn subset_eq_tar_check_mode_reports_exec_bit() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let temp = tempfile::tempdir().unwrap();
+    let archive = temp.path().join("golden.tar");
+    build_tar(
+        &archive,
+        &[
+            ("run.sh", Some("echo hello\n")),
+            ("data.txt", Some("hello\n")),
+        ],
+    );
+    let actual_root = temp.path().join("actual");
+    std::fs::create_dir_all(&actual_root).unwrap();
+    for (name, content, mode) in [
+        ("run.sh", "echo hello\n", 0o755),
+        ("data.txt", "hello\n", 0o664),
+    ] {
+        let path = actual_root.join(name);
+        std::fs::write(&path, content).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    let checks: Vec<_> = PathDiff::subset_tar_iter_inner(
+        archive.clone(),
+        actual_root.clone(),
+        None,
+        crate::filter::PathSeparators::Never,
+        false,
+        false,
+    )
+    .collect();
+    assert!(checks.iter().all(Result::is_ok), "{checks:?}");
+
+    let diffs: Vec<_> = PathDiff::subset_tar_iter_inner(
+        archive.clone(),
+        actual_root.clone(),
+        None,
+        crate::filter::PathSeparators::Never,
+        true,
+        false,
+    )
+    .filter_map(Result::err)
+    .collect();
+    assert_eq!(
+        diffs,
+        vec![PathDiff::Failure(
+            format!(
+                "{}: Expected mode 644, was 755",
+                archive.join("run.sh").display()
+            )
+            .into()
+        )]
+    );
+}
+
+#[test]
+#[cfg(feature = "tar")]
+fn subset_eq_tar_sizes_first() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive = temp.path().join("golden.tar");
+    let files = [
+        ("same.txt", "hello\n", "hello\n"),
+        ("newlines.txt", "hello\n", "hello\r\n"),
+        ("long.txt", "hello\n", "hello world\n"),
+        ("short.txt", "hello\n", "howdy\n"),
+    ];
+    let entries: Vec<_> = files
+        .iter()
+        .map(|(name, expected, _actual)| (*name, Some(*expected)))
+        .collect();
+    build_tar(&archive, &entries);
+    let actual_root = temp.path().join("actual");
+    std::fs::create_dir_all(&actual_root).unwrap();
+    for (name, _expected, actual) in files {
+        std::fs::write(actual_root.join(name), actual).unwrap();
+    }
+
+    let diffs: Vec<_> = PathDiff::subset_tar_iter_inner(
+        archive.clone(),
+        actual_root.clone(),
+        None,
+        crate::filter::PathSeparators::Never,
+        false,
+        true,
+    )
+    .filter_map(Result::err)
+    .collect();
+    // Sizes only skip comparing identical files, other files are still normalized
+    let paths: Vec<_> = diffs.iter().map(PathDiff::expected_path).collect();
+    assert_eq!(
+        paths,
+        [
+            Some(archive.join("long.txt").as_path()),
+            Some(archive.join("short.txt").as_path()),
+        ],
+        "{diffs:?}"
+    );
+    assert!(
+        diffs
+            .iter()
+            .all(|diff| matches!(diff, PathDiff::ContentMismatch { .. })),
+        "{diffs:?}"
+    );
+}
@@ -0,0 +1,111 @@
+use super::Filter;
+use crate::Data;
+
+/// Redact anything that looks like an absolute path as `[PATH]`
+///
+/// Errors from dependencies can embed paths, like temp directories, that can't be registered
+/// as [`Redactions`][crate::Redactions] ahead of time.  This finds paths by their shape:
+/// - Unix paths with at least two components, like `/tmp/.tmpA1b2/Cargo.toml`
+/// - Windows paths with a drive, like `C:\Users\ci\file.txt` or `C:/Users/ci/file.txt`
+/// - Windows UNC paths, like `\\server\share\file.txt`
+///
+/// A path must start a word, so URLs like `https://example.com/a/b` and fractions like `1/2`
+/// are left alone.  A path ends at whitespace, quotes, brackets, or a `:`, so locations like
+/// `/tmp/main.rs:10:5` become `[PATH]:10:5`.  Paths containing spaces are only redacted up to
+/// the first space.
+///
+/// Apply this to `actual` and write `[PATH]` in `expected`.  For json, this applies to strings.
+///
+/// With [`Assert::filter`][crate::Assert::filter], this runs before redactions, so it takes
+/// precedence over them: with `[ROOT]` redacting `/home/ci`, `/home/ci/src/lib.rs` becomes
+/// `[PATH]`, not `[ROOT]/src/lib.rs`.  Only add this filter when `expected` doesn't rely on
+/// redactions of absolute paths.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterAbsolutePaths
+///     .filter("error: could not read /tmp/.tmpA1b2/config.toml: not found".into());
+/// assert_eq!(actual, snapbox::Data::text("error: could not read [PATH]: not found"));
+/// ```
+pub struct FilterAbsolutePaths;
+impl Filter for FilterAbsolutePaths {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &redact_absolute_paths)
+    }
+}
+
+const PLACEHOLDER: &str = "[PATH]";
+
+fn redact_absolute_paths(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        let starts_word = text[..index]
+            .chars()
+            .next_back()
+            .map(is_path_boundary)
+            .unwrap_or(true);
+        if let Some(len) = starts_word.then(|| absolute_path_len(rest)).flatten() {
+            redacted.push_str(PLACEHOLDER);
+            index += len;
+            continue;
+        }
+        let c = rest.chars().next().expect("`index` is in bounds");
+        redacted.push(c);
+        index += c.len_utf8();
+    }
+    redacted
+}
+
+/// Characters that can come right before a path
+fn is_path_boundary(c: char) -> bool {
+    c.is_whitespace() || "\"'`([{<=,".contains(c)
+}
+
+/// Characters that end a path
+fn is_path_end(c: char) -> bool {
+    c.is_whitespace() || "\"'`()[]{}<>,;:|".contains(c)
+}
+
+/// The length of the absolute path at the start of `text`, if any
+fn absolute_path_len(text: &str) -> Option<usize> {
+    let (prefix_len, separators): (usize, &[char]) = if text.starts_with(r"\\") {
+        (2, &['\\', '/'])
+    } else if is_drive_prefix(text) {
+        (3, &['\\', '/'])
+    } else if text.starts_with('/') {
+        (1, &['/'])
+    } else {
+        return None;
+    };
+
+    let body = &text[prefix_len..];
+    let body_len = body.find(is_path_end).unwrap_or(body.len());
+    // Drop punctuation ending a sentence, like `see /tmp/log.txt.`
+    let body = body[..body_len].trim_end_matches(['.', '!', '?']);
+
+    let components = body
+        .split(separators)
+        .filter(|component| !component.is_empty())
+        .count();
+    let starts_with_component = body
+        .chars()
+        .next()
+        .map(|c| !separators.contains(&c))
+        .unwrap_or(false);
+    let min_components = if prefix_len == 1 { 2 } else { 1 };
+    (starts_with_component && min_components <= components).then_some(prefix_len + body.len())
+}
+
+/// `C:\` or `C:/`
+fn is_drive_prefix(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/')
+}
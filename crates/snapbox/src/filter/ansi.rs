@@ -0,0 +1,94 @@
+use super::Filter;
+use crate::Data;
+
+/// Remove ANSI escape sequences, like colors, from text
+///
+/// Many programs emit colors even when their output isn't a terminal.  This removes CSI
+/// sequences (`ESC [ <params> <final byte>`), which include SGR sequences for colors and styles
+/// (`ESC [ ... m`) as well as cursor movement and erasing.  To only remove the sequences that
+/// aren't styling, see [`StripAnsi::keep_sgr`].
+///
+/// Unterminated escape sequences are left as-is.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let colored = "\x1b[1m\x1b[32mok\x1b[0m: 3 passed";
+/// let actual = snapbox::filter::StripAnsi::new().filter(colored.into());
+/// assert_eq!(actual, snapbox::Data::text("ok: 3 passed"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StripAnsi {
+    keep_sgr: bool,
+}
+
+impl StripAnsi {
+    /// Remove all CSI sequences
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep SGR sequences, for snapshotting styling, while removing other CSI sequences
+    ///
+    /// ```rust
+    /// use snapbox::filter::Filter as _;
+    ///
+    /// let progress = "\x1b[2K\x1b[32mok\x1b[0m";
+    /// let actual = snapbox::filter::StripAnsi::new()
+    ///     .keep_sgr(true)
+    ///     .filter(progress.into());
+    /// assert_eq!(actual, snapbox::Data::text("\x1b[32mok\x1b[0m"));
+    /// ```
+    pub fn keep_sgr(mut self, yes: bool) -> Self {
+        self.keep_sgr = yes;
+        self
+    }
+
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(CSI_START) {
+            normalized.push_str(&rest[..start]);
+            let sequence = &rest[start..];
+            let Some(len) = csi_len(sequence) else {
+                // Unterminated, leave as-is
+                rest = sequence;
+                break;
+            };
+            if self.keep_sgr && sequence[..len].ends_with(SGR_FINAL) {
+                normalized.push_str(&sequence[..len]);
+            }
+            rest = &sequence[len..];
+        }
+        normalized.push_str(rest);
+        normalized
+    }
+}
+
+impl Filter for StripAnsi {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.normalize(text))
+    }
+}
+
+const CSI_START: &str = "\x1b[";
+const SGR_FINAL: char = 'm';
+
+/// Length of the CSI sequence at the start of `sequence`, including its final byte
+///
+/// The body is parameter bytes (`0x30..=0x3F`) followed by intermediate bytes (`0x20..=0x2F`).
+fn csi_len(sequence: &str) -> Option<usize> {
+    let body = &sequence[CSI_START.len()..];
+    let params = body
+        .find(|c| !('\x30'..='\x3f').contains(&c))
+        .unwrap_or(body.len());
+    let intermediates = body[params..]
+        .find(|c| !('\x20'..='\x2f').contains(&c))
+        .map(|len| params + len)?;
+    let final_byte = body[intermediates..].chars().next()?;
+    ('\x40'..='\x7e')
+        .contains(&final_byte)
+        .then_some(CSI_START.len() + intermediates + final_byte.len_utf8())
+}
@@ -0,0 +1,139 @@
+use super::Filter;
+use crate::data::DataInner;
+use crate::Data;
+
+/// Parse an HTTP response into json for structural comparison
+///
+/// A response is a status line, headers, a blank line, and an optional body:
+/// ```text
+/// HTTP/1.1 200 OK
+/// Content-Type: application/json
+///
+/// {"id": 42}
+/// ```
+///
+/// This becomes
+/// ```json
+/// {
+///   "version": "HTTP/1.1",
+///   "status": 200,
+///   "reason": "OK",
+///   "headers": {"content-type": "application/json"},
+///   "body": {"id": 42}
+/// }
+/// ```
+/// - Header names are lowercased and sorted, so their case and order don't matter
+/// - Repeated headers become an array of their values, in order
+/// - A body that is valid json is compared structurally, including json wildcards like `"{...}"`,
+///   otherwise it is a string
+///
+/// Apply this to both `actual` and `expected`.  Text that isn't an HTTP response is left as-is.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+/// use snapbox::filter::FilterHttpResponse;
+///
+/// let filter = FilterHttpResponse::new().ignore_header("date");
+/// let actual = filter.filter(snapbox::Data::text(
+///     "HTTP/1.1 200 OK\r\nDate: Mon, 1 Jan 2024\r\nContent-Type: application/json\r\n\r\n{\"id\": 42}",
+/// ));
+/// let expected = filter.filter(snapbox::Data::text(
+///     "HTTP/1.1 200 OK\ncontent-type: application/json\n\n{\"id\": 42}\n",
+/// ));
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterHttpResponse {
+    ignored_headers: Vec<String>,
+}
+
+impl FilterHttpResponse {
+    /// Parse HTTP responses, keeping all headers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop a header that varies between runs, like `date`
+    ///
+    /// Header names are case-insensitive.
+    pub fn ignore_header(mut self, name: impl Into<String>) -> Self {
+        self.ignored_headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    fn parse(&self, text: &str) -> Option<serde_json::Value> {
+        let (head, body) = split_head(text);
+        let mut lines = head.lines().map(|line| line.trim_end_matches('\r'));
+
+        let status_line = lines.next()?;
+        let (version, rest) = status_line.split_once(' ')?;
+        if !version.starts_with("HTTP/") {
+            return None;
+        }
+        let (status, reason) = rest.split_once(' ').unwrap_or((rest, ""));
+        let status: u16 = status.parse().ok()?;
+
+        let mut headers = std::collections::BTreeMap::<String, Vec<String>>::new();
+        for line in lines {
+            let (name, value) = line.split_once(':')?;
+            let name = name.trim().to_ascii_lowercase();
+            if self.ignored_headers.contains(&name) {
+                continue;
+            }
+            headers
+                .entry(name)
+                .or_default()
+                .push(value.trim().to_owned());
+        }
+        let headers = headers
+            .into_iter()
+            .map(|(name, mut values)| {
+                let value = if values.len() == 1 {
+                    serde_json::Value::String(values.remove(0))
+                } else {
+                    values.into_iter().map(serde_json::Value::String).collect()
+                };
+                (name, value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        let mut response = serde_json::Map::new();
+        response.insert("version".to_owned(), version.into());
+        response.insert("status".to_owned(), status.into());
+        response.insert("reason".to_owned(), reason.into());
+        response.insert("headers".to_owned(), headers.into());
+        if let Some(body) = body.filter(|body| !body.trim().is_empty()) {
+            let body = serde_json::from_str(body)
+                .unwrap_or_else(|_| serde_json::Value::String(body.to_owned()));
+            response.insert("body".to_owned(), body);
+        }
+        Some(response.into())
+    }
+}
+
+impl Filter for FilterHttpResponse {
+    fn filter(&self, data: Data) -> Data {
+        // Text becomes json, which `filter_text` can't express
+        super::filter_inner(data, &|inner| match inner {
+            DataInner::Text(text) => match self.parse(&text) {
+                Some(response) => DataInner::Json(response),
+                None => DataInner::Text(text),
+            },
+            inner => inner,
+        })
+    }
+}
+
+/// Split the status line and headers from the body, at the first blank line
+fn split_head(text: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    for line in crate::utils::LinesWithTerminator::new(text) {
+        if line.trim_end_matches(['\n', '\r']).is_empty() {
+            return (&text[..offset], Some(&text[(offset + line.len())..]));
+        }
+        offset += line.len();
+    }
+    (text, None)
+}
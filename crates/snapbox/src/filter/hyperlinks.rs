@@ -0,0 +1,96 @@
+use super::Filter;
+use crate::Data;
+
+/// Normalize terminal hyperlinks (OSC 8 escape sequences)
+///
+/// A hyperlink is written as `ESC ] 8 ; <params> ; <url> ST <text> ESC ] 8 ; ; ST` where `ST` is
+/// either `ESC \` or `BEL`.  The URLs can vary between runs, so by default the escape sequences
+/// are removed, leaving the link text.  Alternatively, see [`FilterHyperlinks::redact_urls`].
+///
+/// Unterminated escape sequences are left as-is.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let link = "see \x1b]8;;https://example.com/123\x1b\\the docs\x1b]8;;\x1b\\";
+/// let actual = snapbox::filter::FilterHyperlinks::new().filter(link.into());
+/// assert_eq!(actual, snapbox::Data::text("see the docs"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterHyperlinks {
+    redact_urls: bool,
+}
+
+impl FilterHyperlinks {
+    /// Remove hyperlinks, keeping the link text
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep hyperlinks but replace their URLs with `[URL]`
+    ///
+    /// ```rust
+    /// use snapbox::filter::Filter as _;
+    ///
+    /// let link = "\x1b]8;;https://example.com/123\x1b\\docs\x1b]8;;\x1b\\";
+    /// let actual = snapbox::filter::FilterHyperlinks::new()
+    ///     .redact_urls(true)
+    ///     .filter(link.into());
+    /// assert_eq!(actual, snapbox::Data::text("\x1b]8;;[URL]\x1b\\docs\x1b]8;;\x1b\\"));
+    /// ```
+    pub fn redact_urls(mut self, yes: bool) -> Self {
+        self.redact_urls = yes;
+        self
+    }
+
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(OSC8_START) {
+            normalized.push_str(&rest[..start]);
+            let sequence = &rest[start..];
+            let body = &sequence[OSC8_START.len()..];
+            let Some((body_len, terminator)) = find_terminator(body) else {
+                // Unterminated, leave as-is
+                rest = sequence;
+                break;
+            };
+            let (params, url) = body[..body_len].split_once(';').unwrap_or(("", ""));
+            if self.redact_urls {
+                normalized.push_str(OSC8_START);
+                normalized.push_str(params);
+                normalized.push(';');
+                if !url.is_empty() {
+                    normalized.push_str(REDACTED_URL);
+                }
+                normalized.push_str(terminator);
+            }
+            rest = &body[(body_len + terminator.len())..];
+        }
+        normalized.push_str(rest);
+        normalized
+    }
+}
+
+impl Filter for FilterHyperlinks {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.normalize(text))
+    }
+}
+
+const OSC8_START: &str = "\x1b]8;";
+const ST: &str = "\x1b\\";
+const BEL: &str = "\x07";
+const REDACTED_URL: &str = "[URL]";
+
+/// Find the end of an escape sequence's body, returning its length and the terminator
+fn find_terminator(body: &str) -> Option<(usize, &'static str)> {
+    let st = body.find(ST).map(|index| (index, ST));
+    let bel = body.find(BEL).map(|index| (index, BEL));
+    match (st, bel) {
+        (Some(st), Some(bel)) => Some(if st.0 < bel.0 { st } else { bel }),
+        (st, bel) => st.or(bel),
+    }
+}
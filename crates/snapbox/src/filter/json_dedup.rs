@@ -0,0 +1,90 @@
+use super::pointer::JsonPointer;
+use super::Filter;
+use crate::Data;
+
+/// Remove duplicate elements from set-like json arrays
+///
+/// Tools can be inconsistent about repeating entries in arrays that are conceptually sets.  Apply
+/// this to both `actual` and `expected` so those differences don't fail the comparison.
+///
+/// Only arrays at the [pointers][DedupJsonArrays::at] are deduplicated, so lists where
+/// duplicates matter, like logs, are left alone.  The first occurrence of each element is kept.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+/// use snapbox::filter::DedupJsonArrays;
+///
+/// let filter = DedupJsonArrays::new().at("/tags");
+/// let actual = filter.filter(snapbox::Data::json(serde_json::json!({
+///     "tags": ["fast", "slow", "fast"],
+/// })));
+/// let expected = filter.filter(snapbox::Data::json(serde_json::json!({
+///     "tags": ["fast", "slow"],
+/// })));
+/// assert_eq!(actual, expected);
+/// ```
+///
+/// Note: Requires feature flag `json`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DedupJsonArrays {
+    pointers: Vec<JsonPointer>,
+}
+
+impl DedupJsonArrays {
+    /// Deduplicate no arrays until some are selected with [`DedupJsonArrays::at`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deduplicate the array at `pointer`
+    ///
+    /// `pointer` is a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) where a `*` segment
+    /// matches any key or index, like `/packages/*/features`.
+    ///
+    /// Panics if `pointer` isn't empty and doesn't start with `/`.
+    pub fn at(mut self, pointer: &str) -> Self {
+        self.pointers.push(JsonPointer::parse_or_panic(pointer));
+        self
+    }
+
+    fn dedup(&self, value: &mut serde_json::Value, path: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Array(elements) => {
+                if self.pointers.iter().any(|pointer| pointer.matches(path)) {
+                    let mut unique: Vec<serde_json::Value> = Vec::with_capacity(elements.len());
+                    for element in std::mem::take(elements) {
+                        if !unique.contains(&element) {
+                            unique.push(element);
+                        }
+                    }
+                    *elements = unique;
+                }
+                for (index, element) in elements.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    self.dedup(element, path);
+                    path.pop();
+                }
+            }
+            serde_json::Value::Object(fields) => {
+                for (key, field) in fields {
+                    path.push(key.clone());
+                    self.dedup(field, path);
+                    path.pop();
+                }
+            }
+            serde_json::Value::Null
+            | serde_json::Value::Bool(_)
+            | serde_json::Value::Number(_)
+            | serde_json::Value::String(_) => {}
+        }
+    }
+}
+
+impl Filter for DedupJsonArrays {
+    fn filter(&self, data: Data) -> Data {
+        // For json lines, pointers are relative to each line
+        super::filter_json(data, &|value| self.dedup(value, &mut Vec::new()))
+    }
+}
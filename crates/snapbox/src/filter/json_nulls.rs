@@ -0,0 +1,71 @@
+use super::Filter;
+use crate::Data;
+
+/// Remove `null`-valued fields from json
+///
+/// APIs can be inconsistent about including or omitting `null` fields.  Apply this to both
+/// `actual` and `expected` so those differences don't fail the comparison.
+///
+/// By default, `null`s in arrays are kept since removing them shifts the remaining elements.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+/// use snapbox::filter::DropJsonNulls;
+///
+/// let actual = DropJsonNulls::new().filter(snapbox::Data::json(serde_json::json!({
+///     "name": "snapbox",
+///     "description": null,
+/// })));
+/// let expected = DropJsonNulls::new().filter(snapbox::Data::json(serde_json::json!({
+///     "name": "snapbox",
+/// })));
+/// assert_eq!(actual, expected);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DropJsonNulls {
+    in_arrays: bool,
+}
+
+impl DropJsonNulls {
+    /// Remove `null` object fields, leaving arrays alone
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also remove `null` elements from arrays
+    pub fn in_arrays(mut self, yes: bool) -> Self {
+        self.in_arrays = yes;
+        self
+    }
+
+    fn drop_nulls(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Array(elements) => {
+                if self.in_arrays {
+                    elements.retain(|element| !element.is_null());
+                }
+                for element in elements {
+                    self.drop_nulls(element);
+                }
+            }
+            serde_json::Value::Object(fields) => {
+                fields.retain(|_key, field| !field.is_null());
+                for (_key, field) in fields {
+                    self.drop_nulls(field);
+                }
+            }
+            serde_json::Value::Null
+            | serde_json::Value::Bool(_)
+            | serde_json::Value::Number(_)
+            | serde_json::Value::String(_) => {}
+        }
+    }
+}
+
+impl Filter for DropJsonNulls {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_json(data, &|value| self.drop_nulls(value))
+    }
+}
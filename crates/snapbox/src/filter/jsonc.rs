@@ -0,0 +1,140 @@
+use super::Filter;
+use crate::data::DataFormat;
+use crate::data::DataInner;
+use crate::Data;
+
+/// Strip `//` and `/* */` comments from JSONC text so it can be parsed as json
+///
+/// Comments inside of json strings are left alone.  Already-parsed json is passed through.
+///
+/// This is intended as a [preprocessor][crate::Assert::preprocess] for [`DataFormat::Json`].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterJsonComments.filter("{\"a\": 1 // one\n}".into());
+/// assert_eq!(actual, snapbox::Data::text("{\"a\": 1 \n}"));
+/// ```
+pub struct FilterJsonComments;
+impl Filter for FilterJsonComments {
+    fn filter(&self, data: Data) -> Data {
+        let data = match &data.inner {
+            DataInner::Binary(_) => data.coerce_to(DataFormat::Text),
+            _ => data,
+        };
+        // Only text, as `filter_text` would also strip `//` from json strings like urls
+        super::filter_inner(data, &|inner| match inner {
+            DataInner::Text(text) => DataInner::Text(strip_json_comments(&text)),
+            inner => inner,
+        })
+    }
+}
+
+/// Leniently accept not-quite-json by stripping comments and trailing commas
+///
+/// This accepts
+/// - `//` and `/* */` comments, like [`FilterJsonComments`]
+/// - Trailing commas in arrays and objects, like `[1, 2,]`
+///
+/// Usually enabled through [`Assert::lenient_json`][crate::Assert::lenient_json].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterLenientJson.filter("[1, 2, /* three */]".into());
+/// assert_eq!(actual, snapbox::Data::text("[1, 2 ]"));
+/// ```
+pub struct FilterLenientJson;
+impl Filter for FilterLenientJson {
+    fn filter(&self, data: Data) -> Data {
+        let data = FilterJsonComments.filter(data);
+        super::filter_inner(data, &|inner| match inner {
+            DataInner::Text(text) => DataInner::Text(strip_json_trailing_commas(&text)),
+            inner => inner,
+        })
+    }
+}
+
+/// Strip commas before a closing `]` or `}`, see [`FilterLenientJson`]
+pub fn strip_json_trailing_commas(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.char_indices();
+    let mut in_string = false;
+    while let Some((index, c)) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+            output.push(c);
+        } else if c == ',' {
+            let next = input[(index + 1)..].trim_start().chars().next();
+            if !matches!(next, Some(']' | '}')) {
+                output.push(c);
+            }
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// Strip comments from JSONC, see [`FilterJsonComments`]
+///
+/// Newlines within comments are preserved so line numbers in parse errors stay accurate.
+pub fn strip_json_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+            output.push(c);
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            for next in chars.by_ref() {
+                if next == '\n' {
+                    output.push(next);
+                    break;
+                }
+            }
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut prev = None;
+            for next in chars.by_ref() {
+                if prev == Some('*') && next == '/' {
+                    break;
+                }
+                if next == '\n' {
+                    output.push(next);
+                }
+                prev = Some(next);
+            }
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
@@ -0,0 +1,103 @@
+use super::Filter;
+use crate::Data;
+
+/// Sort the lines of text
+///
+/// Output from iterating over a hash map or from parallel jobs can be in any order.  Sorting
+/// both `actual` and `expected` lets snapshots ignore the order.  Patterns, like `...`, are
+/// sorted as well, so they only work when they sort next to the lines they match.  For json, this
+/// applies to strings.
+///
+/// Usually enabled through [`Assert::sort_lines`][crate::Assert::sort_lines].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::SortLines.filter("job 2 done\njob 1 done\n".into());
+/// assert_eq!(actual, snapbox::Data::text("job 1 done\njob 2 done\n"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SortLines;
+
+impl SortLines {
+    fn sort(text: &str) -> String {
+        let (body, terminator) = match text.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (text, ""),
+        };
+        let mut lines: Vec<_> = body.split('\n').collect();
+        lines.sort_unstable();
+        let mut sorted = lines.join("\n");
+        sorted.push_str(terminator);
+        sorted
+    }
+}
+
+impl Filter for SortLines {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &Self::sort)
+    }
+}
+
+/// Truncate lines longer than a width, appending a marker
+///
+/// Minified JSON or encoded blobs on a single line make diffs hard to read and snapshots large.
+/// Truncating keeps the start of the line, which is usually enough to identify it.  Truncated
+/// lines keep their first `width` characters, followed by the marker, so truncating again
+/// leaves them unchanged and this can be applied to both `actual` and `expected`.
+///
+/// Usually enabled through [`Assert::truncate_lines`][crate::Assert::truncate_lines].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::TruncateLines::new(8).filter("short\naGVsbG8gd29ybGQ=\n".into());
+/// assert_eq!(actual, snapbox::Data::text("short\naGVsbG8g[TRUNC]\n"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TruncateLines {
+    width: usize,
+    marker: String,
+}
+
+impl TruncateLines {
+    /// Truncate lines longer than `width` characters, marking them with `[TRUNC]`
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            marker: "[TRUNC]".to_owned(),
+        }
+    }
+
+    /// The text appended to truncated lines
+    pub fn marker(mut self, marker: impl Into<String>) -> Self {
+        self.marker = marker.into();
+        self
+    }
+
+    pub(crate) fn truncate(&self, text: &str) -> String {
+        let mut truncated = String::with_capacity(text.len());
+        for line in crate::utils::LinesWithTerminator::new(text) {
+            let content = line.trim_end_matches(['\r', '\n']);
+            match content.char_indices().nth(self.width) {
+                Some((end, _)) if content[end..] != self.marker => {
+                    truncated.push_str(&content[..end]);
+                    truncated.push_str(&self.marker);
+                    truncated.push_str(&line[content.len()..]);
+                }
+                _ => truncated.push_str(line),
+            }
+        }
+        truncated
+    }
+}
+
+impl Filter for TruncateLines {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.truncate(text))
+    }
+}
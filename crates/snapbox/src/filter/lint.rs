@@ -0,0 +1,179 @@
+use super::pattern::{
+    is_line_elide, is_unordered_end, is_unordered_start, is_verbatim_end, is_verbatim_start,
+};
+use super::token::Token;
+use super::Redactions;
+
+/// Report likely mistakes in an expected pattern
+///
+/// Malformed patterns don't fail on their own, they just match literally, so a typo like
+/// `[EXEC]` shows up as a confusing mismatch.  This reports
+/// - Placeholders that aren't in `redactions` and aren't tokens like `[N:0..10]`
+/// - Tokens with invalid arguments, like `[N:ten]`
+/// - Wildcards that aren't `[..]`, `[..>]`, or `[...]`, like `[.]`, and unclosed ones, like `[..`
+/// - `...` lines with surrounding whitespace or invalid bounds, like `...{3,1}`
+/// - Unclosed `<<<verbatim` and unbalanced `[UNORDERED]` blocks
+///
+/// Text that only looks like a pattern, like a `[INFO]` log level, can be escaped as
+/// `[literal:[INFO]]`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut redactions = snapbox::Redactions::new();
+/// redactions.insert("[EXE]", std::env::consts::EXE_SUFFIX).unwrap();
+/// let errors = snapbox::filter::lint_pattern("bin[EXEC]\n...\n", &redactions).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "line 1: unknown placeholder `[EXEC]`");
+/// ```
+pub fn lint_pattern(pattern: &str, redactions: &Redactions) -> Result<(), Vec<PatternError>> {
+    let mut errors = Vec::new();
+    let mut verbatim_start = None;
+    let mut unordered_start = None;
+    for (index, line) in pattern.lines().enumerate() {
+        let line_num = index + 1;
+        if verbatim_start.is_some() {
+            if is_verbatim_end(line) {
+                verbatim_start = None;
+            }
+            continue;
+        }
+        if is_verbatim_start(line) {
+            verbatim_start = Some(line_num);
+            continue;
+        }
+        if is_unordered_start(line) {
+            if let Some(start) = unordered_start {
+                errors.push(PatternError::new(
+                    line_num,
+                    format_args!("`[UNORDERED]` within the block started on line {start}"),
+                ));
+            }
+            unordered_start = Some(line_num);
+            continue;
+        }
+        if is_unordered_end(line) {
+            if unordered_start.take().is_none() {
+                errors.push(PatternError::new(
+                    line_num,
+                    "`[/UNORDERED]` without a `[UNORDERED]`",
+                ));
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let is_elide_like =
+            trimmed == "..." || (trimmed.starts_with("...{") && trimmed.ends_with('}'));
+        if is_elide_like && !is_line_elide(line) {
+            errors.push(PatternError::new(
+                line_num,
+                "`...` must be on a line by itself, like `...` or `...{0,3}`",
+            ));
+        }
+        lint_line(line, line_num, redactions, &mut errors);
+    }
+    if let Some(start) = verbatim_start {
+        errors.push(PatternError::new(start, "`<<<verbatim` without a `>>>`"));
+    }
+    if let Some(start) = unordered_start {
+        errors.push(PatternError::new(
+            start,
+            "`[UNORDERED]` without a `[/UNORDERED]`",
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn lint_line(line: &str, line_num: usize, redactions: &Redactions, errors: &mut Vec<PatternError>) {
+    let mut index = 0;
+    while let Some(offset) = line[index..].find('[') {
+        let start = index + offset;
+        index = start + 1;
+        if let Some((_literal, len)) = super::token::parse_literal(&line[start..]) {
+            index = start + len;
+            continue;
+        }
+        // The outside of a bracketed placeholder
+        if line[index..].starts_with('[') {
+            continue;
+        }
+        #[cfg(feature = "regex")]
+        if let Some((_token, len)) = Token::parse_regex(&line[start..]) {
+            index = start + len;
+            continue;
+        }
+        let Some(end) = line[start..].find(']').map(|end| start + end + 1) else {
+            if line[start..].starts_with("[..") {
+                errors.push(PatternError::new(line_num, "unclosed `[..`"));
+            }
+            break;
+        };
+        let raw = &line[start..end];
+        let inner = &raw[1..raw.len() - 1];
+        if inner.starts_with("..") || inner == "." {
+            if !matches!(inner, ".." | "..>" | "...") {
+                errors.push(PatternError::new(
+                    line_num,
+                    format_args!("unknown wildcard `{raw}`, expected `[..]`, `[..>]`, or `[...]`"),
+                ));
+            }
+        } else if is_placeholder_like(inner)
+            && !redactions.contains(raw)
+            && Token::parse(raw).is_none()
+        {
+            let name = inner.split(':').next().unwrap_or(inner);
+            if inner.contains(':') && matches!(name, "N" | "NUM" | "ID" | "SEMVER") {
+                errors.push(PatternError::new(line_num, format_args!("invalid `{raw}`")));
+            } else {
+                errors.push(PatternError::new(
+                    line_num,
+                    format_args!("unknown placeholder `{raw}`"),
+                ));
+            }
+        }
+        index = end;
+    }
+}
+
+/// `NAME` or `NAME:<arg>`, see [`Redactions::insert`]
+fn is_placeholder_like(inner: &str) -> bool {
+    let name = inner.split(':').next().unwrap_or(inner);
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// A likely mistake in an expected pattern, see [`lint_pattern`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    line: usize,
+    message: String,
+}
+
+impl PatternError {
+    fn new(line: usize, message: impl std::fmt::Display) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    /// The line of the pattern, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PatternError {}
@@ -4,7 +4,26 @@
 //! - Making snapshots consistent across platforms or conditional compilation
 //! - Focusing snapshots on the characteristics of the data being tested
 
+mod absolute_paths;
+mod ansi;
+#[cfg(feature = "json")]
+mod http;
+mod hyperlinks;
+#[cfg(feature = "json")]
+mod json_dedup;
+#[cfg(feature = "json")]
+mod json_nulls;
+#[cfg(feature = "json")]
+mod jsonc;
+mod lines;
+mod lint;
+mod numbers;
+mod panic;
 mod pattern;
+#[cfg(feature = "json")]
+mod pointer;
+mod preprocess;
+mod quotes;
 mod redactions;
 #[cfg(test)]
 mod test;
@@ -12,23 +31,81 @@ mod test;
 mod test_redactions;
 #[cfg(test)]
 mod test_unordered_redactions;
+mod token;
+#[cfg(feature = "unicode-normalization")]
+mod unicode;
+mod whitespace;
 
 use crate::data::DataInner;
 use crate::Data;
 
+pub use absolute_paths::FilterAbsolutePaths;
+pub use ansi::StripAnsi;
+#[cfg(feature = "json")]
+pub use http::FilterHttpResponse;
+pub use hyperlinks::FilterHyperlinks;
+#[cfg(feature = "json")]
+pub use json_dedup::DedupJsonArrays;
+#[cfg(feature = "json")]
+pub use json_nulls::DropJsonNulls;
+#[cfg(feature = "json")]
+pub use jsonc::strip_json_comments;
+#[cfg(feature = "json")]
+pub use jsonc::strip_json_trailing_commas;
+#[cfg(feature = "json")]
+pub use jsonc::FilterJsonComments;
+#[cfg(feature = "json")]
+pub use jsonc::FilterLenientJson;
+pub use lines::SortLines;
+pub use lines::TruncateLines;
+pub use lint::lint_pattern;
+pub use lint::PatternError;
+pub use numbers::normalize_locale_numbers;
+pub use numbers::normalize_numbers;
+pub use numbers::FilterLocaleNumbers;
+pub use numbers::FilterNumbers;
+pub use numbers::RoundSignificantFigures;
+pub use panic::FilterPanic;
+pub(crate) use pattern::capture_str_to_redactions;
+pub(crate) use pattern::is_line_elide;
+pub(crate) use pattern::resolve_str_to_redactions;
+pub(crate) use pattern::split_bounded_lines;
 pub use pattern::NormalizeToExpected;
+pub(crate) use pattern::StreamMatcher;
+#[cfg(feature = "json")]
+pub(crate) use pointer::JsonPointer;
+pub(crate) use preprocess::FilterChain;
+pub(crate) use preprocess::Preprocessors;
+pub use quotes::FilterQuotes;
+pub(crate) use redactions::Document;
 pub use redactions::RedactedValue;
+pub use redactions::RedactionError;
 pub use redactions::Redactions;
+#[cfg(feature = "unicode-normalization")]
+pub use unicode::NormalizeUnicode;
+pub use whitespace::FilterTrailingWhitespace;
+pub use whitespace::FilterWhitespace;
 
+/// Transform [`Data`], like to normalize it before comparing
+///
+/// Custom filters can be added to an [`Assert`][crate::Assert] with
+/// [`Assert::filter`][crate::Assert::filter].
 pub trait Filter {
     fn filter(&self, data: Data) -> Data;
 }
 
+impl<F: Filter + ?Sized> Filter for Box<F> {
+    fn filter(&self, data: Data) -> Data {
+        (**self).filter(data)
+    }
+}
+
 pub struct FilterNewlines;
 impl Filter for FilterNewlines {
     fn filter(&self, data: Data) -> Data {
         let source = data.source;
         let filters = data.filters;
+        let meta = data.meta;
         let inner = match data.inner {
             DataInner::Error(err) => DataInner::Error(err),
             DataInner::Binary(bin) => DataInner::Binary(bin),
@@ -58,6 +135,7 @@ impl Filter for FilterNewlines {
             inner,
             source,
             filters,
+            meta,
         }
     }
 }
@@ -76,6 +154,7 @@ impl Filter for FilterPaths {
     fn filter(&self, data: Data) -> Data {
         let source = data.source;
         let filters = data.filters;
+        let meta = data.meta;
         let inner = match data.inner {
             DataInner::Error(err) => DataInner::Error(err),
             DataInner::Binary(bin) => DataInner::Binary(bin),
@@ -105,6 +184,7 @@ impl Filter for FilterPaths {
             inner,
             source,
             filters,
+            meta,
         }
     }
 }
@@ -123,35 +203,133 @@ fn normalize_paths_chars(data: impl Iterator<Item = char>) -> impl Iterator<Item
     data.map(|c| if c == '\\' { '/' } else { c })
 }
 
-struct NormalizeRedactions<'r> {
-    redactions: &'r Redactions,
+/// Where to normalize path separators, see [`Assert::path_separators`][crate::Assert::path_separators]
+///
+/// JSON strings are always quoted, so [`PathSeparators::InQuotes`] normalizes all of them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathSeparators {
+    /// Replace every `\` with `/`, like [`FilterPaths`]
+    #[default]
+    Everywhere,
+    /// Only within `"`-quoted text, where `\\` is one escaped separator
+    ///
+    /// Other escapes, like `\"` and `\n`, are left alone.
+    InQuotes,
+    /// Leave `\` as-is
+    Never,
+}
+
+impl Filter for PathSeparators {
+    fn filter(&self, data: Data) -> Data {
+        match self {
+            Self::Everywhere => FilterPaths.filter(data),
+            Self::InQuotes => {
+                let source = data.source;
+                let filters = data.filters;
+                let meta = data.meta;
+                let inner = match data.inner {
+                    DataInner::Error(err) => DataInner::Error(err),
+                    DataInner::Binary(bin) => DataInner::Binary(bin),
+                    DataInner::Text(text) => DataInner::Text(normalize_quoted_paths(&text)),
+                    #[cfg(feature = "json")]
+                    DataInner::Json(value) => {
+                        let mut value = value;
+                        normalize_json_string(&mut value, &normalize_paths);
+                        DataInner::Json(value)
+                    }
+                    #[cfg(feature = "json")]
+                    DataInner::JsonLines(value) => {
+                        let mut value = value;
+                        normalize_json_string(&mut value, &normalize_paths);
+                        DataInner::JsonLines(value)
+                    }
+                    #[cfg(feature = "term-svg")]
+                    DataInner::TermSvg(text) => DataInner::TermSvg(normalize_quoted_paths(&text)),
+                };
+                Data {
+                    inner,
+                    source,
+                    filters,
+                    meta,
+                }
+            }
+            Self::Never => data,
+        }
+    }
+}
+
+/// Normalize escaped path separators within `"`-quoted text
+///
+/// Quotes end at the end of the line.
+fn normalize_quoted_paths(data: &str) -> String {
+    let mut normalized = String::with_capacity(data.len());
+    let mut in_quotes = false;
+    let mut chars = data.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' => in_quotes = false,
+            '\\' if in_quotes => match chars.next() {
+                Some('\\') => {
+                    normalized.push('/');
+                    continue;
+                }
+                Some(next) => {
+                    normalized.push(c);
+                    if next == '\n' {
+                        in_quotes = false;
+                    }
+                    normalized.push(next);
+                    continue;
+                }
+                None => {}
+            },
+            _ => {}
+        }
+        normalized.push(c);
+    }
+    normalized
+}
+
+pub(crate) struct NormalizeRedactions<'r> {
+    pub(crate) redactions: &'r Redactions,
+    pub(crate) case_insensitive: bool,
 }
 impl Filter for NormalizeRedactions<'_> {
     fn filter(&self, data: Data) -> Data {
         let source = data.source;
         let filters = data.filters;
+        let meta = data.meta;
         let inner = match data.inner {
             DataInner::Error(err) => DataInner::Error(err),
             DataInner::Binary(bin) => DataInner::Binary(bin),
             DataInner::Text(text) => {
-                let lines = self.redactions.redact(&text);
+                let lines = self.redactions.redact_case(&text, self.case_insensitive);
                 DataInner::Text(lines)
             }
             #[cfg(feature = "json")]
             DataInner::Json(value) => {
                 let mut value = value;
-                normalize_json_string(&mut value, &|s| self.redactions.redact(s));
+                self.redactions.redact_json(&mut value);
                 DataInner::Json(value)
             }
             #[cfg(feature = "json")]
             DataInner::JsonLines(value) => {
                 let mut value = value;
-                normalize_json_string(&mut value, &|s| self.redactions.redact(s));
+                // Each line is its own document
+                if let serde_json::Value::Array(lines) = &mut value {
+                    for line in lines {
+                        self.redactions.redact_json(line);
+                    }
+                } else {
+                    self.redactions.redact_json(&mut value);
+                }
                 DataInner::JsonLines(value)
             }
             #[cfg(feature = "term-svg")]
             DataInner::TermSvg(text) => {
-                let lines = self.redactions.redact(&text);
+                let lines = self.redactions.redact_case(&text, self.case_insensitive);
                 DataInner::TermSvg(lines)
             }
         };
@@ -159,10 +337,64 @@ impl Filter for NormalizeRedactions<'_> {
             inner,
             source,
             filters,
+            meta,
         }
     }
 }
 
+/// Replace the content of `data` with `op`, keeping its source, filters, and metadata
+fn filter_inner(data: Data, op: &dyn Fn(DataInner) -> DataInner) -> Data {
+    Data {
+        inner: op(data.inner),
+        source: data.source,
+        filters: data.filters,
+        meta: data.meta,
+    }
+}
+
+/// Apply `op` to all text within `data`, including json strings
+fn filter_text(data: Data, op: &dyn Fn(&str) -> String) -> Data {
+    filter_inner(data, &|inner| match inner {
+        DataInner::Error(err) => DataInner::Error(err),
+        DataInner::Binary(bin) => DataInner::Binary(bin),
+        DataInner::Text(text) => DataInner::Text(op(&text)),
+        #[cfg(feature = "json")]
+        DataInner::Json(value) => {
+            let mut value = value;
+            normalize_json_string(&mut value, op);
+            DataInner::Json(value)
+        }
+        #[cfg(feature = "json")]
+        DataInner::JsonLines(value) => {
+            let mut value = value;
+            normalize_json_string(&mut value, op);
+            DataInner::JsonLines(value)
+        }
+        #[cfg(feature = "term-svg")]
+        DataInner::TermSvg(text) => DataInner::TermSvg(op(&text)),
+    })
+}
+
+/// Apply `op` to each json document within `data`, with each line of json lines being its own
+#[cfg(feature = "json")]
+fn filter_json(data: Data, op: &dyn Fn(&mut serde_json::Value)) -> Data {
+    filter_inner(data, &|inner| match inner {
+        DataInner::Json(mut value) => {
+            op(&mut value);
+            DataInner::Json(value)
+        }
+        DataInner::JsonLines(mut value) => {
+            if let serde_json::Value::Array(lines) = &mut value {
+                for line in lines {
+                    op(line);
+                }
+            }
+            DataInner::JsonLines(value)
+        }
+        inner => inner,
+    })
+}
+
 #[cfg(feature = "structured-data")]
 fn normalize_json_string(value: &mut serde_json::Value, op: &dyn Fn(&str) -> String) {
     match value {
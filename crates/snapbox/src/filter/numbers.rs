@@ -0,0 +1,392 @@
+use super::Filter;
+use crate::Data;
+
+/// Canonicalize numeric literals in text
+///
+/// Tools can render the same number as `1e3`, `1000.0`, or `1000`.  This rewrites each number to
+/// a plain decimal without an exponent or insignificant zeros so these compare equal.
+///
+/// Only stand-alone numbers are rewritten, so version strings like `1.2.3`, identifiers like
+/// `x1e3`, and numbers joined to others by `-`, `:`, `,`, `=`, or `_`, like `2024-01-05`,
+/// `12:05:00`, `1,000`, or `id=0042`, are left alone.  For json, this applies to strings; json
+/// numbers are left as-is.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterNumbers.filter("took 1.50e3 ms".into());
+/// assert_eq!(actual, snapbox::Data::text("took 1500 ms"));
+/// ```
+pub struct FilterNumbers;
+impl Filter for FilterNumbers {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &normalize_numbers)
+    }
+}
+
+/// Round decimal numbers in text to a number of significant figures
+///
+/// Scientific and benchmark output can vary in the last digits.  This rounds each number with a
+/// fraction or exponent, like `3.14159265` to `3.14` for 3 significant figures, so that noise
+/// doesn't fail the comparison.  Unlike rounding to a fixed number of decimal places, this keeps
+/// the same relative precision for large and small numbers, like `123456.7` to `123000`.
+///
+/// Integers are left alone, as are version strings like `1.2.3` and identifiers like `x1.5`.
+/// Numbers with an exponent keep it, like `1.23456e-7` to `1.23e-7`.  For json, this applies to
+/// strings; json numbers are left as-is.
+///
+/// Apply this to both `actual` and `expected`.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::RoundSignificantFigures(3).filter("pi = 3.14159265".into());
+/// assert_eq!(actual, snapbox::Data::text("pi = 3.14"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RoundSignificantFigures(pub usize);
+impl Filter for RoundSignificantFigures {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| {
+            map_numbers(text, |token, number| {
+                let is_decimal = !number.fraction.is_empty() || token.len() != number.len_digits();
+                is_decimal.then(|| number.round(token, self.0)).flatten()
+            })
+        })
+    }
+}
+
+/// Canonicalize locale-specific digit grouping and decimal separators in text
+///
+/// Depending on the locale, the same number can be rendered as `1,234.5`, `1.234,5`, or
+/// `1 234,5`.  This rewrites each grouped number to use no grouping and a `.` decimal separator,
+/// like `1234.5`, so these compare equal.
+///
+/// The decimal separator comes from the locale the text was written in, like `,` for `de-DE`,
+/// rather than being guessed from each number, as `1.234` is one thousand two hundred
+/// thirty-four in one locale and one point two three four in another.  Grouping may use `,`,
+/// `.`, `'`, a space, or a (narrow) no-break space, other than the decimal separator, with groups
+/// of 3 digits.  A plain space only groups digits when the number has a decimal separator, to
+/// avoid joining neighboring numbers.
+///
+/// Numbers that don't fit these rules, like version strings `1.2.3` or addresses `10.0.0.1`, are
+/// left alone.  For json, this applies to strings; json numbers are left as-is.
+///
+/// Apply this to `actual` and write `expected` in the canonical form.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterLocaleNumbers::new(',').filter("total: 1.234,5 EUR".into());
+/// assert_eq!(actual, snapbox::Data::text("total: 1234.5 EUR"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FilterLocaleNumbers {
+    decimal_separator: char,
+}
+
+impl FilterLocaleNumbers {
+    /// Canonicalize numbers written with `decimal_separator`, usually `.` or `,`
+    pub fn new(decimal_separator: char) -> Self {
+        Self { decimal_separator }
+    }
+}
+
+impl Filter for FilterLocaleNumbers {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| {
+            normalize_locale_numbers(text, self.decimal_separator)
+        })
+    }
+}
+
+/// Canonicalize digit grouping and decimal separators, see [`FilterLocaleNumbers`]
+pub fn normalize_locale_numbers(data: &str, decimal_separator: char) -> String {
+    let mut normalized = String::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len() {
+        let rest = &data[index..];
+        let preceded_by_token = data[..index]
+            .chars()
+            .next_back()
+            .map(is_token_char)
+            .unwrap_or(false);
+        if !preceded_by_token {
+            if let Some((len, canonical)) = parse_grouped_number(rest, decimal_separator) {
+                normalized.push_str(&canonical);
+                index += len;
+                continue;
+            }
+        }
+        let c = rest.chars().next().expect("`index` is in bounds");
+        normalized.push(c);
+        index += c.len_utf8();
+    }
+    normalized
+}
+
+fn is_number_separator(c: char) -> bool {
+    matches!(c, ',' | '.' | '\'' | ' ' | '\u{a0}' | '\u{202f}')
+}
+
+/// Parse digit runs joined by separators, returning the length consumed and the canonical form
+fn parse_grouped_number(s: &str, decimal_separator: char) -> Option<(usize, String)> {
+    let mut runs: Vec<&str> = Vec::new();
+    let mut separators: Vec<char> = Vec::new();
+    let mut index = 0;
+    loop {
+        let run_len = s[index..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(s.len() - index);
+        if run_len == 0 {
+            return None;
+        }
+        runs.push(&s[index..index + run_len]);
+        index += run_len;
+
+        let mut chars = s[index..].chars();
+        match (chars.next(), chars.next()) {
+            (Some(sep), Some(next))
+                if (is_number_separator(sep) || sep == decimal_separator)
+                    && next.is_ascii_digit() =>
+            {
+                separators.push(sep);
+                index += sep.len_utf8();
+            }
+            _ => break,
+        }
+    }
+    let followed_by_token = s[index..]
+        .chars()
+        .next()
+        .map(|c| c.is_alphanumeric() || c == '_')
+        .unwrap_or(false);
+    if separators.is_empty() || followed_by_token {
+        return None;
+    }
+
+    let (integer, fraction, groups) = match separators.split_last() {
+        Some((last, groups)) if *last == decimal_separator => {
+            (&runs[..runs.len() - 1], runs.last().copied(), groups)
+        }
+        _ => (&runs[..], None, &separators[..]),
+    };
+
+    if let Some(group) = groups.first() {
+        let consistent = groups.iter().all(|g| g == group);
+        let grouping = *group != decimal_separator;
+        let sized = integer[0].len() <= 3 && integer[1..].iter().all(|run| run.len() == 3);
+        let unambiguous = *group != ' ' || fraction.is_some();
+        if !(consistent && grouping && sized && unambiguous) {
+            return None;
+        }
+    }
+
+    let mut canonical = integer.concat();
+    if let Some(fraction) = fraction {
+        canonical.push('.');
+        canonical.push_str(fraction);
+    }
+    Some((index, canonical))
+}
+
+/// Canonicalize numeric literals, see [`FilterNumbers`]
+pub fn normalize_numbers(data: &str) -> String {
+    map_numbers(data, |_, number| number.canonical())
+}
+
+/// Replace each stand-alone number in `data` with the result of `f`, if any
+fn map_numbers(data: &str, f: impl Fn(&str, &Number<'_>) -> Option<String>) -> String {
+    let mut normalized = String::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len() {
+        let rest = &data[index..];
+        let preceded_by_token = is_joined(data[..index].chars().rev());
+        if let Some(number) = (!preceded_by_token).then(|| Number::parse(rest)).flatten() {
+            let followed_by_token = is_joined(rest[number.len..].chars());
+            if !followed_by_token {
+                if let Some(replaced) = f(&rest[..number.len], &number) {
+                    normalized.push_str(&replaced);
+                } else {
+                    normalized.push_str(&rest[..number.len]);
+                }
+                index += number.len;
+                continue;
+            }
+        }
+        let c = rest.chars().next().expect("`index` is in bounds");
+        normalized.push(c);
+        index += c.len_utf8();
+    }
+    normalized
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Whether a number is part of a larger token, given the `chars` leading away from it
+///
+/// Besides [`is_token_char`], this includes separators between digits or letters, like the `-`
+/// in `2024-01` or the `=` in `id=0042`, but not punctuation like `1, 2`.
+fn is_joined(mut chars: impl Iterator<Item = char>) -> bool {
+    match chars.next() {
+        Some(c) if is_token_char(c) => true,
+        Some('-' | ':' | ',' | '=') => chars.next().map(char::is_alphanumeric).unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn is_digit_at(bytes: &[u8], index: usize) -> bool {
+    bytes.get(index).map(u8::is_ascii_digit).unwrap_or(false)
+}
+
+struct Number<'s> {
+    negative: bool,
+    integer: &'s str,
+    fraction: &'s str,
+    exponent: i64,
+    len: usize,
+}
+
+impl<'s> Number<'s> {
+    fn parse(s: &'s str) -> Option<Self> {
+        let bytes = s.as_bytes();
+        let mut index = 0;
+        let negative = bytes.first() == Some(&b'-');
+        if negative {
+            index += 1;
+        }
+
+        let integer_start = index;
+        while is_digit_at(bytes, index) {
+            index += 1;
+        }
+        let integer = &s[integer_start..index];
+        if integer.is_empty() {
+            return None;
+        }
+
+        let mut fraction = "";
+        if bytes.get(index) == Some(&b'.') && is_digit_at(bytes, index + 1) {
+            let fraction_start = index + 1;
+            index = fraction_start;
+            while is_digit_at(bytes, index) {
+                index += 1;
+            }
+            fraction = &s[fraction_start..index];
+        }
+
+        let mut exponent = 0;
+        if matches!(bytes.get(index), Some(b'e' | b'E')) {
+            let mut exponent_end = index + 1;
+            if matches!(bytes.get(exponent_end), Some(b'-' | b'+')) {
+                exponent_end += 1;
+            }
+            let digits_start = exponent_end;
+            while is_digit_at(bytes, exponent_end) {
+                exponent_end += 1;
+            }
+            if digits_start < exponent_end {
+                exponent = s[(index + 1)..exponent_end].parse().unwrap_or(i64::MAX);
+                index = exponent_end;
+            }
+        }
+
+        Some(Self {
+            negative,
+            integer,
+            fraction,
+            exponent,
+            len: index,
+        })
+    }
+
+    /// Length of the sign, integer, and fraction, excluding any exponent
+    fn len_digits(&self) -> usize {
+        let mut len = usize::from(self.negative) + self.integer.len();
+        if !self.fraction.is_empty() {
+            len += 1 + self.fraction.len();
+        }
+        len
+    }
+
+    /// Round to `figures` significant figures, in the notation of `token`
+    fn round(&self, token: &str, figures: usize) -> Option<String> {
+        let value: f64 = token.parse().ok()?;
+        if figures == 0 || value == 0.0 || !value.is_finite() {
+            return None;
+        }
+        let scientific = format!("{:.*e}", figures - 1, value);
+        if token.len() != self.len_digits() {
+            return Some(scientific);
+        }
+
+        let rounded = Number::parse(&scientific)?;
+        let digits = format!("{}{}", rounded.integer, rounded.fraction);
+        let point = rounded.integer.len() as i64 + rounded.exponent;
+        let mut plain = String::new();
+        if rounded.negative {
+            plain.push('-');
+        }
+        if point <= 0 {
+            plain.push_str("0.");
+            plain.push_str(&"0".repeat(point.unsigned_abs() as usize));
+            plain.push_str(&digits);
+        } else if (digits.len() as i64) <= point {
+            plain.push_str(&digits);
+            plain.push_str(&"0".repeat((point - digits.len() as i64) as usize));
+        } else {
+            let (integer, fraction) = digits.split_at(point as usize);
+            plain.push_str(integer);
+            plain.push('.');
+            plain.push_str(fraction);
+        }
+        Some(plain)
+    }
+
+    /// Render as a plain decimal, if reasonable
+    fn canonical(&self) -> Option<String> {
+        // Avoid rendering absurdly long numbers
+        const MAX_EXPONENT: i64 = 64;
+        if !(-MAX_EXPONENT..=MAX_EXPONENT).contains(&self.exponent) {
+            return None;
+        }
+
+        let digits = format!("{}{}", self.integer, self.fraction);
+        let point = self.integer.len() as i64 + self.exponent;
+        let (integer, fraction) = if point <= 0 {
+            let zeros = "0".repeat(point.unsigned_abs() as usize);
+            (String::new(), format!("{zeros}{digits}"))
+        } else if (digits.len() as i64) <= point {
+            let zeros = "0".repeat((point - digits.len() as i64) as usize);
+            (format!("{digits}{zeros}"), String::new())
+        } else {
+            let (integer, fraction) = digits.split_at(point as usize);
+            (integer.to_owned(), fraction.to_owned())
+        };
+        let integer = integer.trim_start_matches('0');
+        let fraction = fraction.trim_end_matches('0');
+
+        let mut canonical = String::new();
+        if self.negative && (!integer.is_empty() || !fraction.is_empty()) {
+            canonical.push('-');
+        }
+        if integer.is_empty() {
+            canonical.push('0');
+        } else {
+            canonical.push_str(integer);
+        }
+        if !fraction.is_empty() {
+            canonical.push('.');
+            canonical.push_str(fraction);
+        }
+        Some(canonical)
+    }
+}
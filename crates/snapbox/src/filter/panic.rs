@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use super::Filter;
+use crate::Data;
+
+/// Normalize Rust panic output, like from a command's stderr
+///
+/// This makes panics comparable across toolchains and `RUST_BACKTRACE` settings:
+/// - The panic header is rewritten to the `thread '<name>' panicked at <location>:` form,
+///   dropping any thread id
+/// - `note: run with RUST_BACKTRACE=...` hints are removed
+/// - Backtrace frames are reduced to their symbol and location, dropping frame numbers,
+///   addresses, and symbol hashes
+/// - Frames from the Rust runtime and standard library are removed, so short and full
+///   backtraces are the same
+/// - Paths into the cargo registry are redacted as `[CARGO_REGISTRY]`
+///
+/// Other lines are left as-is.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let stderr = "\
+/// thread 'main' (1234) panicked at src/main.rs:2:5:
+/// boom
+/// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+/// ";
+/// let actual = snapbox::filter::FilterPanic::new().filter(stderr.into());
+/// assert_eq!(actual, snapbox::Data::text("thread 'main' panicked at src/main.rs:2:5:\nboom\n"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterPanic {
+    cwd: Option<PathBuf>,
+}
+
+impl FilterPanic {
+    /// Normalize panic output without making paths relative
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The directory the command ran in
+    ///
+    /// Full backtraces report absolute paths while short backtraces report paths under the
+    /// current directory as `./<path>`.  Paths under `cwd` are made relative so these match.
+    pub fn cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        let mut lines = crate::utils::LinesWithTerminator::new(text).peekable();
+        while let Some(line) = lines.next() {
+            let (content, terminator) = split_terminator(line);
+            if let Some(header) = normalize_header(content) {
+                normalized.push_str(&header);
+                normalized.push_str(terminator);
+            } else if is_backtrace_hint(content) {
+                // Differs between `RUST_BACKTRACE` settings
+            } else if content == "stack backtrace:" {
+                normalized.push_str(line);
+                let mut frames: Vec<Frame> = Vec::new();
+                while let Some(frame_line) = lines.next_if(|line| line.starts_with(' ')) {
+                    let (frame_line, _) = split_terminator(frame_line);
+                    let frame_line = frame_line.trim();
+                    if let Some(location) = frame_line.strip_prefix("at ") {
+                        if let Some(frame) = frames.last_mut() {
+                            frame.location = Some(self.normalize_path(location));
+                        }
+                    } else if !frame_line.is_empty() {
+                        frames.push(Frame::parse(frame_line));
+                    }
+                }
+                for frame in frames.iter().filter(|frame| !frame.is_runtime()) {
+                    normalized.push_str("  ");
+                    normalized.push_str(&frame.symbol);
+                    normalized.push_str(terminator);
+                    if let Some(location) = &frame.location {
+                        normalized.push_str("    at ");
+                        normalized.push_str(location);
+                        normalized.push_str(terminator);
+                    }
+                }
+            } else {
+                normalized.push_str(line);
+            }
+        }
+        normalized
+    }
+
+    fn normalize_path(&self, path: &str) -> String {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        if let Some(cwd) = self.cwd.as_deref().and_then(|cwd| cwd.to_str()) {
+            let cwd = cwd.trim_end_matches(['/', '\\']);
+            if let Some(relative) = path
+                .strip_prefix(cwd)
+                .and_then(|path| path.strip_prefix(['/', '\\']))
+            {
+                return relative.to_owned();
+            }
+        }
+        if let Some(index) = path.find(REGISTRY_SRC) {
+            let rest = &path[(index + REGISTRY_SRC.len())..];
+            // Skip the registry's index directory
+            if let Some((_index, rest)) = rest.split_once(['/', '\\']) {
+                return format!("[CARGO_REGISTRY]/{rest}");
+            }
+        }
+        path.to_owned()
+    }
+}
+
+impl Filter for FilterPanic {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.normalize(text))
+    }
+}
+
+const REGISTRY_SRC: &str = "registry/src/";
+
+struct Frame {
+    symbol: String,
+    location: Option<String>,
+}
+
+impl Frame {
+    /// Parse `0: symbol`, `0: 0x1234 - symbol`, or an inlined `symbol`
+    fn parse(line: &str) -> Self {
+        let symbol = line
+            .split_once(": ")
+            .filter(|(index, _)| index.bytes().all(|b| b.is_ascii_digit()))
+            .map(|(_, symbol)| symbol.trim_start())
+            .unwrap_or(line);
+        let symbol = symbol
+            .strip_prefix("0x")
+            .and_then(|symbol| symbol.split_once(" - "))
+            .map(|(_address, symbol)| symbol)
+            .unwrap_or(symbol);
+        let symbol = strip_symbol_hash(symbol);
+        Self {
+            symbol: symbol.to_owned(),
+            location: None,
+        }
+    }
+
+    fn is_runtime(&self) -> bool {
+        const RUNTIME_PREFIXES: &[&str] = &[
+            "std::",
+            "core::",
+            "alloc::",
+            "test::",
+            "<std::",
+            "<core::",
+            "<alloc::",
+            "rust_begin_unwind",
+            "rust_panic",
+            "__rust",
+            "_start",
+            "__libc_start",
+            "__scrt_common_main",
+            "invoke_main",
+            "BaseThreadInitThunk",
+            "RtlUserThreadStart",
+            "<unknown>",
+        ];
+        self.symbol == "main"
+            || RUNTIME_PREFIXES
+                .iter()
+                .any(|prefix| self.symbol.starts_with(prefix))
+    }
+}
+
+/// Remove the `::h<hash>` suffix from full backtrace symbols
+fn strip_symbol_hash(symbol: &str) -> &str {
+    if let Some((name, hash)) = symbol.rsplit_once("::h") {
+        if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return name;
+        }
+    }
+    symbol
+}
+
+/// Rewrite a panic header to `thread '<name>' panicked at <location>:`
+///
+/// Before Rust 1.73, the message was on the same line: `thread '<name>' panicked at '<message>', <location>`
+fn normalize_header(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("thread '")?;
+    let (name, rest) = rest.split_once('\'')?;
+    let rest = rest.trim_start();
+    // Drop the thread id, like `(12345)`
+    let rest = match rest.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+        Some((id, rest)) if id.bytes().all(|b| b.is_ascii_digit()) => rest.trim_start(),
+        _ => rest,
+    };
+    let rest = rest.strip_prefix("panicked at ")?;
+    if let Some(location) = rest.strip_suffix(':') {
+        Some(format!("thread '{name}' panicked at {location}:"))
+    } else {
+        let (message, location) = rest.strip_prefix('\'')?.rsplit_once("', ")?;
+        Some(format!(
+            "thread '{name}' panicked at {location}:\n{message}"
+        ))
+    }
+}
+
+fn is_backtrace_hint(line: &str) -> bool {
+    line.starts_with("note: ") && line.contains("RUST_BACKTRACE")
+}
+
+fn split_terminator(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content, &line[content.len()..])
+}
@@ -1,4 +1,7 @@
-use super::{Filter, NormalizeRedactions, Redactions};
+#[cfg(feature = "json")]
+use super::pointer::JsonPointer;
+use super::token::{find, strip_prefix, Backrefs};
+use super::{Document, Filter, NormalizeRedactions, Redactions};
 use crate::data::DataInner;
 use crate::Data;
 
@@ -6,6 +9,14 @@ use crate::Data;
 pub struct NormalizeToExpected<'a> {
     substitutions: Option<&'a Redactions>,
     unordered: bool,
+    #[cfg(feature = "json")]
+    unordered_at: Vec<JsonPointer>,
+    #[cfg(feature = "json")]
+    whole_numbers: bool,
+    trim_elide_anchors: bool,
+    case_insensitive: bool,
+    tokens: bool,
+    non_empty: Option<Emptiness>,
 }
 
 impl<'a> NormalizeToExpected<'a> {
@@ -13,9 +24,33 @@ impl<'a> NormalizeToExpected<'a> {
         Self {
             substitutions: None,
             unordered: false,
+            #[cfg(feature = "json")]
+            unordered_at: Vec::new(),
+            #[cfg(feature = "json")]
+            whole_numbers: false,
+            trim_elide_anchors: false,
+            case_insensitive: false,
+            tokens: false,
+            non_empty: None,
         }
     }
 
+    /// Fail if `actual` has no content, even if `expected` would match it
+    ///
+    /// This catches commands that silently stop producing output when `expected` is all `...`.
+    ///
+    /// Whitespace is considered content, see [`NormalizeToExpected::require_non_blank`].
+    pub fn require_non_empty(mut self) -> Self {
+        self.non_empty = Some(Emptiness::Empty);
+        self
+    }
+
+    /// Fail if `actual` is empty or only whitespace, even if `expected` would match it
+    pub fn require_non_blank(mut self) -> Self {
+        self.non_empty = Some(Emptiness::Blank);
+        self
+    }
+
     /// Make unordered content comparable
     ///
     /// This is done by re-ordering `actual` according to `expected`.
@@ -24,10 +59,89 @@ impl<'a> NormalizeToExpected<'a> {
         self
     }
 
+    /// Compare the json arrays at `pointer` without regard to order
+    ///
+    /// `pointer` is a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) where a `*` segment
+    /// matches any key or index, like `/steps/*/tags`.
+    /// Arrays elsewhere are still compared positionally.
+    ///
+    /// `"{...}"` is not supported as an element of these arrays and is reported as an error.
+    ///
+    /// Panics if `pointer` isn't empty and doesn't start with `/`.
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn unordered_at(mut self, pointer: &str) -> Self {
+        self.unordered_at.push(JsonPointer::parse_or_panic(pointer));
+        self
+    }
+
+    /// Treat json integers and whole-valued floats, like `1` and `1.0`, as equal
+    ///
+    /// `actual` is rewritten to the form in `expected`.  Values are compared where `actual` and
+    /// `expected` line up: same object keys and array indices.
+    ///
+    /// This is opt-in as it would hide a change in how a value is computed.
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn equate_whole_numbers(mut self) -> Self {
+        self.whole_numbers = true;
+        self
+    }
+
+    /// Ignore trailing whitespace on the first line matched after a `...`
+    ///
+    /// The line after a `...` anchors where the elided lines end.  Trailing whitespace in `actual`,
+    /// like from padded table output, would otherwise cause the anchor to not be found and the
+    /// rest of the report to be out of alignment.
+    ///
+    /// Only applies to text, not json values, when lines are matched in order with redactions as
+    /// that is the only time a `...` has an anchor.  This is ignored without
+    /// [`NormalizeToExpected::redact`], with [`NormalizeToExpected::unordered`], and with
+    /// [`NormalizeToExpected::tokens`].
+    pub fn trim_elide_anchors(mut self) -> Self {
+        self.trim_elide_anchors = true;
+        self
+    }
+
+    /// Compare lines ignoring ASCII case, like for drive letters and paths on Windows and macOS
+    ///
+    /// Redactions still apply and literal redactions, like `[ROOT]`, also match ignoring ASCII
+    /// case.  When lines match, `actual` is rewritten to `expected`'s case.
+    ///
+    /// Applies with or without [`NormalizeToExpected::redact`], to [`NormalizeToExpected::unordered`]
+    /// lines, and to [`NormalizeToExpected::tokens`].  Only applies to text, not json values.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    /// Match text as whitespace-delimited tokens, rather than lines
+    ///
+    /// This is for free-form output, like a paragraph that may be reflowed, where line breaks
+    /// aren't meaningful.  Any run of whitespace, including a trailing newline, separates tokens
+    /// and is otherwise ignored.
+    ///
+    /// With redactions:
+    /// - `...` as its own token: match zero or more tokens
+    /// - `[..]` as its own token: match a single token
+    /// - `[..]` and placeholders within a token match as they would within a line
+    ///
+    /// When every token matches, `actual` is rewritten to `expected`, including its whitespace.
+    /// Otherwise, `actual` is rewritten up to the first token that doesn't match so the diff
+    /// starts there.
+    ///
+    /// Only applies to text.  [`NormalizeToExpected::unordered`] is ignored.
+    pub fn tokens(mut self) -> Self {
+        self.tokens = true;
+        self
+    }
+
     /// Apply built-in redactions.
     ///
     /// Built-in redactions:
-    /// - `...` on a line of its own: match multiple complete lines
+    /// - `...` on a line of its own: match zero or more complete lines
     /// - `[..]`: match multiple characters within a line
     ///
     /// Built-ins cannot automatically be applied to `actual` but are inferred from `expected`
@@ -40,7 +154,7 @@ impl<'a> NormalizeToExpected<'a> {
     /// Apply built-in and user [`Redactions`]
     ///
     /// Built-in redactions:
-    /// - `...` on a line of its own: match multiple complete lines
+    /// - `...` on a line of its own: match zero or more complete lines
     /// - `[..]`: match multiple characters within a line
     ///
     /// Built-ins cannot automatically be applied to `actual` but are inferred from `expected`
@@ -50,23 +164,79 @@ impl<'a> NormalizeToExpected<'a> {
     }
 
     pub fn normalize(&self, actual: Data, expected: &Data) -> Data {
+        if let Some(emptiness) = self.non_empty {
+            if emptiness.is(&actual) {
+                let inner = Data::error(
+                    format!("expected non-{emptiness} output, got `{actual}`"),
+                    actual.format(),
+                )
+                .inner;
+                let source = actual.source;
+                let filters = actual.filters;
+                let meta = actual.meta;
+                return Data {
+                    inner,
+                    source,
+                    filters,
+                    meta,
+                };
+            }
+        }
+
+        // Verbatim regions are compared with what was written, before redactions
+        let unredacted = match (&actual.inner, self.substitutions, self.unordered) {
+            (DataInner::Text(text), Some(_), false) if has_verbatim_region(expected) => {
+                Some(text.clone())
+            }
+            _ => None,
+        };
         let actual = if let Some(substitutions) = self.substitutions {
             NormalizeRedactions {
                 redactions: substitutions,
+                case_insensitive: self.case_insensitive,
             }
             .filter(actual)
         } else {
             actual
         };
+        #[cfg(feature = "json")]
+        let actual = if self.whole_numbers {
+            normalize_data_to_whole_numbers(actual, expected)
+        } else {
+            actual
+        };
+        #[cfg(feature = "json")]
+        let actual = if self.unordered_at.is_empty() {
+            actual
+        } else {
+            normalize_data_to_unordered_at(actual, expected, &self.unordered_at, self.substitutions)
+        };
+        if self.tokens {
+            return normalize_data_to_tokens(
+                actual,
+                expected,
+                self.substitutions,
+                self.case_insensitive,
+            );
+        }
         match (self.substitutions, self.unordered) {
+            (None, false) if self.case_insensitive => normalize_data_to_case(actual, expected),
             (None, false) => actual,
-            (Some(substitutions), false) => {
-                normalize_data_to_redactions(actual, expected, substitutions)
-            }
-            (None, true) => normalize_data_to_unordered(actual, expected),
-            (Some(substitutions), true) => {
-                normalize_data_to_unordered_redactions(actual, expected, substitutions)
-            }
+            (Some(substitutions), false) => normalize_data_to_redactions(
+                actual,
+                expected,
+                substitutions,
+                self.trim_elide_anchors,
+                self.case_insensitive,
+                unredacted.as_deref(),
+            ),
+            (None, true) => normalize_data_to_unordered(actual, expected, self.case_insensitive),
+            (Some(substitutions), true) => normalize_data_to_unordered_redactions(
+                actual,
+                expected,
+                substitutions,
+                self.case_insensitive,
+            ),
         }
     }
 }
@@ -77,15 +247,92 @@ impl Default for NormalizeToExpected<'_> {
     }
 }
 
-fn normalize_data_to_unordered(actual: Data, expected: &Data) -> Data {
+#[derive(Copy, Clone, Debug)]
+enum Emptiness {
+    Empty,
+    Blank,
+}
+
+impl Emptiness {
+    fn is(self, data: &Data) -> bool {
+        match &data.inner {
+            DataInner::Error(_) => false,
+            DataInner::Binary(bin) => bin.is_empty(),
+            _ => {
+                let rendered = data.render().unwrap_or_default();
+                match self {
+                    Self::Empty => rendered.is_empty(),
+                    Self::Blank => rendered.trim().is_empty(),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Emptiness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => "empty".fmt(f),
+            Self::Blank => "blank".fmt(f),
+        }
+    }
+}
+
+fn normalize_data_to_case(actual: Data, expected: &Data) -> Data {
+    let source = actual.source;
+    let filters = actual.filters;
+    let meta = actual.meta;
+    let inner = match (actual.inner, &expected.inner) {
+        (DataInner::Text(text), DataInner::Text(exp)) => {
+            DataInner::Text(normalize_str_to_case(&text, exp))
+        }
+        #[cfg(feature = "term-svg")]
+        (DataInner::TermSvg(text), DataInner::TermSvg(exp)) => {
+            if let (Some((header, body, footer)), Some((_, exp, _))) = (
+                crate::data::split_term_svg(&text),
+                crate::data::split_term_svg(exp),
+            ) {
+                let lines = normalize_str_to_case(body, exp);
+                DataInner::TermSvg(format!("{header}{lines}{footer}"))
+            } else {
+                DataInner::TermSvg(text)
+            }
+        }
+        (inner, _) => inner,
+    };
+    Data {
+        inner,
+        source,
+        filters,
+        meta,
+    }
+}
+
+/// Rewrite each `actual` line to the `expected` line it equals, ignoring ASCII case
+fn normalize_str_to_case(actual: &str, expected: &str) -> String {
+    if actual == expected {
+        return actual.to_owned();
+    }
+
+    let mut expected_lines = crate::utils::LinesWithTerminator::new(expected);
+    crate::utils::LinesWithTerminator::new(actual)
+        .map(|actual_line| match expected_lines.next() {
+            Some(expected_line) if actual_line.eq_ignore_ascii_case(expected_line) => expected_line,
+            _ => actual_line,
+        })
+        .collect()
+}
+
+fn normalize_data_to_unordered(actual: Data, expected: &Data, case_insensitive: bool) -> Data {
     let source = actual.source;
     let filters = actual.filters;
+    let meta = actual.meta;
     let inner = match (actual.inner, &expected.inner) {
         (DataInner::Error(err), _) => DataInner::Error(err),
         (DataInner::Binary(bin), _) => DataInner::Binary(bin),
         (DataInner::Text(text), _) => {
             if let Some(pattern) = expected.render() {
-                let lines = normalize_str_to_unordered(&text, &pattern);
+                let lines = normalize_str_to_unordered(&text, &pattern, case_insensitive);
                 DataInner::Text(lines)
             } else {
                 DataInner::Text(text)
@@ -109,7 +356,7 @@ fn normalize_data_to_unordered(actual: Data, expected: &Data) -> Data {
                 crate::data::split_term_svg(&text),
                 crate::data::split_term_svg(exp),
             ) {
-                let lines = normalize_str_to_unordered(body, exp);
+                let lines = normalize_str_to_unordered(body, exp, case_insensitive);
                 DataInner::TermSvg(format!("{header}{lines}{footer}"))
             } else {
                 DataInner::TermSvg(text)
@@ -123,6 +370,7 @@ fn normalize_data_to_unordered(actual: Data, expected: &Data) -> Data {
         inner,
         source,
         filters,
+        meta,
     }
 }
 
@@ -132,7 +380,7 @@ fn normalize_value_to_unordered(actual: &mut serde_json::Value, expected: &serde
 
     match (actual, expected) {
         (String(act), String(exp)) => {
-            *act = normalize_str_to_unordered(act, exp);
+            *act = normalize_str_to_unordered(act, exp, false);
         }
         (Array(act), Array(exp)) => {
             let mut actual_values = std::mem::take(act);
@@ -168,7 +416,221 @@ fn normalize_value_to_unordered(actual: &mut serde_json::Value, expected: &serde
     }
 }
 
-fn normalize_str_to_unordered(actual: &str, expected: &str) -> String {
+#[cfg(feature = "json")]
+fn normalize_data_to_whole_numbers(actual: Data, expected: &Data) -> Data {
+    let source = actual.source;
+    let filters = actual.filters;
+    let meta = actual.meta;
+    let inner = match (actual.inner, &expected.inner) {
+        (DataInner::Json(mut value), DataInner::Json(exp)) => {
+            normalize_value_to_whole_numbers(&mut value, exp);
+            DataInner::Json(value)
+        }
+        (DataInner::JsonLines(mut value), DataInner::JsonLines(exp)) => {
+            normalize_value_to_whole_numbers(&mut value, exp);
+            DataInner::JsonLines(value)
+        }
+        (inner, _) => inner,
+    };
+    Data {
+        inner,
+        source,
+        filters,
+        meta,
+    }
+}
+
+#[cfg(feature = "json")]
+fn normalize_value_to_whole_numbers(actual: &mut serde_json::Value, expected: &serde_json::Value) {
+    use serde_json::Value::{Array, Number, Object};
+
+    match (actual, expected) {
+        (Number(act), Number(exp)) => {
+            if is_same_whole_number(act, exp) {
+                *act = exp.clone();
+            }
+        }
+        (Array(act), Array(exp)) => {
+            for (actual_elem, expected_elem) in act.iter_mut().zip(exp) {
+                normalize_value_to_whole_numbers(actual_elem, expected_elem);
+            }
+        }
+        (Object(act), Object(exp)) => {
+            for (key, actual_value) in act.iter_mut() {
+                if let Some(expected_value) = exp.get(key) {
+                    normalize_value_to_whole_numbers(actual_value, expected_value);
+                }
+            }
+        }
+        (_, _) => {}
+    }
+}
+
+/// Whether one is an integer and the other is a float of exactly the same value
+#[cfg(feature = "json")]
+fn is_same_whole_number(left: &serde_json::Number, right: &serde_json::Number) -> bool {
+    let as_int = |number: &serde_json::Number| {
+        number
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| number.as_u64().map(i128::from))
+    };
+    let (int, float) = match (as_int(left), as_int(right)) {
+        (Some(int), None) => (int, right.as_f64()),
+        (None, Some(int)) => (int, left.as_f64()),
+        _ => return false,
+    };
+    // Round-trip to avoid treating large integers as equal to the nearest float
+    float
+        .map(|float| float.fract() == 0.0 && float == int as f64 && float as i128 == int)
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "json")]
+fn normalize_data_to_unordered_at(
+    actual: Data,
+    expected: &Data,
+    pointers: &[JsonPointer],
+    substitutions: Option<&Redactions>,
+) -> Data {
+    let source = actual.source;
+    let filters = actual.filters;
+    let meta = actual.meta;
+    let inner = match (actual.inner, &expected.inner) {
+        (DataInner::Json(mut value), DataInner::Json(exp)) => {
+            match normalize_value_to_unordered_at(
+                &mut value,
+                exp,
+                pointers,
+                substitutions,
+                &mut Vec::new(),
+            ) {
+                Ok(()) => DataInner::Json(value),
+                Err(err) => Data::error(err, crate::data::DataFormat::Json).inner,
+            }
+        }
+        (DataInner::JsonLines(mut value), DataInner::JsonLines(exp)) => {
+            match normalize_value_to_unordered_at(
+                &mut value,
+                exp,
+                pointers,
+                substitutions,
+                &mut Vec::new(),
+            ) {
+                Ok(()) => DataInner::JsonLines(value),
+                Err(err) => Data::error(err, crate::data::DataFormat::JsonLines).inner,
+            }
+        }
+        (inner, _) => inner,
+    };
+    Data {
+        inner,
+        source,
+        filters,
+        meta,
+    }
+}
+
+/// Re-order the arrays at `pointers` in `actual` according to `expected`
+///
+/// Elements that don't match any in `expected` fill the remaining positions in their original
+/// order so the diff lines up.
+#[cfg(feature = "json")]
+fn normalize_value_to_unordered_at(
+    actual: &mut serde_json::Value,
+    expected: &serde_json::Value,
+    pointers: &[JsonPointer],
+    substitutions: Option<&Redactions>,
+    path: &mut Vec<String>,
+) -> Result<(), String> {
+    use serde_json::Value::{Array, Object};
+
+    match (actual, expected) {
+        (Array(act), Array(exp)) => {
+            if let Some(pointer) = pointers.iter().find(|pointer| pointer.matches(path)) {
+                if exp
+                    .iter()
+                    .any(|expected_value| expected_value == VALUE_WILDCARD)
+                {
+                    return Err(format!(
+                        "`{VALUE_WILDCARD}` is not supported in unordered array `{pointer}`"
+                    ));
+                }
+                let mut actual_values = std::mem::take(act);
+                let mut slots = Vec::with_capacity(exp.len());
+                for (index, expected_value) in exp.iter().enumerate() {
+                    path.push(index.to_string());
+                    let mut matched = None;
+                    for (actual_index, actual_value) in actual_values.iter().enumerate() {
+                        let mut actual_value = actual_value.clone();
+                        normalize_value_to_unordered_at(
+                            &mut actual_value,
+                            expected_value,
+                            pointers,
+                            substitutions,
+                            path,
+                        )?;
+                        let is_match = match substitutions {
+                            Some(substitutions) => value_matches_redactions(
+                                &actual_value,
+                                expected_value,
+                                substitutions,
+                            ),
+                            None => actual_value == *expected_value,
+                        };
+                        if is_match {
+                            matched = Some((actual_index, actual_value));
+                            break;
+                        }
+                    }
+                    path.pop();
+                    slots.push(matched.map(|(actual_index, actual_value)| {
+                        actual_values.remove(actual_index);
+                        actual_value
+                    }));
+                }
+                let mut unmatched = actual_values.into_iter();
+                for slot in slots {
+                    if let Some(value) = slot.or_else(|| unmatched.next()) {
+                        act.push(value);
+                    }
+                }
+                act.extend(unmatched);
+            } else {
+                for (index, (actual_value, expected_value)) in act.iter_mut().zip(exp).enumerate() {
+                    path.push(index.to_string());
+                    normalize_value_to_unordered_at(
+                        actual_value,
+                        expected_value,
+                        pointers,
+                        substitutions,
+                        path,
+                    )?;
+                    path.pop();
+                }
+            }
+        }
+        (Object(act), Object(exp)) => {
+            for (key, actual_value) in act.iter_mut() {
+                if let Some(expected_value) = exp.get(key) {
+                    path.push(key.clone());
+                    normalize_value_to_unordered_at(
+                        actual_value,
+                        expected_value,
+                        pointers,
+                        substitutions,
+                        path,
+                    )?;
+                    path.pop();
+                }
+            }
+        }
+        (_, _) => {}
+    }
+    Ok(())
+}
+
+fn normalize_str_to_unordered(actual: &str, expected: &str, case_insensitive: bool) -> String {
     if actual == expected {
         return actual.to_owned();
     }
@@ -179,7 +641,10 @@ fn normalize_str_to_unordered(actual: &str, expected: &str) -> String {
     expected_lines.retain(|expected_line| {
         let mut matched = false;
         actual_lines.retain(|actual_line| {
-            if !matched && actual_line == expected_line {
+            if !matched
+                && (actual_line == expected_line
+                    || (case_insensitive && actual_line.eq_ignore_ascii_case(expected_line)))
+            {
                 matched = true;
                 false
             } else {
@@ -203,19 +668,92 @@ const KEY_WILDCARD: &str = "...";
 #[cfg(feature = "structured-data")]
 const VALUE_WILDCARD: &str = "{...}";
 
+#[cfg(feature = "structured-data")]
+const DURATION_TOKEN: &str = "{duration}";
+
+/// `{"$unordered": [...]}` matches an array with the same elements in any order
+#[cfg(feature = "structured-data")]
+const UNORDERED_KEY: &str = "$unordered";
+
+/// The elements of an `{"$unordered": [...]}` marker
+#[cfg(feature = "structured-data")]
+fn unordered_marker(
+    expected: &serde_json::Map<String, serde_json::Value>,
+) -> Option<&[serde_json::Value]> {
+    if expected.len() != 1 {
+        return None;
+    }
+    expected.get(UNORDERED_KEY)?.as_array().map(Vec::as_slice)
+}
+
+/// Whether `actual` is a duration, as matched by `"{duration}"`
+///
+/// Durations are either:
+/// - A non-negative number, like elapsed seconds or milliseconds
+/// - A `{"secs": <int>, "nanos": <int>}` object, like `serde` writes a `std::time::Duration`
+#[cfg(feature = "structured-data")]
+fn is_duration(actual: &serde_json::Value) -> bool {
+    match actual {
+        serde_json::Value::Number(number) => number.as_f64().map(|n| 0.0 <= n).unwrap_or(false),
+        serde_json::Value::Object(fields) => {
+            let secs = fields.get("secs").and_then(|secs| secs.as_u64());
+            let nanos = fields.get("nanos").and_then(|nanos| nanos.as_u64());
+            fields.len() == 2 && secs.is_some() && nanos.map(|n| n < 1_000_000_000).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `expected` is a typed wildcard, like `"{number}"`, that `actual` matches
+#[cfg(feature = "structured-data")]
+fn matches_type_token(actual: &serde_json::Value, expected: &str) -> bool {
+    match expected {
+        "{number}" => actual.is_number(),
+        "{string}" => actual.is_string(),
+        "{bool}" => actual.is_boolean(),
+        "{array}" => actual.is_array(),
+        "{object}" => actual.is_object(),
+        _ => false,
+    }
+}
+
+/// Whether `expected` is a `"{regex:<pattern>}"` token that `actual` matches
+///
+/// Invalid regexes are treated as literal strings so the diff shows them.  Each pattern is
+/// compiled once, see [`cached_regex`][super::token::cached_regex].
+#[cfg(all(feature = "structured-data", feature = "regex"))]
+fn value_regex_matches(actual: &str, expected: &str) -> bool {
+    let Some(pattern) = expected
+        .strip_prefix("{regex:")
+        .and_then(|pattern| pattern.strip_suffix('}'))
+    else {
+        return false;
+    };
+    super::token::cached_regex(pattern)
+        .map(|regex| regex.is_match(actual))
+        .unwrap_or(false)
+}
+
 fn normalize_data_to_unordered_redactions(
     actual: Data,
     expected: &Data,
     substitutions: &Redactions,
+    case_insensitive: bool,
 ) -> Data {
     let source = actual.source;
     let filters = actual.filters;
+    let meta = actual.meta;
     let inner = match (actual.inner, &expected.inner) {
         (DataInner::Error(err), _) => DataInner::Error(err),
         (DataInner::Binary(bin), _) => DataInner::Binary(bin),
         (DataInner::Text(text), _) => {
             if let Some(pattern) = expected.render() {
-                let lines = normalize_str_to_unordered_redactions(&text, &pattern, substitutions);
+                let lines = normalize_str_to_unordered_redactions(
+                    &text,
+                    &pattern,
+                    substitutions,
+                    case_insensitive,
+                );
                 DataInner::Text(lines)
             } else {
                 DataInner::Text(text)
@@ -239,7 +777,12 @@ fn normalize_data_to_unordered_redactions(
                 crate::data::split_term_svg(&text),
                 crate::data::split_term_svg(exp),
             ) {
-                let lines = normalize_str_to_unordered_redactions(body, exp, substitutions);
+                let lines = normalize_str_to_unordered_redactions(
+                    body,
+                    exp,
+                    substitutions,
+                    case_insensitive,
+                );
                 DataInner::TermSvg(format!("{header}{lines}{footer}"))
             } else {
                 DataInner::TermSvg(text)
@@ -253,6 +796,7 @@ fn normalize_data_to_unordered_redactions(
         inner,
         source,
         filters,
+        meta,
     }
 }
 
@@ -268,8 +812,18 @@ fn normalize_value_to_unordered_redactions(
         (act, String(exp)) if exp == VALUE_WILDCARD => {
             *act = serde_json::json!(VALUE_WILDCARD);
         }
+        (act, String(exp)) if exp == DURATION_TOKEN && is_duration(act) => {
+            *act = serde_json::json!(DURATION_TOKEN);
+        }
+        (act, String(exp)) if matches_type_token(act, exp) => {
+            *act = String(exp.clone());
+        }
+        #[cfg(feature = "regex")]
+        (String(act), String(exp)) if value_regex_matches(act, exp) => {
+            *act = exp.clone();
+        }
         (String(act), String(exp)) => {
-            *act = normalize_str_to_unordered_redactions(act, exp, substitutions);
+            *act = normalize_str_to_unordered_redactions(act, exp, substitutions, false);
         }
         (Array(act), Array(exp)) => {
             *act = normalize_array_to_unordered_redactions(act, exp, substitutions);
@@ -350,6 +904,7 @@ fn normalize_str_to_unordered_redactions(
     actual: &str,
     expected: &str,
     substitutions: &Redactions,
+    case_insensitive: bool,
 ) -> String {
     if actual == expected {
         return actual.to_owned();
@@ -364,9 +919,30 @@ fn normalize_str_to_unordered_redactions(
         if is_line_elide(expected_line) {
             matched = true;
             elided = true;
+        } else if let Some(optional) = optional_line(expected_line) {
+            let mut found = false;
+            actual_lines.retain(|actual_line| {
+                if !found
+                    && line_matches_case(actual_line, optional, substitutions, case_insensitive)
+                {
+                    found = true;
+                    false
+                } else {
+                    true
+                }
+            });
+            // Whether present or not, this is a match
+            matched = true;
         } else {
             actual_lines.retain(|actual_line| {
-                if !matched && line_matches(actual_line, expected_line, substitutions) {
+                if !matched
+                    && line_matches_case(
+                        actual_line,
+                        expected_line,
+                        substitutions,
+                        case_insensitive,
+                    )
+                {
                     matched = true;
                     false
                 } else {
@@ -388,22 +964,160 @@ fn normalize_str_to_unordered_redactions(
     normalized.join("")
 }
 
-fn normalize_data_to_redactions(actual: Data, expected: &Data, substitutions: &Redactions) -> Data {
+fn normalize_data_to_tokens(
+    actual: Data,
+    expected: &Data,
+    substitutions: Option<&Redactions>,
+    case_insensitive: bool,
+) -> Data {
     let source = actual.source;
     let filters = actual.filters;
-    let inner = match (actual.inner, &expected.inner) {
-        (DataInner::Error(err), _) => DataInner::Error(err),
-        (DataInner::Binary(bin), _) => DataInner::Binary(bin),
-        (DataInner::Text(text), _) => {
-            if let Some(pattern) = expected.render() {
-                let lines = normalize_str_to_redactions(&text, &pattern, substitutions);
-                DataInner::Text(lines)
-            } else {
-                DataInner::Text(text)
+    let meta = actual.meta;
+    let inner =
+        match (actual.inner, &expected.inner) {
+            (DataInner::Text(text), DataInner::Text(exp)) => DataInner::Text(
+                normalize_str_to_tokens(&text, exp, substitutions, case_insensitive),
+            ),
+            (inner, _) => inner,
+        };
+    Data {
+        inner,
+        source,
+        filters,
+        meta,
+    }
+}
+
+fn normalize_str_to_tokens(
+    actual: &str,
+    expected: &str,
+    substitutions: Option<&Redactions>,
+    case_insensitive: bool,
+) -> String {
+    let token_matches = |actual: &str, expected: &str| match substitutions {
+        Some(redactions) => line_matches_case(actual, expected, redactions, case_insensitive),
+        None => actual == expected || (case_insensitive && actual.eq_ignore_ascii_case(expected)),
+    };
+    let is_elide = |token: &str| substitutions.is_some() && is_line_elide(token);
+
+    let actual_tokens = split_tokens(actual);
+    let expected_tokens = split_tokens(expected);
+    let mut normalized = String::new();
+    let mut actual_index = 0;
+    let mut expected_index = 0;
+    while let Some((separator, expected_token)) = expected_tokens.get(expected_index).copied() {
+        expected_index += 1;
+        if is_elide(expected_token) {
+            let Some((_, next_expected_token)) = expected_tokens.get(expected_index).copied()
+            else {
+                // Stop as elide consumes to end
+                normalized.push_str(separator);
+                normalized.push_str(expected_token);
+                actual_index = actual_tokens.len();
+                break;
+            };
+            let Some(index_offset) = actual_tokens[actual_index..]
+                .iter()
+                .position(|(_, actual_token)| token_matches(actual_token, next_expected_token))
+            else {
+                // Give up as we can't find where the elide ends
+                expected_index -= 1;
+                break;
+            };
+            normalized.push_str(separator);
+            normalized.push_str(expected_token);
+            actual_index += index_offset;
+        } else {
+            match actual_tokens.get(actual_index) {
+                Some((_, actual_token)) if token_matches(actual_token, expected_token) => {
+                    normalized.push_str(separator);
+                    normalized.push_str(expected_token);
+                    actual_index += 1;
+                }
+                _ => {
+                    expected_index -= 1;
+                    break;
+                }
             }
         }
-        #[cfg(feature = "json")]
-        (DataInner::Json(value), DataInner::Json(exp)) => {
+    }
+
+    if expected_index == expected_tokens.len() && actual_index == actual_tokens.len() {
+        normalized.push_str(trailing_whitespace(expected));
+    } else {
+        for (separator, actual_token) in &actual_tokens[actual_index..] {
+            normalized.push_str(separator);
+            normalized.push_str(actual_token);
+        }
+        normalized.push_str(trailing_whitespace(actual));
+    }
+    normalized
+}
+
+/// Split `text` into tokens, each with the whitespace before it
+fn split_tokens(text: &str) -> Vec<(&str, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    loop {
+        let token_start = rest.len() - rest.trim_start().len();
+        if token_start == rest.len() {
+            break;
+        }
+        let token_len = rest[token_start..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - token_start);
+        let token_end = token_start + token_len;
+        tokens.push((&rest[..token_start], &rest[token_start..token_end]));
+        rest = &rest[token_end..];
+    }
+    tokens
+}
+
+fn trailing_whitespace(text: &str) -> &str {
+    &text[text.trim_end().len()..]
+}
+
+fn has_verbatim_region(expected: &Data) -> bool {
+    match &expected.inner {
+        DataInner::Text(text) => {
+            crate::utils::LinesWithTerminator::new(text).any(is_verbatim_start)
+        }
+        _ => false,
+    }
+}
+
+/// `unredacted` is `actual`'s text before redactions, for comparing verbatim regions
+fn normalize_data_to_redactions(
+    actual: Data,
+    expected: &Data,
+    substitutions: &Redactions,
+    trim_elide_anchors: bool,
+    case_insensitive: bool,
+    unredacted: Option<&str>,
+) -> Data {
+    let source = actual.source;
+    let filters = actual.filters;
+    let meta = actual.meta;
+    let inner = match (actual.inner, &expected.inner) {
+        (DataInner::Error(err), _) => DataInner::Error(err),
+        (DataInner::Binary(bin), _) => DataInner::Binary(bin),
+        (DataInner::Text(text), _) => {
+            if let Some(pattern) = expected.render() {
+                let lines = normalize_str_to_redactions(
+                    &text,
+                    &pattern,
+                    substitutions,
+                    trim_elide_anchors,
+                    case_insensitive,
+                    unredacted,
+                );
+                DataInner::Text(lines)
+            } else {
+                DataInner::Text(text)
+            }
+        }
+        #[cfg(feature = "json")]
+        (DataInner::Json(value), DataInner::Json(exp)) => {
             let mut value = value;
             normalize_value_to_redactions(&mut value, exp, substitutions);
             DataInner::Json(value)
@@ -420,7 +1134,14 @@ fn normalize_data_to_redactions(actual: Data, expected: &Data, substitutions: &R
                 crate::data::split_term_svg(&text),
                 crate::data::split_term_svg(exp),
             ) {
-                let lines = normalize_str_to_redactions(body, exp, substitutions);
+                let lines = normalize_str_to_redactions(
+                    body,
+                    exp,
+                    substitutions,
+                    trim_elide_anchors,
+                    case_insensitive,
+                    None,
+                );
                 DataInner::TermSvg(format!("{header}{lines}{footer}"))
             } else {
                 DataInner::TermSvg(text)
@@ -434,6 +1155,7 @@ fn normalize_data_to_redactions(actual: Data, expected: &Data, substitutions: &R
         inner,
         source,
         filters,
+        meta,
     }
 }
 
@@ -449,12 +1171,30 @@ fn normalize_value_to_redactions(
         (act, String(exp)) if exp == VALUE_WILDCARD => {
             *act = serde_json::json!(VALUE_WILDCARD);
         }
+        (act, String(exp)) if exp == DURATION_TOKEN && is_duration(act) => {
+            *act = serde_json::json!(DURATION_TOKEN);
+        }
+        (act, String(exp)) if matches_type_token(act, exp) => {
+            *act = String(exp.clone());
+        }
+        #[cfg(feature = "regex")]
+        (String(act), String(exp)) if value_regex_matches(act, exp) => {
+            *act = exp.clone();
+        }
         (String(act), String(exp)) => {
-            *act = normalize_str_to_redactions(act, exp, substitutions);
+            *act = normalize_str_to_redactions(act, exp, substitutions, false, false, None);
         }
         (Array(act), Array(exp)) => {
             *act = normalize_array_to_redactions(act, exp, substitutions);
         }
+        (act @ Array(_), Object(exp)) if unordered_marker(exp).is_some() => {
+            let exp = unordered_marker(exp).expect("checked above");
+            let Array(elems) = std::mem::take(act) else {
+                unreachable!("matched above");
+            };
+            let elems = normalize_array_to_unordered(elems, exp, substitutions);
+            *act = serde_json::json!({ UNORDERED_KEY: elems });
+        }
         (Object(act), Object(exp)) => {
             let has_key_wildcard =
                 exp.get(KEY_WILDCARD).and_then(|v| v.as_str()) == Some(VALUE_WILDCARD);
@@ -486,29 +1226,55 @@ fn normalize_array_to_redactions(
 
     let mut normalized: Vec<serde_json::Value> = Vec::new();
     let mut actual_index = 0;
-    let mut expected = expected.iter().peekable();
-    while let Some(expected_elem) = expected.next() {
+    let mut expected_index = 0;
+    while let Some(expected_elem) = expected.get(expected_index) {
+        expected_index += 1;
         if expected_elem == VALUE_WILDCARD {
-            let Some(next_expected_elem) = expected.peek() else {
+            let remaining = &expected[expected_index..];
+            if remaining.is_empty() {
                 // Stop as elide consumes to end
                 normalized.push(expected_elem.clone());
                 actual_index = actual.len();
                 break;
+            }
+            // Elements between this elide and the next one (or the end) must match as a group
+            let group_len = remaining
+                .iter()
+                .position(|elem| elem == VALUE_WILDCARD)
+                .unwrap_or(remaining.len());
+            let group = &remaining[..group_len];
+            let group_matches = |start: usize| {
+                actual[start..].len() >= group.len()
+                    && actual[start..]
+                        .iter()
+                        .zip(group)
+                        .all(|(actual_elem, expected_elem)| {
+                            value_matches_redactions(actual_elem, expected_elem, redactions)
+                        })
             };
-            let Some(index_offset) = actual[actual_index..].iter().position(|next_actual_elem| {
-                let mut next_actual_elem = next_actual_elem.clone();
-                normalize_value_to_redactions(
-                    &mut next_actual_elem,
-                    next_expected_elem,
-                    redactions,
-                );
-                next_actual_elem == **next_expected_elem
-            }) else {
+            let group_start = if group_len == remaining.len() {
+                // Trailing elements are anchored to the end
+                actual
+                    .len()
+                    .checked_sub(group.len())
+                    .filter(|start| actual_index <= *start && group_matches(*start))
+            } else {
+                (actual_index..actual.len()).find(|start| group_matches(*start))
+            };
+            // Fallback to the first possible match so the rest of the report lines up
+            let group_start = group_start.or_else(|| {
+                let next_expected_elem = group.first()?;
+                let index_offset = actual[actual_index..].iter().position(|next_actual_elem| {
+                    value_matches_redactions(next_actual_elem, next_expected_elem, redactions)
+                })?;
+                Some(actual_index + index_offset)
+            });
+            let Some(group_start) = group_start else {
                 // Give up as we can't find where the elide ends
                 break;
             };
             normalized.push(expected_elem.clone());
-            actual_index += index_offset;
+            actual_index = group_start;
         } else {
             let Some(actual_elem) = actual.get(actual_index) else {
                 // Give up as we have no more content to check
@@ -526,44 +1292,383 @@ fn normalize_array_to_redactions(
     normalized
 }
 
-fn normalize_str_to_redactions(actual: &str, expected: &str, redactions: &Redactions) -> String {
+/// Re-order `actual` to line up with the elements of an `{"$unordered": [...]}` marker
+///
+/// Elements are paired to match as many as possible, each `actual` element being used at most
+/// once, so an earlier wildcard like `"[..]"` doesn't take an element a later literal needs.
+/// `"{...}"` elements match any one element and are paired last.  Elements that don't match any
+/// in `expected` fill the remaining positions in their original order so the diff lines up.
+#[cfg(feature = "structured-data")]
+fn normalize_array_to_unordered(
+    actual: Vec<serde_json::Value>,
+    expected: &[serde_json::Value],
+    redactions: &Redactions,
+) -> Vec<serde_json::Value> {
+    let matches: Vec<Vec<bool>> = expected
+        .iter()
+        .map(|expected_elem| {
+            actual
+                .iter()
+                .map(|actual_elem| value_matches_redactions(actual_elem, expected_elem, redactions))
+                .collect()
+        })
+        .collect();
+    let specific = (0..expected.len()).filter(|index| expected[*index] != VALUE_WILDCARD);
+    let wildcards = (0..expected.len()).filter(|index| expected[*index] == VALUE_WILDCARD);
+    // The `expected` element each `actual` element is paired with
+    let mut pairs: Vec<Option<usize>> = vec![None; actual.len()];
+    for expected_index in specific.chain(wildcards) {
+        let mut visited = vec![false; actual.len()];
+        augment_pairing(expected_index, &matches, &mut pairs, &mut visited);
+    }
+
+    let mut slots: Vec<Option<serde_json::Value>> = vec![None; expected.len()];
+    let mut unmatched = Vec::new();
+    for (mut actual_elem, pair) in actual.into_iter().zip(pairs) {
+        if let Some(expected_index) = pair {
+            normalize_value_to_redactions(&mut actual_elem, &expected[expected_index], redactions);
+            slots[expected_index] = Some(actual_elem);
+        } else {
+            unmatched.push(actual_elem);
+        }
+    }
+
+    let mut unmatched = unmatched.into_iter();
+    let mut normalized: Vec<serde_json::Value> = slots
+        .into_iter()
+        .filter_map(|slot| slot.or_else(|| unmatched.next()))
+        .collect();
+    normalized.extend(unmatched);
+    normalized
+}
+
+/// Pair `expected_index` with an `actual` element, re-pairing others if needed
+///
+/// This finds an augmenting path for a maximum bipartite matching, where `matches[e][a]` is
+/// whether `expected[e]` can match `actual[a]`.
+#[cfg(feature = "structured-data")]
+fn augment_pairing(
+    expected_index: usize,
+    matches: &[Vec<bool>],
+    pairs: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for (actual_index, is_match) in matches[expected_index].iter().enumerate() {
+        if !is_match || visited[actual_index] {
+            continue;
+        }
+        visited[actual_index] = true;
+        let is_free = match pairs[actual_index] {
+            Some(other) => augment_pairing(other, matches, pairs, visited),
+            None => true,
+        };
+        if is_free {
+            pairs[actual_index] = Some(expected_index);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(feature = "structured-data")]
+fn value_matches_redactions(
+    actual: &serde_json::Value,
+    expected: &serde_json::Value,
+    redactions: &Redactions,
+) -> bool {
+    let mut actual = actual.clone();
+    normalize_value_to_redactions(&mut actual, expected, redactions);
+    actual == *expected
+}
+
+fn normalize_str_to_redactions(
+    actual: &str,
+    expected: &str,
+    redactions: &Redactions,
+    trim_elide_anchors: bool,
+    case_insensitive: bool,
+    unredacted: Option<&str>,
+) -> String {
     if actual == expected {
         return actual.to_owned();
     }
 
+    let expected_lines: Vec<_> = crate::utils::LinesWithTerminator::new(expected).collect();
+    if expected_lines
+        .iter()
+        .all(|line| negative_line(line).is_none())
+    {
+        return normalize_str_to_lines(
+            actual,
+            expected,
+            redactions,
+            trim_elide_anchors,
+            case_insensitive,
+            unredacted,
+        );
+    }
+
+    // Negative lines don't correspond to any actual line, so match without them
+    let required: String = expected_lines
+        .iter()
+        .copied()
+        .filter(|line| negative_line(line).is_none())
+        .collect();
+    let normalized = normalize_str_to_lines(
+        actual,
+        &required,
+        redactions,
+        trim_elide_anchors,
+        case_insensitive,
+        unredacted,
+    );
+    if normalized != required {
+        return normalized;
+    }
+
+    // Replace each negative line with the lines that violate it so they show up in the diff
+    let actual_lines: Vec<_> = crate::utils::LinesWithTerminator::new(actual).collect();
+    let mut normalized = String::new();
+    for expected_line in expected_lines {
+        let Some(fragment) = negative_line(expected_line) else {
+            normalized.push_str(expected_line);
+            continue;
+        };
+        let pattern = format!("[..]{fragment}[..]");
+        let violations: Vec<_> = actual_lines
+            .iter()
+            .filter(|actual_line| {
+                let actual_line = actual_line.trim_end_matches(['\n', '\r']);
+                line_matches_case(actual_line, &pattern, redactions, case_insensitive)
+            })
+            .collect();
+        if violations.is_empty() {
+            normalized.push_str(expected_line);
+        } else {
+            normalized.extend(violations.into_iter().copied());
+        }
+    }
+    normalized
+}
+
+fn normalize_str_to_lines<'a>(
+    actual: &'a str,
+    expected: &'a str,
+    redactions: &Redactions,
+    trim_elide_anchors: bool,
+    case_insensitive: bool,
+    unredacted: Option<&'a str>,
+) -> String {
+    let anchor_matches = |actual_line: &str, expected_line: &str, backrefs: &mut Backrefs| {
+        if trim_elide_anchors {
+            line_matches_bound(
+                actual_line.trim_end(),
+                expected_line.trim_end(),
+                redactions,
+                backrefs,
+                case_insensitive,
+            )
+        } else {
+            line_matches_bound(
+                actual_line,
+                expected_line,
+                redactions,
+                backrefs,
+                case_insensitive,
+            )
+        }
+    };
+
+    let mut backrefs = Backrefs::new();
+    let mut resync_cache = ResyncCache::new();
+
     let mut normalized: Vec<&str> = Vec::new();
+    let mut anchor_index = None;
     let mut actual_index = 0;
     let actual_lines: Vec<_> = crate::utils::LinesWithTerminator::new(actual).collect();
-    let mut expected_lines = crate::utils::LinesWithTerminator::new(expected).peekable();
-    while let Some(expected_line) = expected_lines.next() {
+    let expected_lines: Vec<_> = crate::utils::LinesWithTerminator::new(expected).collect();
+    // Only when redactions didn't change which line is which
+    let verbatim_lines: Vec<_> = unredacted
+        .map(|unredacted| crate::utils::LinesWithTerminator::new(unredacted).collect())
+        .filter(|lines: &Vec<_>| lines.len() == actual_lines.len())
+        .unwrap_or_else(|| actual_lines.clone());
+    let mut expected_index = 0;
+    while let Some(expected_line) = expected_lines.get(expected_index).copied() {
+        expected_index += 1;
+        let is_anchor = anchor_index == Some(expected_index);
         if is_line_elide(expected_line) {
-            let Some(next_expected_line) = expected_lines.peek() else {
-                // Stop as elide consumes to end
+            // Optional lines may have been elided, so look for the next required line
+            let next_index = expected_lines[expected_index..]
+                .iter()
+                .position(|line| optional_line(line).is_none())
+                .map(|offset| expected_index + offset);
+            let (min, max) = line_elide_bounds(expected_line).expect("checked by `is_line_elide`");
+            let remaining = actual_lines.len() - actual_index;
+            let max = max.unwrap_or(remaining).min(remaining);
+            let Some(next_index) = next_index else {
+                if remaining < min {
+                    // Give up as there aren't enough lines to elide
+                    break;
+                }
+                // Stop as elide consumes to end, including when there is nothing left.  Any
+                // remaining lines are optional and match whether or not they were elided.
                 normalized.push(expected_line);
-                actual_index = actual_lines.len();
+                normalized.extend(&expected_lines[expected_index..]);
+                actual_index += max;
                 break;
             };
-            let Some(index_offset) =
-                actual_lines[actual_index..]
-                    .iter()
-                    .position(|next_actual_line| {
-                        line_matches(next_actual_line, next_expected_line, redactions)
-                    })
-            else {
+            let next_expected_line = expected_lines[next_index];
+            let offsets = min..=max;
+            let index_offset = if is_verbatim_start(next_expected_line) {
+                let (region, _) = verbatim_region(&expected_lines[(next_index + 1)..]);
+                offsets
+                    .into_iter()
+                    .find(|offset| verbatim_lines[(actual_index + offset)..].starts_with(region))
+            } else if is_unordered_start(next_expected_line) {
+                let (block, _) = unordered_block(&expected_lines[(next_index + 1)..]);
+                offsets.into_iter().find(|offset| {
+                    let start = actual_index + offset;
+                    actual_lines
+                        .get(start..(start + block.len()))
+                        .map(|window| {
+                            pair_unordered(window, block, redactions, case_insensitive)
+                                .iter()
+                                .all(Option::is_some)
+                        })
+                        .unwrap_or(false)
+                })
+            } else {
+                offsets.into_iter().find(|offset| {
+                    actual_lines
+                        .get(actual_index + offset)
+                        .map(|next_actual_line| {
+                            anchor_matches(
+                                next_actual_line,
+                                next_expected_line,
+                                &mut backrefs.clone(),
+                            )
+                        })
+                        .unwrap_or(false)
+                })
+            };
+            let Some(index_offset) = index_offset else {
                 // Give up as we can't find where the elide ends
                 break;
             };
             normalized.push(expected_line);
             actual_index += index_offset;
+            anchor_index = Some(next_index + 1);
+        } else if is_verbatim_start(expected_line) {
+            let (region, closed) = verbatim_region(&expected_lines[expected_index..]);
+            expected_index += region.len();
+            let region_end = (actual_index + region.len()).min(actual_lines.len());
+            normalized.push(expected_line);
+            normalized.extend(&verbatim_lines[actual_index..region_end]);
+            if closed {
+                normalized.push(expected_lines[expected_index]);
+                expected_index += 1;
+            }
+            actual_index = region_end;
+        } else if is_unordered_start(expected_line) {
+            let (block, closed) = unordered_block(&expected_lines[expected_index..]);
+            expected_index += block.len();
+            let block_end = (actual_index + block.len()).min(actual_lines.len());
+            let window = &actual_lines[actual_index..block_end];
+            let pairs = pair_unordered(window, block, redactions, case_insensitive);
+            normalized.push(expected_line);
+            normalized.extend(
+                block
+                    .iter()
+                    .zip(&pairs)
+                    .filter(|(_, pair)| pair.is_some())
+                    .map(|(expected_line, _)| *expected_line),
+            );
+            normalized.extend(
+                window
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !pairs.contains(&Some(*index)))
+                    .map(|(_, actual_line)| *actual_line),
+            );
+            if closed {
+                normalized.push(expected_lines[expected_index]);
+                expected_index += 1;
+            }
+            actual_index = block_end;
+        } else if let Some(optional) = optional_line(expected_line) {
+            if actual_lines
+                .get(actual_index)
+                .map(|actual_line| {
+                    line_matches_bound(
+                        actual_line,
+                        optional,
+                        redactions,
+                        &mut backrefs,
+                        case_insensitive,
+                    )
+                })
+                .unwrap_or(false)
+            {
+                actual_index += 1;
+            }
+            // Whether present or not, this is a match
+            normalized.push(expected_line);
         } else {
             let Some(actual_line) = actual_lines.get(actual_index) else {
                 // Give up as we have no more content to check
                 break;
             };
 
-            if line_matches(actual_line, expected_line, redactions) {
-                actual_index += 1;
+            let is_match = if is_anchor {
+                anchor_matches(actual_line, expected_line, &mut backrefs)
+            } else {
+                line_matches_bound(
+                    actual_line,
+                    expected_line,
+                    redactions,
+                    &mut backrefs,
+                    case_insensitive,
+                )
+            };
+            let spanned = if is_match {
+                Some(1)
+            } else {
+                multiline_wildcard_span(
+                    &actual_lines[actual_index..],
+                    expected_line,
+                    expected_lines.get(expected_index).copied(),
+                    redactions,
+                    &mut backrefs,
+                    case_insensitive,
+                )
+            };
+            if let Some(spanned) = spanned {
+                actual_index += spanned;
                 normalized.push(expected_line);
+            } else if let Some((inserted, missing)) = resync(
+                &actual_lines,
+                actual_index,
+                &expected_lines,
+                expected_index - 1,
+                redactions,
+                &backrefs,
+                case_insensitive,
+                &mut resync_cache,
+            ) {
+                // Lines were added or removed, so keep the extra actual lines and drop the missing
+                // expected lines so only they show up in the diff
+                normalized.extend(&actual_lines[actual_index..(actual_index + inserted)]);
+                actual_index += inserted;
+                expected_index += missing;
+                line_matches_bound(
+                    actual_lines[actual_index],
+                    expected_lines[expected_index - 1],
+                    redactions,
+                    &mut backrefs,
+                    case_insensitive,
+                );
+                actual_index += 1;
+                normalized.push(expected_lines[expected_index - 1]);
             } else {
                 // Skip this line and keep processing
                 actual_index += 1;
@@ -576,23 +1681,773 @@ fn normalize_str_to_redactions(actual: &str, expected: &str, redactions: &Redact
     normalized.join("")
 }
 
-fn is_line_elide(line: &str) -> bool {
-    line == "...\n" || line == "..."
+/// How many `actual` lines `expected` matches when its `[...]` spans lines
+///
+/// The fewest lines are used.  The span stops before a line that matches `next`, the following
+/// `expected` line, when that is a plain line and is limited to [`MULTILINE_WILDCARD_MAX_LINES`]
+/// lines so a mismatch doesn't join the rest of `actual`.
+fn multiline_wildcard_span(
+    actual: &[&str],
+    expected: &str,
+    next: Option<&str>,
+    redactions: &Redactions,
+    backrefs: &mut Backrefs,
+    case_insensitive: bool,
+) -> Option<usize> {
+    if !has_multiline_wildcard(expected) {
+        return None;
+    }
+    let next = next.filter(|next| {
+        !is_line_elide(next)
+            && optional_line(next).is_none()
+            && !is_verbatim_start(next)
+            && !is_unordered_start(next)
+            && !has_multiline_wildcard(next)
+    });
+    let mut joined = (*actual.first()?).to_owned();
+    for (index, line) in actual
+        .iter()
+        .enumerate()
+        .take(MULTILINE_WILDCARD_MAX_LINES)
+        .skip(1)
+    {
+        let is_anchor = next
+            .map(|next| {
+                line_matches_bound(
+                    line,
+                    next,
+                    redactions,
+                    &mut backrefs.clone(),
+                    case_insensitive,
+                )
+            })
+            .unwrap_or(false);
+        if is_anchor {
+            return None;
+        }
+        joined.push_str(line);
+        if line_matches_bound(&joined, expected, redactions, backrefs, case_insensitive) {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+const MULTILINE_WILDCARD: &str = "[...]";
+
+/// The most `actual` lines a single `[...]` line can span
+const MULTILINE_WILDCARD_MAX_LINES: usize = 64;
+
+/// The leading lines of `pattern` that each match one `actual` line and how many there are
+///
+/// This stops at the first `...`, `[...]`, or `[!CONTAINS] ` line, as those can look at the
+/// rest of `actual`, which is returned.
+pub(crate) fn split_bounded_lines(pattern: &str) -> (&str, usize, Option<&str>) {
+    let mut len = 0;
+    let mut count = 0;
+    for line in crate::utils::LinesWithTerminator::new(pattern) {
+        if is_line_elide(line) || has_multiline_wildcard(line) || negative_line(line).is_some() {
+            return (&pattern[..len], count, Some(&pattern[len..]));
+        }
+        len += line.len();
+        count += 1;
+    }
+    (pattern, count, None)
+}
+
+fn has_multiline_wildcard(line: &str) -> bool {
+    line.match_indices(MULTILINE_WILDCARD)
+        .any(|(index, _)| !line[..index].ends_with('\\'))
 }
 
-fn line_matches(mut actual: &str, expected: &str, redactions: &Redactions) -> bool {
-    if actual == expected {
+/// Find where `actual` and `expected` match again after a mismatch
+///
+/// Returns how many `actual` lines were inserted and how many `expected` lines are missing before
+/// the lines that match.  The lines are aligned by their longest common subsequence so a line that
+/// happens to match nearby doesn't throw off the rest of the report.  Only plain `expected` lines
+/// are considered, stopping at the first `...`, optional line, or block, and at most
+/// [`RESYNC_WINDOW`] lines of each are looked at.
+///
+/// `actual_lines[actual_index]` and `expected_lines[expected_index]` are the lines that didn't
+/// match.  Once `cache` runs out of budget, nothing more is resynced.
+#[allow(clippy::too_many_arguments)]
+fn resync(
+    actual_lines: &[&str],
+    actual_index: usize,
+    expected_lines: &[&str],
+    expected_index: usize,
+    redactions: &Redactions,
+    backrefs: &Backrefs,
+    case_insensitive: bool,
+    cache: &mut ResyncCache,
+) -> Option<(usize, usize)> {
+    let actual = &actual_lines[actual_index..];
+    let expected = &expected_lines[expected_index..];
+    let plain_len = expected
+        .iter()
+        .position(|line| {
+            is_line_elide(line)
+                || optional_line(line).is_some()
+                || is_verbatim_start(line)
+                || is_unordered_start(line)
+        })
+        .unwrap_or(expected.len());
+    let expected = &expected[..plain_len.min(RESYNC_WINDOW)];
+    let actual = &actual[..actual.len().min(RESYNC_WINDOW)];
+    let cost = actual.len() * expected.len();
+    if cache.budget < cost {
+        return None;
+    }
+    cache.budget -= cost;
+
+    let mut matches = vec![vec![false; expected.len()]; actual.len()];
+    for (inserted, row) in matches.iter_mut().enumerate() {
+        for (missing, cell) in row.iter_mut().enumerate() {
+            // The current lines are already known to not match
+            if (inserted, missing) == (0, 0) {
+                continue;
+            }
+            *cell = cache.line_matches(
+                (actual_index + inserted, expected_index + missing),
+                actual[inserted],
+                expected[missing],
+                redactions,
+                backrefs,
+                case_insensitive,
+            );
+        }
+    }
+
+    // `common[i][j]` is the length of the longest common subsequence of `actual[i..]` and
+    // `expected[j..]`
+    let mut common = vec![vec![0; expected.len() + 1]; actual.len() + 1];
+    for inserted in (0..actual.len()).rev() {
+        for missing in (0..expected.len()).rev() {
+            common[inserted][missing] = if matches[inserted][missing] {
+                common[inserted + 1][missing + 1] + 1
+            } else {
+                common[inserted + 1][missing].max(common[inserted][missing + 1])
+            };
+        }
+    }
+
+    let (mut inserted, mut missing) = (0, 0);
+    while inserted < actual.len() && missing < expected.len() {
+        if matches[inserted][missing]
+            && common[inserted][missing] == common[inserted + 1][missing + 1] + 1
+        {
+            return Some((inserted, missing));
+        } else if common[inserted][missing + 1] < common[inserted + 1][missing] {
+            inserted += 1;
+        } else {
+            missing += 1;
+        }
+    }
+    None
+}
+
+/// Line matches for [`resync`], shared across a document so overlapping windows reuse them
+struct ResyncCache {
+    /// Whether the lines at `(actual_index, expected_index)` match
+    matches: std::collections::HashMap<(usize, usize), bool>,
+    /// How many more pairs of lines can be aligned, so a large diff can't take minutes
+    budget: usize,
+}
+
+impl ResyncCache {
+    fn new() -> Self {
+        Self {
+            matches: Default::default(),
+            budget: RESYNC_BUDGET,
+        }
+    }
+
+    fn line_matches(
+        &mut self,
+        key: (usize, usize),
+        actual: &str,
+        expected: &str,
+        redactions: &Redactions,
+        backrefs: &Backrefs,
+        case_insensitive: bool,
+    ) -> bool {
+        let is_match = |backrefs: &mut Backrefs| {
+            line_matches_bound(actual, expected, redactions, backrefs, case_insensitive)
+        };
+        // Whether `[ID:<name>]`s match depends on what was bound before
+        if expected.contains("[ID:") {
+            return is_match(&mut backrefs.clone());
+        }
+        *self
+            .matches
+            .entry(key)
+            .or_insert_with(|| is_match(&mut Backrefs::new()))
+    }
+}
+
+const RESYNC_WINDOW: usize = 256;
+
+/// The most pairs of lines [`resync`] aligns for a document
+const RESYNC_BUDGET: usize = 1 << 20;
+
+/// Pair each `expected` line with the `actual` text it matched
+///
+/// `...` is paired with the lines it elided while `[..]` and redaction placeholders are replaced
+/// with the text they matched.  Lines that could not be matched are paired with `None`.
+///
+/// Like `[ID:<name>]`, each placeholder must match the same text everywhere it's used.
+pub(crate) fn resolve_str_to_redactions<'e>(
+    actual: &str,
+    expected: &'e str,
+    redactions: &Redactions,
+) -> Vec<(&'e str, Option<String>)> {
+    let mut bound = Backrefs::new();
+    pair_lines(actual, expected, redactions)
+        .into_iter()
+        .map(|(expected_line, actual_text)| {
+            let resolved = if is_line_elide(expected_line) {
+                actual_text.map(|actual_text| actual_text.concat())
+            } else if let Some(optional) = optional_line(expected_line) {
+                actual_text.and_then(|actual_text| match actual_text.first() {
+                    Some(actual_line) => {
+                        resolve_line(actual_line, optional, redactions, &mut bound)
+                    }
+                    None => Some(String::new()),
+                })
+            } else {
+                actual_text.and_then(|actual_text| {
+                    resolve_line(actual_text.first()?, expected_line, redactions, &mut bound)
+                })
+            };
+            (expected_line, resolved)
+        })
+        .collect()
+}
+
+/// Find the text each redaction placeholder in `expected` matched in `actual`
+///
+/// Only the first match for each placeholder is reported.
+pub(crate) fn capture_str_to_redactions(
+    actual: &str,
+    expected: &str,
+    redactions: &Redactions,
+) -> Vec<(&'static str, String)> {
+    let mut captures: Vec<(&'static str, String)> = Vec::new();
+    let mut bound = Backrefs::new();
+    for (expected_line, actual_text) in pair_lines(actual, expected, redactions) {
+        if is_line_elide(expected_line) {
+            continue;
+        }
+        let Some(actual_line) = actual_text.and_then(|actual_text| actual_text.first().copied())
+        else {
+            continue;
+        };
+        let actual_line = actual_line.trim_end_matches(&['\n', '\r'][..]);
+        let expected_line = optional_line(expected_line).unwrap_or(expected_line);
+        let expected_line = redactions.clear_unused(expected_line);
+        let expected_line = expected_line.trim_end_matches(&['\n', '\r'][..]);
+        let Some(sections) = match_sections(actual_line, expected_line, redactions, &mut bound)
+        else {
+            continue;
+        };
+        for (section, matched) in sections {
+            let Section::Wildcard(token) = section else {
+                continue;
+            };
+            let Some(placeholder) = redactions.find_placeholder(token) else {
+                continue;
+            };
+            if captures
+                .iter()
+                .all(|(existing, _)| *existing != placeholder)
+            {
+                captures.push((placeholder, matched.to_owned()));
+            }
+        }
+    }
+    captures
+}
+
+/// Pair each `expected` line with the `actual` lines it corresponds to
+///
+/// `...` is paired with the lines it elided, other lines with a single line.
+/// Lines that have no corresponding `actual` lines are paired with `None`.
+fn pair_lines<'a, 'e>(
+    actual: &'a str,
+    expected: &'e str,
+    redactions: &Redactions,
+) -> Vec<(&'e str, Option<Vec<&'a str>>)> {
+    let mut paired = Vec::new();
+    let mut actual_index = 0;
+    let actual_lines: Vec<_> = crate::utils::LinesWithTerminator::new(actual).collect();
+    let mut expected_lines = crate::utils::LinesWithTerminator::new(expected).peekable();
+    while let Some(expected_line) = expected_lines.next() {
+        if is_line_elide(expected_line) {
+            let elide_end = if let Some(next_expected_line) = expected_lines.peek() {
+                actual_lines[actual_index..]
+                    .iter()
+                    .position(|next_actual_line| {
+                        let next_actual_line = redactions.redact(next_actual_line);
+                        line_matches(&next_actual_line, next_expected_line, redactions)
+                    })
+                    .map(|offset| actual_index + offset)
+            } else {
+                Some(actual_lines.len())
+            };
+            if let Some(elide_end) = elide_end {
+                paired.push((
+                    expected_line,
+                    Some(actual_lines[actual_index..elide_end].to_vec()),
+                ));
+                actual_index = elide_end;
+            } else {
+                paired.push((expected_line, None));
+            }
+        } else if let Some(optional) = optional_line(expected_line) {
+            let actual_line = actual_lines
+                .get(actual_index)
+                .filter(|actual_line| {
+                    let actual_line = redactions.redact(actual_line);
+                    line_matches(&actual_line, optional, redactions)
+                })
+                .copied();
+            if actual_line.is_some() {
+                actual_index += 1;
+            }
+            paired.push((expected_line, Some(actual_line.into_iter().collect())));
+        } else {
+            let actual_line = actual_lines.get(actual_index).map(|line| vec![*line]);
+            actual_index += 1;
+            paired.push((expected_line, actual_line));
+        }
+    }
+
+    paired
+}
+
+/// Match `actual` against `expected` one line at a time, as it is produced
+///
+/// Supports `[..]`, redactions, and optional lines.  `...` skips lines until one matches the
+/// next required line of `expected`.  Verbatim regions are not supported.
+pub(crate) struct StreamMatcher<'e> {
+    expected: Vec<&'e str>,
+    index: usize,
+    redactions: &'e Redactions,
+    document: Document,
+}
+
+impl<'e> StreamMatcher<'e> {
+    pub(crate) fn new(expected: &'e str, redactions: &'e Redactions) -> Self {
+        let expected = crate::utils::LinesWithTerminator::new(expected)
+            .map(|line| line.trim_end_matches(['\n', '\r']))
+            .collect();
+        Self {
+            expected,
+            index: 0,
+            redactions,
+            document: Document::text(),
+        }
+    }
+
+    /// Check the next line of `actual`, without its line terminator
+    ///
+    /// On a mismatch, returns the `expected` line it failed to match, if any were left.
+    pub(crate) fn push(&mut self, actual: &str) -> Result<(), Option<&'e str>> {
+        let actual = self.redactions.redact_in(actual, false, &mut self.document);
+        while let Some(expected) = self.expected.get(self.index).copied() {
+            if is_line_elide(expected) {
+                // Optional lines may have been elided, so look for the next required line
+                let next_index = self.expected[(self.index + 1)..]
+                    .iter()
+                    .position(|line| optional_line(line).is_none())
+                    .map(|offset| self.index + 1 + offset);
+                if let Some(next_index) = next_index {
+                    if line_matches(&actual, self.expected[next_index], self.redactions) {
+                        self.index = next_index + 1;
+                    }
+                }
+                return Ok(());
+            } else if let Some(optional) = optional_line(expected) {
+                self.index += 1;
+                if line_matches(&actual, optional, self.redactions) {
+                    return Ok(());
+                }
+            } else if line_matches(&actual, expected, self.redactions) {
+                self.index += 1;
+                return Ok(());
+            } else {
+                return Err(Some(expected));
+            }
+        }
+        Err(None)
+    }
+
+    /// Check that no required lines of `expected` are left, returning the first one if there is
+    pub(crate) fn finish(&self) -> Result<(), &'e str> {
+        match self.expected[self.index..]
+            .iter()
+            .find(|line| !is_line_elide(line) && optional_line(line).is_none())
+        {
+            Some(line) => Err(line),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Replace each `[..]` and redaction placeholder in `expected` with the text it matched
+fn resolve_line(
+    actual: &str,
+    expected: &str,
+    redactions: &Redactions,
+    bound: &mut Backrefs,
+) -> Option<String> {
+    let expected = redactions.clear_unused(expected);
+    let sections = match_sections(actual, &expected, redactions, bound)?;
+    Some(
+        sections
+            .into_iter()
+            .map(|(_section, matched)| matched)
+            .collect(),
+    )
+}
+
+/// Pair each section of `expected` with the text of `actual` it matched
+///
+/// Placeholders and `[ID:<name>]`s in `bound` must match the same text again, others are bound
+/// to what they matched.  On a mismatch, `bound` is unchanged.
+fn match_sections<'a, 'e>(
+    actual: &'a str,
+    expected: &'e str,
+    redactions: &Redactions,
+    bound: &mut Backrefs,
+) -> Option<Vec<(Section<'e>, &'a str)>> {
+    let bound_len = bound.len();
+    let matched = match_sections_inner(actual, expected, redactions, bound);
+    if matched.is_none() {
+        bound.truncate(bound_len);
+    }
+    matched
+}
+
+fn match_sections_inner<'a, 'e>(
+    mut actual: &'a str,
+    expected: &'e str,
+    redactions: &Redactions,
+    bound: &mut Backrefs,
+) -> Option<Vec<(Section<'e>, &'a str)>> {
+    let mut matched = Vec::new();
+    let mut pending_wildcard = None;
+    let mut sections = split_wildcards(expected, redactions).peekable();
+    while let Some(section) = sections.next() {
+        let literal = match section {
+            Section::Literal(literal) => literal,
+            Section::Wildcard(_) => {
+                pending_wildcard = Some(section);
+                continue;
+            }
+        };
+        if let Some(wildcard) = pending_wildcard.take() {
+            let Section::Wildcard(token) = wildcard else {
+                unreachable!("only wildcards are pending");
+            };
+            let offset = if let Some((_, text)) = bound.iter().find(|(name, _)| name == token) {
+                actual.starts_with(text.as_str()).then_some(text.len())?
+            } else if sections.peek().is_none() {
+                actual.strip_suffix(literal)?.len()
+            } else if wildcard == Section::Wildcard("[..>]") {
+                actual.rfind(literal)?
+            } else {
+                actual.find(literal)?
+            };
+            bind_wildcard(token, &actual[..offset], redactions, bound)?;
+            matched.push((wildcard, &actual[..offset]));
+            actual = &actual[offset..];
+        }
+        let remainder = actual.strip_prefix(literal)?;
+        matched.push((section, &actual[..literal.len()]));
+        actual = remainder;
+    }
+    if let Some(wildcard) = pending_wildcard {
+        let Section::Wildcard(token) = wildcard else {
+            unreachable!("only wildcards are pending");
+        };
+        bind_wildcard(token, actual, redactions, bound)?;
+        matched.push((wildcard, actual));
+    } else if !actual.is_empty() {
+        return None;
+    }
+
+    Some(matched)
+}
+
+/// Bind a placeholder or `[ID:<name>]` to the text it `matched`, failing if it was bound to other text
+fn bind_wildcard(
+    token: &str,
+    matched: &str,
+    redactions: &Redactions,
+    bound: &mut Backrefs,
+) -> Option<()> {
+    let is_bindable = redactions.is_placeholder(token)
+        || matches!(
+            super::token::Token::parse(token),
+            Some(super::token::Token::Backref(_))
+        );
+    if !is_bindable {
+        return Some(());
+    }
+    match bound.iter().find(|(name, _)| name == token) {
+        Some((_, text)) => (text == matched).then_some(()),
+        None => {
+            bound.push((token.to_owned(), matched.to_owned()));
+            Some(())
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Section<'e> {
+    Literal(&'e str),
+    /// `[..]`, `[..>]`, or a redaction placeholder
+    Wildcard(&'e str),
+}
+
+/// Split `expected` into literal sections and wildcards
+fn split_wildcards<'e>(
+    mut expected: &'e str,
+    redactions: &Redactions,
+) -> impl Iterator<Item = Section<'e>> {
+    let mut sections = Vec::new();
+    let mut literal_start = 0;
+    let mut index = 0;
+    while let Some(offset) = expected[index..].find('[') {
+        let start = index + offset;
+        if let Some((literal, len)) = super::token::parse_literal(&expected[start..]) {
+            if literal_start < start {
+                sections.push(Section::Literal(&expected[literal_start..start]));
+            }
+            if !literal.is_empty() {
+                sections.push(Section::Literal(literal));
+            }
+            index = start + len;
+            literal_start = index;
+            continue;
+        }
+        let wildcard_len = expected[start..].find(']').and_then(|end| {
+            let token = &expected[start..=(start + end)];
+            (token == "[..]"
+                || token == "[..>]"
+                || redactions.is_placeholder(token)
+                || super::token::Token::parse(token).is_some())
+            .then_some(token.len())
+        });
+        if let Some(wildcard_len) = wildcard_len {
+            if literal_start < start {
+                sections.push(Section::Literal(&expected[literal_start..start]));
+            }
+            sections.push(Section::Wildcard(&expected[start..(start + wildcard_len)]));
+            index = start + wildcard_len;
+            literal_start = index;
+        } else {
+            index = start + 1;
+        }
+    }
+    expected = &expected[literal_start..];
+    if !expected.is_empty() {
+        sections.push(Section::Literal(expected));
+    }
+    sections.into_iter()
+}
+
+pub(crate) fn is_line_elide(line: &str) -> bool {
+    line_elide_bounds(line).is_some()
+}
+
+/// The minimum and maximum number of lines a `...` line can elide
+///
+/// Bounds are written like regex repetitions: `...{2}`, `...{0,3}`, `...{1,}`.
+fn line_elide_bounds(line: &str) -> Option<(usize, Option<usize>)> {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let bounds = line.strip_prefix("...")?;
+    if bounds.is_empty() {
+        return Some((0, None));
+    }
+    let bounds = bounds.strip_prefix('{')?.strip_suffix('}')?;
+    match bounds.split_once(',') {
+        Some((min, "")) => Some((min.parse().ok()?, None)),
+        Some((min, max)) => {
+            let (min, max) = (min.parse().ok()?, max.parse().ok()?);
+            (min <= max).then_some((min, Some(max)))
+        }
+        None => {
+            let count = bounds.parse().ok()?;
+            Some((count, Some(count)))
+        }
+    }
+}
+
+/// The line after an optional-line marker (`[?] `)
+///
+/// A line of only the marker is an optional blank line.
+fn optional_line(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(OPTIONAL_LINE)?;
+    if rest.is_empty() || rest == "\n" {
+        Some(rest)
+    } else {
+        rest.strip_prefix(' ')
+    }
+}
+
+const OPTIONAL_LINE: &str = "[?]";
+
+/// The fragment after a negative-line marker (`[!CONTAINS] `), without the line terminator
+fn negative_line(line: &str) -> Option<&str> {
+    let fragment = line
+        .strip_prefix(NEGATIVE_LINE)?
+        .strip_prefix(' ')?
+        .trim_end_matches(['\n', '\r']);
+    (!fragment.is_empty()).then_some(fragment)
+}
+
+const NEGATIVE_LINE: &str = "[!CONTAINS]";
+
+pub(crate) fn is_verbatim_start(line: &str) -> bool {
+    line == "<<<verbatim\n" || line == "<<<verbatim"
+}
+
+pub(crate) fn is_verbatim_end(line: &str) -> bool {
+    line == ">>>\n" || line == ">>>"
+}
+
+/// The lines of a verbatim region, starting after `<<<verbatim`, and whether it was closed
+fn verbatim_region<'e, 'l>(lines: &'l [&'e str]) -> (&'l [&'e str], bool) {
+    match lines.iter().position(|line| is_verbatim_end(line)) {
+        Some(end) => (&lines[..end], true),
+        None => (lines, false),
+    }
+}
+
+pub(crate) fn is_unordered_start(line: &str) -> bool {
+    line == "[UNORDERED]\n" || line == "[UNORDERED]"
+}
+
+pub(crate) fn is_unordered_end(line: &str) -> bool {
+    line == "[/UNORDERED]\n" || line == "[/UNORDERED]"
+}
+
+/// The lines of an unordered block, starting after `[UNORDERED]`, and whether it was closed
+fn unordered_block<'e, 'l>(lines: &'l [&'e str]) -> (&'l [&'e str], bool) {
+    match lines.iter().position(|line| is_unordered_end(line)) {
+        Some(end) => (&lines[..end], true),
+        None => (lines, false),
+    }
+}
+
+/// For each line of an unordered `block`, the index of the `actual` line it matched
+///
+/// Each `actual` line is used at most once, going with the first `expected` line it matches.
+fn pair_unordered(
+    actual: &[&str],
+    block: &[&str],
+    redactions: &Redactions,
+    case_insensitive: bool,
+) -> Vec<Option<usize>> {
+    let mut pairs: Vec<Option<usize>> = Vec::with_capacity(block.len());
+    for expected_line in block {
+        let pair = actual.iter().enumerate().position(|(index, actual_line)| {
+            !pairs.contains(&Some(index))
+                && line_matches_bound(
+                    actual_line,
+                    expected_line,
+                    redactions,
+                    &mut Backrefs::new(),
+                    case_insensitive,
+                )
+        });
+        pairs.push(pair);
+    }
+    pairs
+}
+
+/// The alternatives after an alternation marker (`[!ONE_OF] A|B|C`) and the line terminator
+///
+/// `\|` is a literal `|`.
+fn alternatives_line(line: &str) -> Option<(Vec<String>, &str)> {
+    let (body, terminator) = match line.strip_suffix('\n') {
+        Some(body) => (body, "\n"),
+        None => (line, ""),
+    };
+    let inner = body.strip_prefix(ALTERNATIVES_LINE)?.strip_prefix(' ')?;
+    let mut alternatives = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                alternatives.last_mut().expect("always non-empty").push('|');
+            }
+            '|' => alternatives.push(String::new()),
+            c => alternatives.last_mut().expect("always non-empty").push(c),
+        }
+    }
+    Some((alternatives, terminator))
+}
+
+const ALTERNATIVES_LINE: &str = "[!ONE_OF]";
+
+fn line_matches(actual: &str, expected: &str, redactions: &Redactions) -> bool {
+    line_matches_bound(actual, expected, redactions, &mut Backrefs::new(), false)
+}
+
+/// Like [`line_matches`] but optionally ignoring ASCII case
+fn line_matches_case(
+    actual: &str,
+    expected: &str,
+    redactions: &Redactions,
+    case_insensitive: bool,
+) -> bool {
+    line_matches_bound(
+        actual,
+        expected,
+        redactions,
+        &mut Backrefs::new(),
+        case_insensitive,
+    )
+}
+
+/// Like [`line_matches`] but `[ID:<name>]`s must match the text they matched on earlier lines
+fn line_matches_bound(
+    mut actual: &str,
+    expected: &str,
+    redactions: &Redactions,
+    backrefs: &mut Backrefs,
+    case_insensitive: bool,
+) -> bool {
+    if actual == expected || (case_insensitive && actual.eq_ignore_ascii_case(expected)) {
         return true;
     }
 
+    if let Some((alternatives, terminator)) = alternatives_line(expected) {
+        let Some(actual) = actual.strip_suffix(terminator) else {
+            return false;
+        };
+        return alternatives.iter().any(|alternative| {
+            line_matches_bound(actual, alternative, redactions, backrefs, case_insensitive)
+        });
+    }
+
     let expected = redactions.clear_unused(expected);
+    if let Some(segments) = super::token::parse_segments(&expected) {
+        return super::token::segments_match(actual, &segments, backrefs, case_insensitive);
+    }
+
     let mut sections = expected.split("[..]").peekable();
     while let Some(section) = sections.next() {
-        if let Some(remainder) = actual.strip_prefix(section) {
+        if let Some(remainder) = strip_prefix(actual, section, case_insensitive) {
             if let Some(next_section) = sections.peek() {
                 if next_section.is_empty() {
                     actual = "";
-                } else if let Some(restart_index) = remainder.find(next_section) {
+                } else if let Some(restart_index) = find(remainder, next_section, case_insensitive)
+                {
                     actual = &remainder[restart_index..];
                 }
             } else {
@@ -610,6 +2465,74 @@ fn line_matches(mut actual: &str, expected: &str, redactions: &Redactions) -> bo
 mod test {
     use super::*;
 
+    #[test]
+    fn resolve_line_cases() {
+        let mut redactions = Redactions::new();
+        redactions.insert("[NAME]", "World").unwrap();
+        let cases = [
+            ("hello", "hello", Some("hello")),
+            ("hello", "goodbye", None),
+            ("hello", "[..]", Some("hello")),
+            ("hello", "he[..]", Some("hello")),
+            ("hello", "he[..]e", None),
+            ("Hello World!", "Hello [NAME]!", Some("Hello World!")),
+            (
+                "Hello World, bye",
+                "Hello [NAME], [..]",
+                Some("Hello World, bye"),
+            ),
+            ("Hello [..] bye", "Hello [OTHER] [..]", None),
+        ];
+        for (line, pattern, expected) in cases {
+            let actual = resolve_line(line, pattern, &redactions, &mut Backrefs::new());
+            assert_eq!(
+                expected,
+                actual.as_deref(),
+                "line={line:?}  pattern={pattern:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_line_repeated_placeholders() {
+        let mut redactions = Redactions::new();
+        redactions.insert("[NAME]", "World").unwrap();
+        let cases = [
+            (
+                "World and World",
+                "[NAME] and [NAME]",
+                Some("World and World"),
+            ),
+            ("World and Moon", "[NAME] and [NAME]", None),
+            ("World and Moon", "[NAME] and [..]", Some("World and Moon")),
+            ("x1 -> x1!", "[ID:a] -> [ID:a]!", Some("x1 -> x1!")),
+            ("x1 -> x2!", "[ID:a] -> [ID:a]!", None),
+            ("x1 -> x1", "[ID:a] -> [ID:a]", Some("x1 -> x1")),
+            ("x1 -> x2", "[ID:a] -> [ID:a]", None),
+        ];
+        for (line, pattern, expected) in cases {
+            let actual = resolve_line(line, pattern, &redactions, &mut Backrefs::new());
+            assert_eq!(
+                expected,
+                actual.as_deref(),
+                "line={line:?}  pattern={pattern:?}"
+            );
+        }
+
+        let resolved = resolve_str_to_redactions(
+            "created x1\nremoved x2\n",
+            "created [ID:a]\nremoved [ID:a]\n",
+            &redactions,
+        );
+        assert_eq!(
+            resolved,
+            [
+                ("created [ID:a]\n", Some("created x1\n".to_owned())),
+                ("removed [ID:a]\n", None),
+            ]
+        );
+    }
+
     #[test]
     fn str_normalize_redactions_line_matches_cases() {
         let cases = [
@@ -648,10 +2571,32 @@ mod test {
                 false,
             ),
             ("hello world, goodbye moon", "hello [..], [..] world", false),
+            ("path/to/path/to/file", "[..>]/to/file", true),
+            ("path/to/path/to/file", "[..>]/to/[..]", true),
+            ("path/to/path/to/file", "[..>]/to/path", false),
         ];
         for (line, pattern, expected) in cases {
             let actual = line_matches(line, pattern, &Redactions::new());
             assert_eq!(expected, actual, "line={line:?}  pattern={pattern:?}");
         }
     }
+
+    #[test]
+    fn match_sections_greedy() {
+        let redactions = Redactions::new();
+        let matched = |pattern| {
+            match_sections(
+                "path/to/path/to/file",
+                pattern,
+                &redactions,
+                &mut Backrefs::new(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|(_section, matched)| matched)
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(matched("[..]/to/[..]"), ["path", "/to/", "path/to/file"]);
+        assert_eq!(matched("[..>]/to/[..]"), ["path/to/path", "/to/", "file"]);
+    }
 }
@@ -0,0 +1,111 @@
+/// A [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) for scoping filters
+///
+/// A `*` segment matches any object key or array index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct JsonPointer {
+    segments: Vec<String>,
+}
+
+impl JsonPointer {
+    /// Parse `pointer`, which is empty for the root or starts with `/`
+    pub(crate) fn parse(pointer: &str) -> Result<Self, String> {
+        let segments = match pointer.strip_prefix('/') {
+            Some(rest) => rest
+                .split('/')
+                .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+            None if pointer.is_empty() => Vec::new(),
+            None => return Err(format!("JSON Pointer `{pointer}` must start with `/`")),
+        };
+        Ok(Self { segments })
+    }
+
+    /// Like [`JsonPointer::parse`] but for builders, which can't return an error
+    pub(crate) fn parse_or_panic(pointer: &str) -> Self {
+        Self::parse(pointer).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Whether this points at the value reached by following `path` from the root
+    pub(crate) fn matches(&self, path: &[String]) -> bool {
+        self.segments.len() == path.len()
+            && self
+                .segments
+                .iter()
+                .zip(path)
+                .all(|(segment, key)| segment == "*" || segment == key)
+    }
+
+    /// The rest of `path` if this points at it or one of its parents
+    pub(crate) fn strip_prefix_of<'p>(&self, path: &'p [String]) -> Option<&'p [String]> {
+        if path.len() < self.segments.len() {
+            return None;
+        }
+        let (prefix, rest) = path.split_at(self.segments.len());
+        self.matches(prefix).then_some(rest)
+    }
+}
+
+impl std::fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.segments {
+            write!(f, "/{}", segment.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| (*key).to_owned()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("", vec![]),
+            ("/", vec![""]),
+            ("/tags", vec!["tags"]),
+            ("/steps/0/tags", vec!["steps", "0", "tags"]),
+            ("/a~1b/c~0d", vec!["a/b", "c~d"]),
+        ];
+        for (pointer, segments) in cases {
+            let actual = JsonPointer::parse(pointer).unwrap();
+            assert_eq!(actual.segments, path(&segments), "{pointer}");
+            assert_eq!(actual.to_string(), pointer);
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let pointer = JsonPointer::parse("/steps/*/tags").unwrap();
+        assert!(pointer.matches(&path(&["steps", "0", "tags"])));
+        assert!(pointer.matches(&path(&["steps", "12", "tags"])));
+        assert!(!pointer.matches(&path(&["steps", "0"])));
+        assert!(!pointer.matches(&path(&["steps", "0", "tags", "0"])));
+        assert!(!pointer.matches(&path(&["other", "0", "tags"])));
+
+        assert!(JsonPointer::parse("").unwrap().matches(&[]));
+    }
+
+    #[test]
+    fn strip_prefix_of() {
+        let pointer = JsonPointer::parse("/events/*").unwrap();
+        let event = path(&["events", "1"]);
+        let field = path(&["events", "1", "at"]);
+        assert_eq!(pointer.strip_prefix_of(&event), Some(&[][..]));
+        assert_eq!(pointer.strip_prefix_of(&field), Some(&field[2..]));
+        assert_eq!(pointer.strip_prefix_of(&path(&["events"])), None);
+        assert_eq!(pointer.strip_prefix_of(&path(&["other", "1"])), None);
+    }
+
+    #[test]
+    fn parse_requires_leading_slash() {
+        assert_eq!(
+            JsonPointer::parse("tags"),
+            Err("JSON Pointer `tags` must start with `/`".to_owned())
+        );
+    }
+}
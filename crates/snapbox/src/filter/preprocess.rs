@@ -0,0 +1,83 @@
+use std::any::TypeId;
+use std::sync::Arc;
+
+use super::Filter;
+use crate::data::DataFormat;
+use crate::Data;
+
+/// Filters to apply to `actual` before it is parsed as a [`DataFormat`]
+#[derive(Clone, Default)]
+pub(crate) struct Preprocessors {
+    filters: Vec<(DataFormat, Arc<dyn Filter + Send + Sync>)>,
+}
+
+impl Preprocessors {
+    pub(crate) fn insert(&mut self, format: DataFormat, filter: Arc<dyn Filter + Send + Sync>) {
+        self.filters.push((format, filter));
+    }
+
+    /// Apply, in registration order, each filter registered for `format`
+    pub(crate) fn apply(&self, format: DataFormat, mut data: Data) -> Data {
+        for (filter_format, filter) in &self.filters {
+            if *filter_format == format {
+                data = filter.filter(data);
+            }
+        }
+        data
+    }
+}
+
+impl std::fmt::Debug for Preprocessors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.filters.iter().map(|(format, _)| format))
+            .finish()
+    }
+}
+
+/// Filters to apply after the built-in normalization, before matching patterns
+///
+/// Filters added with [`FilterChain::set`] are keyed by their type so they can be replaced or
+/// removed, like for an option that can be turned off again.
+#[derive(Clone, Default)]
+pub(crate) struct FilterChain {
+    filters: Vec<(Option<TypeId>, Arc<dyn Filter + Send + Sync>)>,
+}
+
+impl FilterChain {
+    pub(crate) fn push(&mut self, filter: Arc<dyn Filter + Send + Sync>) {
+        self.filters.push((None, filter));
+    }
+
+    /// Replace the filter of the same type, keeping its place, or add `filter`
+    pub(crate) fn set<F: Filter + Send + Sync + 'static>(&mut self, filter: F) {
+        let key = Some(TypeId::of::<F>());
+        let filter = Arc::new(filter);
+        match self.filters.iter_mut().find(|(k, _)| *k == key) {
+            Some(existing) => existing.1 = filter,
+            None => self.filters.push((key, filter)),
+        }
+    }
+
+    /// Remove the filter of type `F` added with [`FilterChain::set`]
+    pub(crate) fn remove<F: Filter + Send + Sync + 'static>(&mut self) {
+        let key = Some(TypeId::of::<F>());
+        self.filters.retain(|(k, _)| *k != key);
+    }
+
+    /// Apply each filter in registration order
+    pub(crate) fn apply(&self, mut data: Data) -> Data {
+        for (_, filter) in &self.filters {
+            data = filter.filter(data);
+        }
+        data
+    }
+}
+
+impl std::fmt::Debug for FilterChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterChain")
+            .field("len", &self.filters.len())
+            .finish()
+    }
+}
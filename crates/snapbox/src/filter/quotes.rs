@@ -0,0 +1,102 @@
+use super::Filter;
+use crate::Data;
+
+/// Canonicalize the quotes around tokens
+///
+/// Tools can switch between `'x'` and `"x"` across versions.  This rewrites a quoted token to use
+/// the canonical quote so these compare equal.
+///
+/// To avoid corrupting text, a quote pair is only rewritten when
+/// - Both quotes are on the same line and the same character
+/// - The opening quote does not follow a letter or digit, so apostrophes are left alone
+/// - The closing quote is not followed by a letter or digit
+/// - The quoted content contains no other quote characters
+///
+/// Usually enabled through [`Assert::normalize_quotes`][crate::Assert::normalize_quotes].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterQuotes::new().filter("don't say 'hi'".into());
+/// assert_eq!(actual, snapbox::Data::text("don't say \"hi\""));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilterQuotes {
+    quotes: Vec<char>,
+    canonical: char,
+}
+
+impl FilterQuotes {
+    /// Rewrite `'` and `` ` `` quotes to `"`
+    pub fn new() -> Self {
+        Self {
+            quotes: vec!['\'', '"', '`'],
+            canonical: '"',
+        }
+    }
+
+    /// The quote characters to canonicalize
+    pub fn quotes(mut self, quotes: impl IntoIterator<Item = char>) -> Self {
+        self.quotes = quotes.into_iter().collect();
+        self
+    }
+
+    /// The quote character to rewrite to
+    pub fn canonical(mut self, quote: char) -> Self {
+        self.canonical = quote;
+        self
+    }
+
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        let is_quote = |c: char| c == self.canonical || self.quotes.contains(&c);
+        let mut normalized = String::with_capacity(text.len());
+        let mut prev = None;
+        let mut index = 0;
+        while let Some(c) = text[index..].chars().next() {
+            let opens =
+                self.quotes.contains(&c) && !prev.map(char::is_alphanumeric).unwrap_or(false);
+            let quoted = opens
+                .then(|| {
+                    let content_start = index + c.len_utf8();
+                    let content_len =
+                        text[content_start..].find(|next: char| next == '\n' || is_quote(next))?;
+                    let close = content_start + content_len;
+                    let after = close + c.len_utf8();
+                    let closes = text[close..].starts_with(c)
+                        && !text[after..]
+                            .chars()
+                            .next()
+                            .map(char::is_alphanumeric)
+                            .unwrap_or(false);
+                    closes.then_some((content_start..close, after))
+                })
+                .flatten();
+            if let Some((content, after)) = quoted {
+                normalized.push(self.canonical);
+                normalized.push_str(&text[content]);
+                normalized.push(self.canonical);
+                prev = Some(c);
+                index = after;
+            } else {
+                normalized.push(c);
+                prev = Some(c);
+                index += c.len_utf8();
+            }
+        }
+        normalized
+    }
+}
+
+impl Default for FilterQuotes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filter for FilterQuotes {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.normalize(text))
+    }
+}
@@ -22,6 +22,14 @@ pub struct Redactions {
         std::collections::BTreeMap<RedactedValueInner, std::collections::BTreeSet<&'static str>>,
     >,
     unused: Option<std::collections::BTreeSet<RedactedValueInner>>,
+    conditions: Option<std::collections::BTreeMap<&'static str, Condition>>,
+    priorities: Option<std::collections::BTreeMap<&'static str, i32>>,
+    secrets: Option<std::collections::BTreeSet<&'static str>>,
+    #[cfg(feature = "json")]
+    scoped: Vec<(super::pointer::JsonPointer, Redactions)>,
+    #[cfg(feature = "json")]
+    pointers: Vec<(super::pointer::JsonPointer, &'static str)>,
+    literals: LiteralCache,
 }
 
 impl Redactions {
@@ -29,17 +37,401 @@ impl Redactions {
         Self {
             vars: None,
             unused: None,
+            conditions: None,
+            priorities: None,
+            secrets: None,
+            #[cfg(feature = "json")]
+            scoped: Vec::new(),
+            #[cfg(feature = "json")]
+            pointers: Vec::new(),
+            literals: LiteralCache::new(),
         }
     }
 
     pub(crate) fn with_exe() -> Self {
         let mut redactions = Self::new();
+        redactions.insert_exe_preset().unwrap();
         redactions
-            .insert("[EXE]", std::env::consts::EXE_SUFFIX)
-            .unwrap();
+    }
+
+    /// Redactions for data commonly found in program output
+    ///
+    /// - `[UUID]`: UUIDs, like `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    /// - `[TIMESTAMP]`: RFC 3339 timestamps, like `2024-01-31T12:30:45.123Z`
+    /// - `[DURATION]`: elapsed time with a unit, like `1.23s` or `150ms`
+    /// - `[HEXADDR]`: hex addresses with at least 4 digits, like `0x7ffd5e8c`
+    ///
+    /// ```rust
+    /// let subst = snapbox::Redactions::with_common();
+    /// assert_eq!(
+    ///     subst.redact("request 67e55044-10b1-426f-9247-bb680e5fe0c8 took 1.23s"),
+    ///     "request [UUID] took [DURATION]"
+    /// );
+    /// ```
+    ///
+    /// Note: Requires feature flag `regex`
+    #[cfg(feature = "regex")]
+    pub fn with_common() -> Self {
+        let mut redactions = Self::new();
+        for (placeholder, pattern) in [
+            (
+                "[UUID]",
+                r"(?-u:\b)[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}(?-u:\b)",
+            ),
+            (
+                "[TIMESTAMP]",
+                r"(?-u:\b)[0-9]{4}-[0-9]{2}-[0-9]{2}[Tt ][0-9]{2}:[0-9]{2}:[0-9]{2}(?:\.[0-9]+)?(?:[Zz]|[+-][0-9]{2}:[0-9]{2})",
+            ),
+            (
+                "[DURATION]",
+                r"(?-u:\b)[0-9]+(?:\.[0-9]+)?(?:ns|us|µs|ms|s)(?-u:\b)",
+            ),
+            ("[HEXADDR]", r"(?-u:\b)0x[0-9a-fA-F]{4,}(?-u:\b)"),
+        ] {
+            let regex = regex::Regex::new(pattern).expect("common presets are valid");
+            redactions
+                .insert(placeholder, regex)
+                .expect("common presets are valid");
+        }
         redactions
     }
 
+    /// Load redactions shared between test binaries from a JSON file
+    ///
+    /// The file is an object of placeholders to values.  A value is a literal string, a
+    /// `{"regex": "<pattern>"}` object, a `{"glob": "<pattern>"}` object (see
+    /// [`RedactedValue::glob`]), or an array of these:
+    /// ```json
+    /// {
+    ///   "[HOST]": "build-server-01",
+    ///   "[ID]": {"regex": "[0-9]{6}"},
+    ///   "[LOG]": {"glob": "/tmp/*.log"},
+    ///   "[ROOT]": ["/home/ci", "/Users/ci"]
+    /// }
+    /// ```
+    ///
+    /// Placeholders are leaked to get a `'static` lifetime, so load the file once, like into a
+    /// `static`, rather than for every assertion.
+    ///
+    /// Note: Requires feature flag `json`; `regex` values also require feature flag `regex`
+    #[cfg(feature = "json")]
+    pub fn from_file(path: impl AsRef<Path>) -> crate::assert::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let config: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let serde_json::Value::Object(config) = config else {
+            return Err(format!("{}: expected an object of placeholders", path.display()).into());
+        };
+
+        let mut redactions = Self::new();
+        for (placeholder, values) in config {
+            let placeholder: &'static str = Box::leak(placeholder.into_boxed_str());
+            let values = match values {
+                serde_json::Value::Array(values) => values,
+                value => vec![value],
+            };
+            for value in values {
+                let value = redacted_value_from_json(value).map_err(|e| {
+                    format!("{}: invalid value for {placeholder}: {e}", path.display())
+                })?;
+                redactions
+                    .insert(placeholder, value)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+        Ok(redactions)
+    }
+
+    /// Redact the platform's executable suffix as `[EXE]`
+    ///
+    /// This matches `cargo.exe` on Windows and `cargo` elsewhere with `cargo[EXE]`.
+    /// On platforms without a suffix, `[EXE]` is disabled and removed from patterns.
+    ///
+    /// This is included in [`Assert`][crate::Assert]'s default redactions.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_exe_preset().unwrap();
+    /// let program = format!("cargo{}", std::env::consts::EXE_SUFFIX);
+    /// assert_eq!(subst.redact(&program), subst.clear_unused("cargo[EXE]"));
+    /// ```
+    pub fn insert_exe_preset(&mut self) -> crate::assert::Result<()> {
+        self.insert_exe_suffix(std::env::consts::EXE_SUFFIX)
+    }
+
+    fn insert_exe_suffix(&mut self, suffix: &'static str) -> crate::assert::Result<()> {
+        self.insert("[EXE]", suffix)
+    }
+
+    /// Redact directories commonly found in program output
+    ///
+    /// - `[ROOT]`: the workspace root, found from `CARGO_MANIFEST_DIR`
+    /// - `[CWD]`: the current directory, when it isn't `[ROOT]`
+    /// - `[HOME]`: the user's home directory, from `HOME` or `USERPROFILE`
+    /// - `[TEMP]`: [`std::env::temp_dir`]
+    ///
+    /// These are read at runtime.  When one directory is within another, the longer path is
+    /// redacted, like `[ROOT]/src` rather than `[HOME]/project/src`.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_dirs_preset().unwrap();
+    /// let path = std::env::temp_dir().join("output.txt");
+    /// let redacted = subst.redact(&path.display().to_string());
+    /// assert!(redacted.starts_with("[TEMP]"), "{redacted}");
+    /// ```
+    pub fn insert_dirs_preset(&mut self) -> crate::assert::Result<()> {
+        let cwd = std::env::current_dir().ok();
+        let root = std::env::var_os("CARGO_MANIFEST_DIR")
+            .and_then(|manifest_dir| {
+                PathBuf::from(manifest_dir)
+                    .ancestors()
+                    .filter(|dir| dir.join("Cargo.toml").exists())
+                    .last()
+                    .map(Path::to_owned)
+            })
+            .or_else(|| cwd.clone());
+        if let Some(root) = &root {
+            self.insert("[ROOT]", root)?;
+        }
+        if let Some(cwd) = cwd.filter(|cwd| Some(cwd) != root.as_ref()) {
+            self.insert("[CWD]", cwd)?;
+        }
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|home| !home.is_empty());
+        if let Some(home) = home {
+            let home = PathBuf::from(home).components().collect::<PathBuf>();
+            self.insert("[HOME]", home)?;
+        }
+        // Drop any trailing separator so it isn't redacted with the directory
+        let temp = std::env::temp_dir().components().collect::<PathBuf>();
+        self.insert("[TEMP]", temp)?;
+        Ok(())
+    }
+
+    /// Redact OS error messages that differ between platforms
+    ///
+    /// - `[NOT_FOUND]`: like `No such file or directory` or
+    ///   `The system cannot find the file specified.`
+    /// - `[PERMISSION_DENIED]`: like `Permission denied` or `Access is denied.`
+    /// - `[ALREADY_EXISTS]`: like `File exists` or
+    ///   `Cannot create a file when that file already exists.`
+    /// - `[DIR_NOT_EMPTY]`: like `Directory not empty` or `The directory is not empty.`
+    /// - `[NOT_A_DIRECTORY]`: like `Not a directory` or `The directory name is invalid.`
+    /// - `[IS_A_DIRECTORY]`: `Is a directory`
+    /// - `[CONNECTION_REFUSED]`: like `Connection refused` or
+    ///   `No connection could be made because the target machine actively refused it.`
+    /// - `[ADDR_IN_USE]`: like `Address already in use` or
+    ///   `Only one usage of each socket address [..] is normally permitted.`
+    ///
+    /// The Linux, macOS, and Windows messages are all redacted, wherever the tests run.  A
+    /// trailing `(os error <code>)`, as shown by [`std::io::Error`], is included in the
+    /// redaction as the codes also differ.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_os_errors_preset().unwrap();
+    /// assert_eq!(
+    ///     subst.redact("error: No such file or directory (os error 2)"),
+    ///     "error: [NOT_FOUND]"
+    /// );
+    /// assert_eq!(
+    ///     subst.redact("error: The system cannot find the path specified. (os error 3)"),
+    ///     "error: [NOT_FOUND]"
+    /// );
+    /// ```
+    pub fn insert_os_errors_preset(&mut self) -> crate::assert::Result<()> {
+        for (placeholder, messages) in OS_ERRORS {
+            for (message, codes) in *messages {
+                self.insert(placeholder, *message)?;
+                for code in *codes {
+                    self.insert(placeholder, format!("{message} (os error {code})"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Redact the value of the environment variable `name`, like an API token
+    ///
+    /// The variable is read when this is called.  When it is unset, empty, or not valid UTF-8,
+    /// `placeholder` is inserted without a value, like `[EXE]` on non-Windows platforms.
+    ///
+    /// The value is treated as a secret: it is never shown when reporting what placeholders
+    /// matched, see [`Assert::report_redactions`][crate::Assert::report_redactions].
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_env("[TOKEN]", "MY_API_TOKEN").unwrap();
+    /// let token = std::env::var("MY_API_TOKEN").unwrap_or_default();
+    /// if !token.is_empty() {
+    ///     assert_eq!(subst.redact(&format!("token={token}")), "token=[TOKEN]");
+    /// }
+    /// ```
+    pub fn insert_env(
+        &mut self,
+        placeholder: &'static str,
+        name: impl AsRef<std::ffi::OsStr>,
+    ) -> crate::assert::Result<()> {
+        let value = std::env::var(name).unwrap_or_default();
+        self.insert(placeholder, value)?;
+        self.secrets
+            .get_or_insert(std::collections::BTreeSet::new())
+            .insert(placeholder);
+        Ok(())
+    }
+
+    pub(crate) fn is_secret(&self, placeholder: &str) -> bool {
+        self.secrets
+            .as_ref()
+            .map(|secrets| secrets.contains(placeholder))
+            .unwrap_or(false)
+    }
+
+    /// Redact the package being tested and how it was built
+    ///
+    /// - `[CRATE_NAME]`: `name`, usually `env!("CARGO_PKG_NAME")`, as a path component like in
+    ///   `target/debug/[CRATE_NAME]`
+    /// - `[CRATE_VERSION]`: `version`, usually `env!("CARGO_PKG_VERSION")`
+    /// - `[TARGET]`: the target triple, like `x86_64-unknown-linux-gnu`
+    /// - `[PROFILE]`: the name of the profile's directory within the target directory, like
+    ///   `debug`, as a path component
+    ///
+    /// `name` and `version` are passed in as `env!` needs to be called from your package; see
+    /// [`cargo_redactions!`][crate::cargo_redactions] for a shorthand.  `[CRATE_NAME]` and
+    /// `[PROFILE]` are often common words, so they are only redacted after a `/` or `\` and
+    /// before the end of the path component.
+    ///
+    /// `[TARGET]` is `CARGO_BUILD_TARGET` when set at runtime and otherwise is assembled from
+    /// [`std::env::consts`] and `cfg!`, which covers the common targets, like
+    /// `aarch64-apple-darwin` and `x86_64-pc-windows-msvc`.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert_cargo_preset(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    ///     .unwrap();
+    /// let version = format!("bin/{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    /// assert_eq!(subst.redact(&version), "bin/[CRATE_NAME] [CRATE_VERSION]");
+    /// ```
+    pub fn insert_cargo_preset(
+        &mut self,
+        name: &'static str,
+        version: &'static str,
+    ) -> crate::assert::Result<()> {
+        self.insert(
+            "[CRATE_NAME]",
+            path_component("[CRATE_NAME]", name.to_owned()),
+        )?;
+        self.insert("[CRATE_VERSION]", version)?;
+        self.insert("[TARGET]", target_triple())?;
+        let profile = std::env::current_exe().ok().and_then(|mut path| {
+            path.pop();
+            if path.ends_with("deps") {
+                path.pop();
+            }
+            Some(path.file_name()?.to_str()?.to_owned())
+        });
+        if let Some(profile) = profile {
+            self.insert("[PROFILE]", path_component("[PROFILE]", profile))?;
+        }
+        Ok(())
+    }
+
+    /// Redact cargo's status lines that change between runs, for testing cargo plugins
+    ///
+    /// - `[ELAPSED]`: the time in `Finished ... in 0.53s` and `Downloaded ... in 1m 05s`
+    /// - `[LOCKING]`: the count in `Locking 5 packages to latest compatible versions`
+    /// - `[DOWNLOADING]`: the count and size in `Downloaded 3 crates (1.2 MB)` and the count in
+    ///   `Downloading 3 crates`
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_cargo_status_preset().unwrap();
+    /// let stderr = "\
+    ///     Locking 5 packages to latest compatible versions
+    ///  Downloaded 3 crates (1.2 MB) in 0.81s
+    ///    Finished `dev` profile [unoptimized + debuginfo] target(s) in 1m 05s
+    /// ";
+    /// assert_eq!(
+    ///     subst.redact(stderr),
+    ///     "\
+    ///     Locking [LOCKING] packages to latest compatible versions
+    ///  Downloaded [DOWNLOADING] in [ELAPSED]s
+    ///    Finished `dev` profile [unoptimized + debuginfo] target(s) in [ELAPSED]s
+    /// "
+    /// );
+    /// ```
+    ///
+    /// Note: Requires feature flag `regex`
+    #[cfg(feature = "regex")]
+    pub fn insert_cargo_status_preset(&mut self) -> crate::assert::Result<()> {
+        for (placeholder, pattern) in [
+            (
+                "[ELAPSED]",
+                r"(?m)^ *(?:Finished|Downloaded) .* in (?<redacted>[0-9]+m [0-9]+|[0-9]+(?:\.[0-9]+)?)s$",
+            ),
+            ("[LOCKING]", r"(?m)^ *Locking (?<redacted>[0-9]+) packages?"),
+            (
+                "[DOWNLOADING]",
+                r"(?m)^ *Downloaded (?<redacted>[0-9]+ crates? \([0-9.]+ ?[KMG]?i?B\))",
+            ),
+            (
+                "[DOWNLOADING]",
+                r"(?m)^ *Downloading (?<redacted>[0-9]+) crates?",
+            ),
+        ] {
+            let regex = regex::Regex::new(pattern).expect("cargo status presets are valid");
+            self.insert(placeholder, regex)?;
+        }
+        Ok(())
+    }
+
+    /// Redact Unix epoch timestamps as `[EPOCH]`
+    ///
+    /// This matches seconds (10 digits) and milliseconds (13 digits) from 2001 through 2033, as
+    /// whole words.  The range is kept narrow so other large integers, like IDs and sizes, aren't
+    /// redacted.  Parts of decimal numbers, like `1706659200.5`, are left alone.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_epoch_preset().unwrap();
+    /// assert_eq!(subst.redact("created 1700000000123"), "created [EPOCH]");
+    /// assert_eq!(subst.redact("id 9700000000123"), "id 9700000000123");
+    /// assert_eq!(subst.redact("took 1706659200.5s"), "took 1706659200.5s");
+    /// ```
+    ///
+    /// Note: Requires feature flag `regex`
+    #[cfg(feature = "regex")]
+    pub fn insert_epoch_preset(&mut self) -> crate::assert::Result<()> {
+        let epoch = regex::Regex::new(
+            r"(?:^|[^.0-9A-Za-z_])(?<redacted>1[0-9]{9}(?:[0-9]{3})?)(?:$|[^.0-9A-Za-z_]|\.(?:$|[^0-9]))",
+        )
+        .expect("epoch preset is valid");
+        self.insert("[EPOCH]", epoch)
+    }
+
+    /// Redact ISO 8601 week dates, like `2024-W05` and `2024-W05-3`, as `[DATE]`
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_iso_week_preset().unwrap();
+    /// assert_eq!(subst.redact("released 2024-W05-3"), "released [DATE]");
+    /// ```
+    ///
+    /// Note: Requires feature flag `regex`
+    #[cfg(feature = "regex")]
+    pub fn insert_iso_week_preset(&mut self) -> crate::assert::Result<()> {
+        let week = regex::Regex::new(
+            r"(?-u:\b)(?<redacted>[0-9]{4}-W(?:0[1-9]|[1-4][0-9]|5[0-3])(?:-[1-7])?)(?:$|[^-0-9A-Za-z_])",
+        )
+        .expect("iso week preset is valid");
+        self.insert("[DATE]", week)
+    }
+
     /// Insert an additional match pattern
     ///
     /// `placeholder` must be enclosed in `[` and `]`.
@@ -62,6 +454,12 @@ impl Redactions {
     /// assert_eq!(subst.redact("Hello other!"), "Hello other!");
     /// # }
     /// ```
+    ///
+    /// Literal values can span multiple lines, like a license header, and are replaced as a unit.
+    /// They match whether lines end with `\n` or `\r\n`.
+    ///
+    /// For simple wildcards without the `regex` feature, see [`RedactedValue::glob`].  For logic
+    /// that can't be expressed as a literal, glob, or regex, see [`RedactedValue::from_fn`].
     pub fn insert(
         &mut self,
         placeholder: &'static str,
@@ -69,6 +467,7 @@ impl Redactions {
     ) -> crate::assert::Result<()> {
         let placeholder = validate_placeholder(placeholder)?;
         let value = value.into();
+        self.literals.clear();
         if let Some(value) = value.inner {
             self.vars
                 .get_or_insert(std::collections::BTreeMap::new())
@@ -83,6 +482,190 @@ impl Redactions {
         Ok(())
     }
 
+    /// Insert a match pattern that only applies while `predicate` returns `true`
+    ///
+    /// `predicate` is checked each time redactions are applied, like when comparing, so this
+    /// can depend on the environment.  While it returns `false`, `placeholder` is skipped as if
+    /// it were never inserted: `actual` keeps the original text and `placeholder` in expected
+    /// data is not a wildcard, only matching itself.  The predicate applies to every value of
+    /// `placeholder`.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert_if("[PORT]", "54321", || std::env::var_os("CI").is_some())
+    ///     .unwrap();
+    /// let expected = if std::env::var_os("CI").is_some() {
+    ///     "listening on [PORT]"
+    /// } else {
+    ///     "listening on 54321"
+    /// };
+    /// assert_eq!(subst.redact("listening on 54321"), expected);
+    /// ```
+    pub fn insert_if(
+        &mut self,
+        placeholder: &'static str,
+        value: impl Into<RedactedValue>,
+        predicate: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> crate::assert::Result<()> {
+        self.insert(placeholder, value)?;
+        self.conditions
+            .get_or_insert(std::collections::BTreeMap::new())
+            .insert(placeholder, Condition(std::sync::Arc::new(predicate)));
+        Ok(())
+    }
+
+    /// Number each distinct text matching `value`, like `[TMP_1]` and `[TMP_2]` for `[TMP]`
+    ///
+    /// Unlike a single placeholder, this keeps track of which lines refer to the same value.
+    /// Numbers start at 1 and go in the order each value first appears in the redacted document,
+    /// so all of the strings in a json value share one document.  This runs with
+    /// [`RedactedValue::from_fn`] redactions, after literal and regex redactions.
+    ///
+    /// `value` is usually a regex or [glob][RedactedValue::glob] so it can match different text.
+    ///
+    /// ```rust
+    /// use snapbox::filter::RedactedValue;
+    ///
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert_numbered("[TMP]", RedactedValue::glob("/tmp/.tmp??????"))
+    ///     .unwrap();
+    /// let log = "\
+    /// created /tmp/.tmpAbC123
+    /// created /tmp/.tmpXyZ789
+    /// removed /tmp/.tmpAbC123
+    /// ";
+    /// assert_eq!(
+    ///     subst.redact(log),
+    ///     "created [TMP_1]\ncreated [TMP_2]\nremoved [TMP_1]\n"
+    /// );
+    /// ```
+    pub fn insert_numbered(
+        &mut self,
+        placeholder: &'static str,
+        value: impl Into<RedactedValue>,
+    ) -> crate::assert::Result<()> {
+        let placeholder = validate_placeholder(placeholder)?;
+        let Some(value) = value.into().inner else {
+            return self.insert(placeholder, "");
+        };
+        if matches!(value, RedactedValueInner::Fn(_)) {
+            return Err(format!("`{placeholder}` can't number `RedactedValue::from_fn`").into());
+        }
+        let name = &placeholder[1..(placeholder.len() - 1)];
+        self.insert(
+            placeholder,
+            RedactedValue {
+                inner: Some(RedactedValueInner::Numbered(Numbered {
+                    id: next_fn_id(),
+                    name,
+                    value: Box::new(value),
+                })),
+            },
+        )
+    }
+
+    /// Redact a range of character columns on each line, like a fixed-width log prefix
+    ///
+    /// Columns start at 0.  Only lines long enough to contain the whole range are redacted, so
+    /// blank and short continuation lines are left alone; with an open end, like `20..`, lines
+    /// need to extend past the start.  A line with `placeholder` already at the start column is
+    /// left alone, so redacting again is a no-op.
+    ///
+    /// This only applies to text; json strings are left alone as their columns don't line up with
+    /// the rendered value.  This runs with [`RedactedValue::from_fn`] redactions, after literal
+    /// and regex redactions, so columns are counted in the partially redacted text.  This works
+    /// best for a prefix that nothing else redacts.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert_columns("[TIMESTAMP]", 0..19).unwrap();
+    /// let log = "2024-01-31 12:30:45 INFO starting\n\n2024-01-31 12:30:46 INFO done\n";
+    /// assert_eq!(
+    ///     subst.redact(log),
+    ///     "[TIMESTAMP] INFO starting\n\n[TIMESTAMP] INFO done\n"
+    /// );
+    /// ```
+    pub fn insert_columns(
+        &mut self,
+        placeholder: &'static str,
+        columns: impl std::ops::RangeBounds<usize>,
+    ) -> crate::assert::Result<()> {
+        let start = match columns.start_bound() {
+            std::ops::Bound::Included(start) => *start,
+            std::ops::Bound::Excluded(start) => start + 1,
+            std::ops::Bound::Unbounded => 0,
+        };
+        let end = match columns.end_bound() {
+            std::ops::Bound::Included(end) => Some(end + 1),
+            std::ops::Bound::Excluded(end) => Some(*end),
+            std::ops::Bound::Unbounded => None,
+        };
+        if end.map(|end| end <= start).unwrap_or(false) {
+            return Err(format!("Columns for `{placeholder}` are empty").into());
+        }
+        self.insert(
+            placeholder,
+            RedactedValue {
+                inner: Some(RedactedValueInner::Columns(Columns {
+                    id: next_fn_id(),
+                    placeholder,
+                    start,
+                    end,
+                })),
+            },
+        )
+    }
+
+    /// Apply `placeholder` before redactions with a lower priority
+    ///
+    /// By default, all redactions have a priority of `0`.  Within a priority, literals are applied
+    /// first, preferring the longest match, followed by regexes and then
+    /// [`RedactedValue::from_fn`].  Use priorities when that doesn't pick the intended
+    /// placeholder, like for a regex that overlaps a literal.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "regex")] {
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[HOME]", "/home/user").unwrap();
+    /// subst
+    ///     .insert("[PROJECT]", regex::Regex::new("/home/[a-z]+/project").unwrap())
+    ///     .unwrap();
+    /// assert_eq!(subst.redact("/home/user/project"), "[HOME]/project");
+    ///
+    /// subst.set_priority("[PROJECT]", 1).unwrap();
+    /// assert_eq!(subst.redact("/home/user/project"), "[PROJECT]");
+    /// # }
+    /// ```
+    pub fn set_priority(
+        &mut self,
+        placeholder: &'static str,
+        priority: i32,
+    ) -> crate::assert::Result<()> {
+        let placeholder = validate_placeholder(placeholder)?;
+        self.priorities
+            .get_or_insert(std::collections::BTreeMap::new())
+            .insert(placeholder, priority);
+        Ok(())
+    }
+
+    fn priority(&self, placeholder: &str) -> i32 {
+        self.priorities
+            .as_ref()
+            .and_then(|priorities| priorities.get(placeholder))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn is_enabled(&self, placeholder: &str) -> bool {
+        self.conditions
+            .as_ref()
+            .and_then(|conditions| conditions.get(placeholder))
+            .map(|condition| (condition.0)())
+            .unwrap_or(true)
+    }
+
     /// Insert additional match patterns
     ///
     /// Placeholders must be enclosed in `[` and `]`.
@@ -98,63 +681,837 @@ impl Redactions {
 
     pub fn remove(&mut self, placeholder: &'static str) -> crate::assert::Result<()> {
         let placeholder = validate_placeholder(placeholder)?;
+        self.literals.clear();
         self.vars
             .get_or_insert(std::collections::BTreeMap::new())
             .retain(|_value, placeholders| {
                 placeholders.retain(|p| *p != placeholder);
                 !placeholders.is_empty()
             });
+        if let Some(conditions) = self.conditions.as_mut() {
+            conditions.remove(placeholder);
+        }
+        if let Some(priorities) = self.priorities.as_mut() {
+            priorities.remove(placeholder);
+        }
+        if let Some(secrets) = self.secrets.as_mut() {
+            secrets.remove(placeholder);
+        }
+        #[cfg(feature = "json")]
+        self.pointers.retain(|(_pointer, p)| *p != placeholder);
+        Ok(())
+    }
+
+    /// Pin placeholders to the values they matched in an earlier assertion
+    ///
+    /// Any existing values for each placeholder are replaced, so later assertions only match
+    /// the exact value captured before.  See [`Comparison::bindings`][crate::assert::Comparison::bindings].
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[ID]", "1234").unwrap();
+    /// subst.insert_bindings([("[ID]", "5678".to_owned())]).unwrap();
+    /// assert_eq!(subst.redact("user 5678"), "user [ID]");
+    /// assert_eq!(subst.redact("user 1234"), "user 1234");
+    /// ```
+    pub fn insert_bindings(
+        &mut self,
+        bindings: impl IntoIterator<Item = (&'static str, impl Into<RedactedValue>)>,
+    ) -> crate::assert::Result<()> {
+        for (placeholder, value) in bindings {
+            self.remove(placeholder)?;
+            self.insert(placeholder, value)?;
+        }
         Ok(())
     }
 
-    /// Apply redaction only, no pattern-dependent globs
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// let mut subst = snapbox::Redactions::new();
-    /// subst.insert("[LOCATION]", "World");
-    /// let output = subst.redact("Hello World!");
-    /// assert_eq!(output, "Hello [LOCATION]!");
-    /// ```
-    pub fn redact(&self, input: &str) -> String {
-        let mut input = input.to_owned();
-        replace_many(
-            &mut input,
-            self.vars
-                .iter()
-                .flatten()
-                .flat_map(|(value, placeholders)| {
-                    placeholders
-                        .iter()
-                        .map(move |placeholder| (value, *placeholder))
-                }),
-        );
-        input
+    /// Add all of `other`'s redactions, including [scoped][Redactions::scoped] ones
+    pub fn merge(&mut self, other: Redactions) {
+        self.literals.clear();
+        for (value, placeholders) in other.vars.into_iter().flatten() {
+            self.vars
+                .get_or_insert(std::collections::BTreeMap::new())
+                .entry(value)
+                .or_default()
+                .extend(placeholders);
+        }
+        self.unused
+            .get_or_insert(std::collections::BTreeSet::new())
+            .extend(other.unused.into_iter().flatten());
+        if let Some(conditions) = other.conditions {
+            self.conditions
+                .get_or_insert(std::collections::BTreeMap::new())
+                .extend(conditions);
+        }
+        if let Some(priorities) = other.priorities {
+            self.priorities
+                .get_or_insert(std::collections::BTreeMap::new())
+                .extend(priorities);
+        }
+        if let Some(secrets) = other.secrets {
+            self.secrets
+                .get_or_insert(std::collections::BTreeSet::new())
+                .extend(secrets);
+        }
+        #[cfg(feature = "json")]
+        self.scoped.extend(other.scoped);
+        #[cfg(feature = "json")]
+        self.pointers.extend(other.pointers);
+    }
+
+    /// Only apply these redactions to JSON values under the `prefix` [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901)
+    ///
+    /// A `*` segment matches any object key or array index.  Scoped redactions are relative to
+    /// their prefix, so scoping them again nests them further.  This lets a set of redactions
+    /// for one structure be reused wherever it appears, see [`Redactions::merge`].
+    ///
+    /// Scoped redactions take precedence over unscoped ones and don't apply to text.
+    ///
+    /// ```rust
+    /// let mut event = snapbox::Redactions::new();
+    /// event.insert("[USER]", "alice").unwrap();
+    ///
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.merge(event.scoped("/events/*").unwrap());
+    ///
+    /// let mut actual = serde_json::json!({"owner": "alice", "events": [{"by": "alice"}]});
+    /// subst.redact_json(&mut actual);
+    /// assert_eq!(actual, serde_json::json!({"owner": "alice", "events": [{"by": "[USER]"}]}));
+    /// ```
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn scoped(&self, prefix: &str) -> crate::assert::Result<Self> {
+        let prefix = super::pointer::JsonPointer::parse(prefix)?;
+        Ok(Self {
+            vars: None,
+            // Still clear unused placeholders from patterns outside of the scope
+            unused: self.unused.clone(),
+            conditions: None,
+            priorities: None,
+            secrets: None,
+            scoped: vec![(prefix, self.clone())],
+            pointers: Vec::new(),
+            literals: LiteralCache::new(),
+        })
+    }
+
+    /// Replace whole JSON values with `placeholder` at the `pointer` [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901)
+    ///
+    /// A `*` segment matches any object key or array index.  Unlike [`Redactions::insert`], this
+    /// picks values by where they are rather than what they look like, so a timestamp field can
+    /// be redacted without touching the same text elsewhere.  Any value is replaced, including
+    /// numbers, objects, and `null`, and it takes precedence over other redactions within it.
+    ///
+    /// For [`DataFormat::JsonLines`][crate::data::DataFormat::JsonLines], `pointer` is relative to each
+    /// line.  This doesn't apply to text.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert_json_pointer("[TIMESTAMP]", "/items/*/created_at")
+    ///     .unwrap();
+    ///
+    /// let mut actual = serde_json::json!({
+    ///     "created_at": "2024-01-31",
+    ///     "items": [{"name": "2024-01-31", "created_at": 1706659200}],
+    /// });
+    /// subst.redact_json(&mut actual);
+    /// assert_eq!(
+    ///     actual,
+    ///     serde_json::json!({
+    ///         "created_at": "2024-01-31",
+    ///         "items": [{"name": "2024-01-31", "created_at": "[TIMESTAMP]"}],
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn insert_json_pointer(
+        &mut self,
+        placeholder: &'static str,
+        pointer: &str,
+    ) -> crate::assert::Result<()> {
+        let placeholder = validate_placeholder(placeholder)?;
+        let pointer = super::pointer::JsonPointer::parse(pointer)
+            .map_err(|err| format!("{err} for `{placeholder}`"))?;
+        self.pointers.push((pointer, placeholder));
+        Ok(())
+    }
+
+    /// The placeholder for the whole value at `path`, see [`Redactions::insert_json_pointer`]
+    #[cfg(feature = "json")]
+    fn pointer_placeholder(&self, path: &[String]) -> Option<&'static str> {
+        let found = self
+            .pointers
+            .iter()
+            .find(|(pointer, _placeholder)| pointer.matches(path))
+            .map(|(_pointer, placeholder)| *placeholder);
+        found.or_else(|| {
+            self.scoped.iter().find_map(|(pointer, scoped)| {
+                pointer
+                    .strip_prefix_of(path)
+                    .and_then(|rest| scoped.pointer_placeholder(rest))
+            })
+        })
+    }
+
+    /// Apply redactions to all strings within `value`, including [scoped][Redactions::scoped] ones
+    ///
+    /// Note: Requires feature flag `json`
+    #[cfg(feature = "json")]
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        self.redact_json_at(value, &mut Vec::new(), &mut Document::json());
+    }
+
+    #[cfg(feature = "json")]
+    fn redact_json_at(
+        &self,
+        value: &mut serde_json::Value,
+        path: &mut Vec<String>,
+        document: &mut Document,
+    ) {
+        if let Some(placeholder) = self.pointer_placeholder(path) {
+            *value = serde_json::Value::String(placeholder.to_owned());
+            return;
+        }
+        match value {
+            serde_json::Value::String(str) => {
+                *str = self.redact_at(str, path, document);
+            }
+            serde_json::Value::Array(arr) => {
+                for (index, value) in arr.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    self.redact_json_at(value, path, document);
+                    path.pop();
+                }
+            }
+            serde_json::Value::Object(obj) => {
+                for (key, mut value) in std::mem::replace(obj, serde_json::Map::new()) {
+                    let redacted_key = self.redact_at(&key, path, document);
+                    path.push(key);
+                    self.redact_json_at(&mut value, path, document);
+                    path.pop();
+                    obj.insert(redacted_key, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Redact `input` found at `path` within a JSON value
+    #[cfg(feature = "json")]
+    fn redact_at(&self, input: &str, path: &[String], document: &mut Document) -> String {
+        let mut redacted = None;
+        for (pointer, scoped) in &self.scoped {
+            if let Some(rest) = pointer.strip_prefix_of(path) {
+                let input = redacted.as_deref().unwrap_or(input);
+                redacted = Some(scoped.redact_at(input, rest, document));
+            }
+        }
+        self.redact_in(redacted.as_deref().unwrap_or(input), false, document)
+    }
+
+    /// Apply redaction only, no pattern-dependent globs
+    ///
+    /// Literal values are replaced in a single pass, preferring the longest match at each
+    /// position.  Regexes and [`RedactedValue::from_fn`] are applied afterwards.  See
+    /// [`Redactions::set_priority`] to change the order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[LOCATION]", "World");
+    /// let output = subst.redact("Hello World!");
+    /// assert_eq!(output, "Hello [LOCATION]!");
+    /// ```
+    pub fn redact(&self, input: &str) -> String {
+        self.redact_case(input, false)
+    }
+
+    /// Like [`Redactions::redact`] but literal values may match ignoring ASCII case
+    ///
+    /// Regexes and [`RedactedValue::from_fn`] are applied as-is.
+    pub(crate) fn redact_case(&self, input: &str, case_insensitive: bool) -> String {
+        self.redact_in(input, case_insensitive, &mut Document::text())
+    }
+
+    /// Like [`Redactions::redact_case`] but for `input` as a part of `document`, like one string
+    /// in a json value
+    pub(crate) fn redact_in(
+        &self,
+        input: &str,
+        case_insensitive: bool,
+        document: &mut Document,
+    ) -> String {
+        let mut input = input.to_owned();
+        let vars = self
+            .vars
+            .iter()
+            .flatten()
+            .flat_map(|(value, placeholders)| {
+                placeholders
+                    .iter()
+                    .filter(|placeholder| self.is_enabled(placeholder))
+                    .map(move |placeholder| (value, *placeholder))
+            });
+        let Some(priorities) = self.priorities.as_ref() else {
+            replace_many(&mut input, vars, case_insensitive, &self.literals, document);
+            return input;
+        };
+
+        let mut levels: Vec<_> = priorities.values().copied().chain([0]).collect();
+        levels.sort_unstable_by(|a, b| b.cmp(a));
+        levels.dedup();
+        for level in levels {
+            replace_many(
+                &mut input,
+                vars.clone()
+                    .filter(|(_, placeholder)| self.priority(placeholder) == level),
+                case_insensitive,
+                &self.literals,
+                document,
+            );
+        }
+        input
+    }
+
+    /// The first text each enabled placeholder matches in `input`, for reporting failures
+    ///
+    /// Placeholders redacted with [`RedactedValue::from_fn`] are skipped as what they match is
+    /// unknown.
+    pub(crate) fn first_matches(&self, input: &str) -> Vec<(&'static str, Option<String>)> {
+        let mut matches: Vec<(&'static str, Option<String>)> = Vec::new();
+        for (value, placeholders) in self.vars.iter().flatten() {
+            if matches!(
+                value,
+                RedactedValueInner::Fn(_)
+                    | RedactedValueInner::Numbered(_)
+                    | RedactedValueInner::Columns(_)
+            ) {
+                continue;
+            }
+            let found = value.find_in(input).map(|range| input[range].to_owned());
+            for placeholder in placeholders {
+                if !self.is_enabled(placeholder) {
+                    continue;
+                }
+                match matches
+                    .iter_mut()
+                    .find(|(existing, _)| existing == placeholder)
+                {
+                    Some((_, existing)) => {
+                        if existing.is_none() {
+                            *existing = found.clone();
+                        }
+                    }
+                    None => matches.push((placeholder, found.clone())),
+                }
+            }
+        }
+        matches.sort_by_key(|(placeholder, _)| *placeholder);
+        matches
+    }
+
+    /// Check that all redactions are well-formed
+    ///
+    /// This reports regexes and globs that can match an empty string, which would redact between
+    /// every character.  Placeholders are already checked when they are inserted.
+    ///
+    /// Empty values are not errors; they disable the placeholder, like `[EXE]` on non-Windows
+    /// platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[LOCATION]", "World").unwrap();
+    /// subst.insert("[EMPTY]", "").unwrap();
+    /// assert!(subst.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<RedactionError>> {
+        let mut errors = Vec::new();
+        for (value, placeholders) in self.vars.iter().flatten() {
+            for placeholder in placeholders {
+                if value.find_in("").is_some() {
+                    errors.push(RedactionError::new(
+                        placeholder,
+                        format!("`{}` matches an empty string", value.as_cmp().2),
+                    ));
+                }
+            }
+        }
+        #[cfg(feature = "json")]
+        for (_pointer, scoped) in &self.scoped {
+            if let Err(scoped_errors) = scoped.validate() {
+                errors.extend(scoped_errors);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Iterate over the registered placeholders and their values
+    ///
+    /// Values are the literal text or the regex pattern.  The value is empty for placeholders
+    /// registered with an empty value, like `[EXE]` on platforms without a suffix, and for
+    /// [`RedactedValue::from_fn`].  A placeholder with multiple values is yielded once for each.
+    /// [Scoped][Redactions::scoped] redactions are not included.
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[LOCATION]", "World").unwrap();
+    /// subst.insert("[EMPTY]", "").unwrap();
+    /// let mut vars: Vec<_> = subst.iter().collect();
+    /// vars.sort();
+    /// assert_eq!(vars, [("[EMPTY]", ""), ("[LOCATION]", "World")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> + '_ {
+        let vars = self
+            .vars
+            .iter()
+            .flatten()
+            .flat_map(|(value, placeholders)| {
+                let value = value.as_cmp().2;
+                placeholders
+                    .iter()
+                    .map(move |placeholder| (*placeholder, value))
+            });
+        let unused = self
+            .unused
+            .iter()
+            .flatten()
+            .filter_map(|value| match value {
+                RedactedValueInner::Str(placeholder) => Some((*placeholder, "")),
+                _ => None,
+            });
+        vars.chain(unused)
+    }
+
+    /// Check if `placeholder` is registered, including with an empty value or a condition
+    ///
+    /// ```rust
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst.insert("[LOCATION]", "World").unwrap();
+    /// assert!(subst.contains("[LOCATION]"));
+    /// assert!(!subst.contains("[NAME]"));
+    /// ```
+    pub fn contains(&self, placeholder: &str) -> bool {
+        let found =
+            self.iter().any(|(p, _value)| p == placeholder) || self.is_numbered_as(placeholder);
+        #[cfg(feature = "json")]
+        let found = found
+            || self
+                .scoped
+                .iter()
+                .any(|(_pointer, scoped)| scoped.contains(placeholder))
+            || self.pointers.iter().any(|(_pointer, p)| *p == placeholder);
+        found
+    }
+
+    /// Whether `placeholder` is what an [`insert_numbered`][Redactions::insert_numbered]
+    /// placeholder is numbered as, like `[TMP_1]` for `[TMP]`
+    fn is_numbered_as(&self, placeholder: &str) -> bool {
+        let Some((name, number)) = placeholder
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+            .and_then(|inner| inner.rsplit_once('_'))
+        else {
+            return false;
+        };
+        !number.is_empty()
+            && number.bytes().all(|b| b.is_ascii_digit())
+            && self.vars.iter().flatten().any(|(value, _placeholders)| {
+                matches!(value, RedactedValueInner::Numbered(n) if n.name == name)
+            })
+    }
+
+    pub(crate) fn find_placeholder(&self, placeholder: &str) -> Option<&'static str> {
+        let found = self
+            .vars
+            .iter()
+            .flatten()
+            .find_map(|(_value, placeholders)| placeholders.get(placeholder).copied())
+            .filter(|placeholder| self.is_enabled(placeholder));
+        #[cfg(feature = "json")]
+        let found = found.or_else(|| {
+            self.scoped
+                .iter()
+                .find_map(|(_pointer, scoped)| scoped.find_placeholder(placeholder))
+        });
+        found
+    }
+
+    pub(crate) fn is_placeholder(&self, placeholder: &str) -> bool {
+        self.find_placeholder(placeholder).is_some()
+    }
+
+    /// Clear unused redactions from expected data
+    ///
+    /// Some redactions can be conditionally present, like redacting [`std::env::consts::EXE_SUFFIX`].
+    /// When the redaction is not present, it needs to be removed from the expected data so it can
+    /// be matched against the actual data.
+    pub fn clear_unused<'v>(&self, pattern: &'v str) -> Cow<'v, str> {
+        if !self.unused.as_ref().map(|s| s.is_empty()).unwrap_or(false) && pattern.contains('[') {
+            let mut cleared = String::with_capacity(pattern.len());
+            let mut rest = pattern;
+            loop {
+                // `[literal:<text>]` is left as-is
+                let escape = rest.find("[literal:").and_then(|start| {
+                    super::token::parse_literal(&rest[start..]).map(|(_text, len)| (start, len))
+                });
+                let (start, len) = escape.unwrap_or((rest.len(), 0));
+                let mut outside = rest[..start].to_owned();
+                replace_many(
+                    &mut outside,
+                    self.unused.iter().flatten().map(|var| (var, "")),
+                    false,
+                    &self.literals,
+                    &mut Document::text(),
+                );
+                cleared.push_str(&outside);
+                cleared.push_str(&rest[start..(start + len)]);
+                rest = &rest[(start + len)..];
+                if escape.is_none() {
+                    break;
+                }
+            }
+            Cow::Owned(cleared)
+        } else {
+            Cow::Borrowed(pattern)
+        }
+    }
+}
+
+/// Messages for [`Redactions::insert_os_errors_preset`] with their `(os error <code>)`s
+const OS_ERRORS: &[(&str, OsErrorMessages)] = &[
+    (
+        "[NOT_FOUND]",
+        &[
+            ("No such file or directory", &[2]),
+            ("The system cannot find the file specified.", &[2]),
+            ("The system cannot find the path specified.", &[3]),
+        ],
+    ),
+    (
+        "[PERMISSION_DENIED]",
+        &[
+            ("Permission denied", &[13]),
+            ("Operation not permitted", &[1]),
+            ("Access is denied.", &[5]),
+        ],
+    ),
+    (
+        "[ALREADY_EXISTS]",
+        &[
+            ("File exists", &[17]),
+            ("The file exists.", &[80]),
+            ("Cannot create a file when that file already exists.", &[183]),
+        ],
+    ),
+    (
+        "[DIR_NOT_EMPTY]",
+        &[
+            ("Directory not empty", &[39, 66]),
+            ("The directory is not empty.", &[145]),
+        ],
+    ),
+    (
+        "[NOT_A_DIRECTORY]",
+        &[
+            ("Not a directory", &[20]),
+            ("The directory name is invalid.", &[267]),
+        ],
+    ),
+    ("[IS_A_DIRECTORY]", &[("Is a directory", &[21])]),
+    (
+        "[CONNECTION_REFUSED]",
+        &[
+            ("Connection refused", &[61, 111]),
+            (
+                "No connection could be made because the target machine actively refused it.",
+                &[10061],
+            ),
+        ],
+    ),
+    (
+        "[ADDR_IN_USE]",
+        &[
+            ("Address already in use", &[48, 98]),
+            (
+                "Only one usage of each socket address (protocol/network address/port) is normally permitted.",
+                &[10048],
+            ),
+        ],
+    ),
+];
+
+/// Each message and its codes
+type OsErrorMessages = &'static [(&'static str, &'static [i32])];
+
+/// A malformed redaction, see [`Redactions::validate`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedactionError {
+    placeholder: String,
+    message: String,
+}
+
+impl RedactionError {
+    fn new(placeholder: &str, message: impl std::fmt::Display) -> Self {
+        Self {
+            placeholder: placeholder.to_owned(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
+    }
+}
+
+impl std::fmt::Display for RedactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.placeholder, self.message)
+    }
+}
+
+impl std::error::Error for RedactionError {}
+
+/// A predicate for [`Redactions::insert_if`]
+#[derive(Clone)]
+struct Condition(std::sync::Arc<dyn Fn() -> bool + Send + Sync>);
+
+impl std::fmt::Debug for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Condition(..)")
+    }
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Condition {}
+
+#[derive(Clone)]
+pub struct RedactedValue {
+    inner: Option<RedactedValueInner>,
+}
+
+impl RedactedValue {
+    /// Redact with custom logic, like normalizing hash prefixes
+    ///
+    /// `redact` is given the whole text and returns it with the placeholder written in place of
+    /// what was redacted.  These run after literal and regex redactions, in the order they were
+    /// inserted.
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use snapbox::filter::RedactedValue;
+    ///
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert(
+    ///         "[ADDR]",
+    ///         RedactedValue::from_fn(|text: &str| {
+    ///             if !text.contains("0x") {
+    ///                 return Cow::Borrowed(text);
+    ///             }
+    ///             let words = text.split(' ').map(|word| {
+    ///                 if word.starts_with("0x") { "[ADDR]" } else { word }
+    ///             });
+    ///             Cow::Owned(words.collect::<Vec<_>>().join(" "))
+    ///         }),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(subst.redact("freed 0x7ffd5e8c at exit"), "freed [ADDR] at exit");
+    /// ```
+    pub fn from_fn(redact: impl Fn(&str) -> Cow<'_, str> + Send + Sync + 'static) -> Self {
+        Self {
+            inner: Some(RedactedValueInner::Fn(RedactFn {
+                id: next_fn_id(),
+                redact: std::sync::Arc::new(redact),
+            })),
+        }
+    }
+
+    /// Redact text matching a glob, a lighter-weight alternative to a regex
+    ///
+    /// - `*` matches any run of characters other than whitespace, including none
+    /// - `?` matches one character other than whitespace
+    ///
+    /// Other characters match themselves.  Like a regex, the earliest match wins, with `*`
+    /// matching as much as it can.
+    ///
+    /// ```rust
+    /// use snapbox::filter::RedactedValue;
+    ///
+    /// let mut subst = snapbox::Redactions::new();
+    /// subst
+    ///     .insert("[TMP]", RedactedValue::glob("/tmp/.tmp??????/*.log"))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     subst.redact("wrote /tmp/.tmpA1b2C3/build.log: ok"),
+    ///     "wrote [TMP]: ok"
+    /// );
+    /// ```
+    pub fn glob(pattern: &str) -> Self {
+        if pattern.is_empty() {
+            Self { inner: None }
+        } else {
+            Self {
+                inner: Some(RedactedValueInner::Glob(Glob::new(pattern))),
+            }
+        }
+    }
+}
+
+/// A pattern for [`RedactedValue::glob`]
+#[derive(Clone, Debug)]
+struct Glob {
+    pattern: String,
+    parts: Vec<GlobPart>,
+}
+
+#[derive(Clone, Debug)]
+enum GlobPart {
+    Literal(String),
+    /// `*`
+    Many,
+    /// `?`
+    One,
+}
+
+impl Glob {
+    fn new(pattern: &str) -> Self {
+        let mut parts = Vec::new();
+        for c in pattern.chars() {
+            match c {
+                '*' => parts.push(GlobPart::Many),
+                '?' => parts.push(GlobPart::One),
+                c => match parts.last_mut() {
+                    Some(GlobPart::Literal(literal)) => literal.push(c),
+                    _ => parts.push(GlobPart::Literal(c.to_string())),
+                },
+            }
+        }
+        Self {
+            pattern: pattern.to_owned(),
+            parts,
+        }
+    }
+
+    fn find_in(&self, buffer: &str) -> Option<std::ops::Range<usize>> {
+        buffer
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([buffer.len()])
+            .find_map(|start| {
+                Self::match_len(&buffer[start..], &self.parts).map(|len| start..(start + len))
+            })
+    }
+
+    /// The length of the longest prefix of `text` matching `parts`
+    fn match_len(text: &str, parts: &[GlobPart]) -> Option<usize> {
+        let Some((first, rest)) = parts.split_first() else {
+            return Some(0);
+        };
+        match first {
+            GlobPart::Literal(literal) => {
+                let remainder = text.strip_prefix(literal.as_str())?;
+                Self::match_len(remainder, rest).map(|len| literal.len() + len)
+            }
+            GlobPart::One => {
+                let c = text.chars().next().filter(|c| !c.is_whitespace())?;
+                Self::match_len(&text[c.len_utf8()..], rest).map(|len| c.len_utf8() + len)
+            }
+            GlobPart::Many => {
+                let max = text.find(char::is_whitespace).unwrap_or(text.len());
+                text[..max]
+                    .char_indices()
+                    .map(|(index, _)| index)
+                    .chain([max])
+                    .rev()
+                    .find_map(|len| Self::match_len(&text[len..], rest).map(|rest| len + rest))
+            }
+        }
+    }
+}
+
+/// A function for [`RedactedValue::from_fn`]
+#[derive(Clone)]
+struct RedactFn {
+    /// Order of creation, to run in the order inserted
+    id: usize,
+    redact: std::sync::Arc<RedactFnInner>,
+}
+
+type RedactFnInner = dyn Fn(&str) -> Cow<'_, str> + Send + Sync;
+
+impl std::fmt::Debug for RedactFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RedactFn").field(&self.id).finish()
     }
+}
 
-    /// Clear unused redactions from expected data
-    ///
-    /// Some redactions can be conditionally present, like redacting [`std::env::consts::EXE_SUFFIX`].
-    /// When the redaction is not present, it needs to be removed from the expected data so it can
-    /// be matched against the actual data.
-    pub fn clear_unused<'v>(&self, pattern: &'v str) -> Cow<'v, str> {
-        if !self.unused.as_ref().map(|s| s.is_empty()).unwrap_or(false) && pattern.contains('[') {
-            let mut pattern = pattern.to_owned();
-            replace_many(
-                &mut pattern,
-                self.unused.iter().flatten().map(|var| (var, "")),
-            );
-            Cow::Owned(pattern)
-        } else {
-            Cow::Borrowed(pattern)
+/// Order of creation for [`RedactFn`], [`Numbered`], and [`Columns`], to run in the order
+/// inserted
+fn next_fn_id() -> usize {
+    static NEXT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+/// A value for [`Redactions::insert_numbered`]
+#[derive(Clone, Debug)]
+struct Numbered {
+    /// Order of creation, to run in the order inserted
+    id: usize,
+    /// The placeholder without its `[` and `]`
+    name: &'static str,
+    value: Box<RedactedValueInner>,
+}
+
+/// What is being redacted, shared by each of its parts
+pub(crate) struct Document {
+    /// The values each [`Numbered`] placeholder has seen, by name
+    numbering: std::collections::BTreeMap<&'static str, Vec<String>>,
+    /// Parts are json strings, rather than text
+    is_json: bool,
+}
+
+impl Document {
+    pub(crate) fn text() -> Self {
+        Self {
+            numbering: Default::default(),
+            is_json: false,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn json() -> Self {
+        Self {
+            numbering: Default::default(),
+            is_json: true,
         }
     }
 }
 
-#[derive(Clone)]
-pub struct RedactedValue {
-    inner: Option<RedactedValueInner>,
+/// A value for [`Redactions::insert_columns`]
+#[derive(Clone, Debug)]
+struct Columns {
+    /// Order of creation, to run in the order inserted
+    id: usize,
+    placeholder: &'static str,
+    start: usize,
+    end: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
@@ -167,6 +1524,10 @@ enum RedactedValueInner {
     },
     #[cfg(feature = "regex")]
     Regex(regex::Regex),
+    Glob(Glob),
+    Fn(RedactFn),
+    Numbered(Numbered),
+    Columns(Columns),
 }
 
 impl RedactedValueInner {
@@ -194,6 +1555,9 @@ impl RedactedValueInner {
                 let m = captures.name("redacted").or_else(|| captures.get(0))?;
                 Some(m.range())
             }
+            Self::Glob(glob) => glob.find_in(buffer),
+            // Applied to the whole buffer by `replace_many`
+            Self::Fn(_) | Self::Numbered(_) | Self::Columns(_) => None,
         }
     }
 
@@ -207,6 +1571,13 @@ impl RedactedValueInner {
                 let s = r.as_str();
                 (1, std::cmp::Reverse(s.len()), s)
             }
+            Self::Glob(glob) => {
+                let s = glob.pattern.as_str();
+                (2, std::cmp::Reverse(s.len()), s)
+            }
+            Self::Fn(f) => (3, std::cmp::Reverse(usize::MAX - f.id), ""),
+            Self::Numbered(n) => (3, std::cmp::Reverse(usize::MAX - n.id), n.value.as_cmp().2),
+            Self::Columns(c) => (3, std::cmp::Reverse(usize::MAX - c.id), ""),
         }
     }
 }
@@ -315,12 +1686,192 @@ impl PartialEq for RedactedValueInner {
 
 impl Eq for RedactedValueInner {}
 
+/// Replace each distinct match of `value` with `[<name><n>]`, see
+/// [`Redactions::insert_numbered`]
+fn number_matches<'t>(
+    text: &'t str,
+    name: &str,
+    value: &RedactedValueInner,
+    seen: &mut Vec<String>,
+) -> Cow<'t, str> {
+    let mut changed = false;
+    let mut redacted = String::new();
+    let mut index = 0;
+    while let Some(range) = value.find_in(&text[index..]) {
+        if range.is_empty() {
+            break;
+        }
+        let matched = &text[(index + range.start)..(index + range.end)];
+        let number = match seen.iter().position(|s| s == matched) {
+            Some(position) => position + 1,
+            None => {
+                seen.push(matched.to_owned());
+                seen.len()
+            }
+        };
+        redacted.push_str(&text[index..(index + range.start)]);
+        redacted.push_str(&format!("[{name}_{number}]"));
+        index += range.end;
+        changed = true;
+    }
+    if !changed {
+        return Cow::Borrowed(text);
+    }
+    redacted.push_str(&text[index..]);
+    Cow::Owned(redacted)
+}
+
+/// Replace columns `start..end` of each line long enough to have them, see
+/// [`Redactions::insert_columns`]
+fn redact_columns<'t>(
+    text: &'t str,
+    placeholder: &str,
+    start: usize,
+    end: Option<usize>,
+) -> Cow<'t, str> {
+    let mut redacted = String::new();
+    let mut changed = false;
+    for line in crate::utils::LinesWithTerminator::new(text) {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let mut offsets = content
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain([content.len()]);
+        let range = offsets.nth(start).and_then(|start_offset| match end {
+            Some(end) => offsets
+                .nth(end - start - 1)
+                .map(|end_offset| start_offset..end_offset),
+            None => (start_offset < content.len()).then_some(start_offset..content.len()),
+        });
+        // Already redacted, so redacting again is a no-op
+        let range = range.filter(|range| !line[range.start..].starts_with(placeholder));
+        match range {
+            Some(range) => {
+                redacted.push_str(&line[..range.start]);
+                redacted.push_str(placeholder);
+                redacted.push_str(&line[range.end..]);
+                changed = true;
+            }
+            None => redacted.push_str(line),
+        }
+    }
+    if changed {
+        Cow::Owned(redacted)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Redact `value` only where it is a whole path component, like `debug` in `target/debug/foo`
+fn path_component(placeholder: &'static str, value: String) -> RedactedValue {
+    RedactedValue::from_fn(move |text: &str| {
+        let mut redacted = String::new();
+        let mut last = 0;
+        for (index, _) in text.match_indices(value.as_str()) {
+            let end = index + value.len();
+            let is_start = text[..index].ends_with(['/', '\\']);
+            let is_end = text[end..]
+                .chars()
+                .next()
+                .map(|c| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(true);
+            if is_start && is_end {
+                redacted.push_str(&text[last..index]);
+                redacted.push_str(placeholder);
+                last = end;
+            }
+        }
+        if last == 0 {
+            Cow::Borrowed(text)
+        } else {
+            redacted.push_str(&text[last..]);
+            Cow::Owned(redacted)
+        }
+    })
+}
+
+/// The target triple this was built for, see [`Redactions::insert_cargo_preset`]
+fn target_triple() -> String {
+    if let Some(target) = std::env::var("CARGO_BUILD_TARGET")
+        .ok()
+        .filter(|target| !target.is_empty())
+    {
+        return target;
+    }
+    let arch = match std::env::consts::ARCH {
+        "x86" => "i686",
+        arch => arch,
+    };
+    let vendor = if cfg!(target_vendor = "apple") {
+        "apple"
+    } else if cfg!(target_vendor = "pc") {
+        "pc"
+    } else {
+        "unknown"
+    };
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    let env = if cfg!(target_env = "gnu") {
+        "-gnu"
+    } else if cfg!(target_env = "msvc") {
+        "-msvc"
+    } else if cfg!(target_env = "musl") {
+        "-musl"
+    } else {
+        ""
+    };
+    format!("{arch}-{vendor}-{os}{env}")
+}
+
 /// Replacements is `(from, to)`
 fn replace_many<'a>(
     buffer: &mut String,
     replacements: impl IntoIterator<Item = (&'a RedactedValueInner, &'a str)>,
+    case_insensitive: bool,
+    cache: &LiteralCache,
+    document: &mut Document,
 ) {
+    let mut literals = Vec::new();
+    let mut rest = Vec::new();
     for (var, replace) in replacements {
+        match var {
+            RedactedValueInner::Str(s) => literals.push((*s, replace)),
+            RedactedValueInner::String(s) => literals.push((s.as_str(), replace)),
+            RedactedValueInner::Path { native, normalized } => {
+                literals.push((native.as_str(), replace));
+                literals.push((normalized.as_str(), replace));
+            }
+            _ => rest.push((var, replace)),
+        }
+    }
+    replace_literals(buffer, literals, case_insensitive, cache);
+
+    for (var, replace) in rest {
+        if let RedactedValueInner::Fn(f) = var {
+            if let Cow::Owned(redacted) = (f.redact)(buffer) {
+                *buffer = redacted;
+            }
+            continue;
+        }
+        if let RedactedValueInner::Columns(c) = var {
+            // Columns of json strings don't line up with the text that was compared
+            if !document.is_json {
+                if let Cow::Owned(redacted) = redact_columns(buffer, c.placeholder, c.start, c.end)
+                {
+                    *buffer = redacted;
+                }
+            }
+            continue;
+        }
+        if let RedactedValueInner::Numbered(n) = var {
+            let seen = document.numbering.entry(n.name).or_default();
+            if let Cow::Owned(redacted) = number_matches(buffer, n.name, &n.value, seen) {
+                *buffer = redacted;
+            }
+            continue;
+        }
         let mut index = 0;
         while let Some(offset) = var.find_in(&buffer[index..]) {
             let old_range = (index + offset.start)..(index + offset.end);
@@ -330,16 +1881,232 @@ fn replace_many<'a>(
     }
 }
 
-fn validate_placeholder(placeholder: &'static str) -> crate::assert::Result<&'static str> {
+/// Replace all literals in a single pass, preferring the longest at each position
+///
+/// When a literal has multiple placeholders, the first one is used.  Multi-line literals match
+/// with either `\n` or `\r\n` line endings.  With `case_insensitive`, literals match ignoring
+/// ASCII case.
+fn replace_literals(
+    buffer: &mut String,
+    literals: Vec<(&str, &str)>,
+    case_insensitive: bool,
+    cache: &LiteralCache,
+) {
+    let mut seen = std::collections::BTreeSet::new();
+    let mut needles: Vec<(Cow<'_, str>, &str)> = Vec::new();
+    for (literal, replace) in literals {
+        if literal.contains('\n') {
+            let lf = literal.replace("\r\n", "\n");
+            let crlf = lf.replace('\n', "\r\n");
+            for variant in [lf, crlf] {
+                if seen.insert(Cow::Owned(variant.clone())) {
+                    needles.push((Cow::Owned(variant), replace));
+                }
+            }
+        } else if !literal.is_empty() && seen.insert(Cow::Borrowed(literal)) {
+            needles.push((Cow::Borrowed(literal), replace));
+        }
+    }
+    if needles.is_empty() {
+        return;
+    }
+
+    #[cfg(feature = "regex")]
+    {
+        let matcher = cache.matcher(&needles, case_insensitive);
+        if !matcher.is_match(buffer.as_str()) {
+            return;
+        }
+        let mut redacted = String::with_capacity(buffer.len());
+        matcher.replace_all_with(buffer, &mut redacted, |m, _, dst| {
+            dst.push_str(needles[m.pattern().as_usize()].1);
+            true
+        });
+        *buffer = redacted;
+    }
+    #[cfg(not(feature = "regex"))]
+    {
+        let _ = cache;
+        let mut redacted = String::with_capacity(buffer.len());
+        let mut rest = buffer.as_str();
+        while let Some(c) = rest.chars().next() {
+            let mut longest: Option<(usize, &str)> = None;
+            for (needle, replace) in &needles {
+                let is_longer = longest.map(|(len, _)| len < needle.len()).unwrap_or(true);
+                if is_longer && super::token::strip_prefix(rest, needle, case_insensitive).is_some()
+                {
+                    longest = Some((needle.len(), replace));
+                }
+            }
+            if let Some((len, replace)) = longest {
+                redacted.push_str(replace);
+                rest = &rest[len..];
+            } else {
+                redacted.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        *buffer = redacted;
+    }
+}
+
+/// Literal matchers, built once and reused by [`Redactions::redact`]
+///
+/// Which literals are replaced together depends on conditions and priorities, so matchers are
+/// looked up by their literals.  Cleared whenever the redactions change.
+struct LiteralCache {
+    #[cfg(feature = "regex")]
+    matchers: std::sync::Mutex<Vec<LiteralMatcher>>,
+}
+
+#[cfg(feature = "regex")]
+struct LiteralMatcher {
+    needles: Vec<String>,
+    case_insensitive: bool,
+    matcher: aho_corasick::AhoCorasick,
+}
+
+impl LiteralCache {
+    /// Matchers kept at once, enough for a few priority levels
+    #[cfg(feature = "regex")]
+    const CAPACITY: usize = 8;
+
+    const fn new() -> Self {
+        Self {
+            #[cfg(feature = "regex")]
+            matchers: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn clear(&mut self) {
+        #[cfg(feature = "regex")]
+        match self.matchers.get_mut() {
+            Ok(matchers) => matchers.clear(),
+            Err(poisoned) => poisoned.into_inner().clear(),
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn matcher(
+        &self,
+        needles: &[(Cow<'_, str>, &str)],
+        case_insensitive: bool,
+    ) -> aho_corasick::AhoCorasick {
+        let mut matchers = match self.matchers.lock() {
+            Ok(matchers) => matchers,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let is_cached = |cached: &LiteralMatcher| {
+            cached.case_insensitive == case_insensitive
+                && cached.needles.len() == needles.len()
+                && cached
+                    .needles
+                    .iter()
+                    .zip(needles)
+                    .all(|(cached, (needle, _))| cached == needle)
+        };
+        if let Some(cached) = matchers.iter().find(|cached| is_cached(cached)) {
+            return cached.matcher.clone();
+        }
+
+        let matcher = aho_corasick::AhoCorasick::builder()
+            .match_kind(aho_corasick::MatchKind::LeftmostLongest)
+            .ascii_case_insensitive(case_insensitive)
+            .build(needles.iter().map(|(needle, _)| needle.as_ref()))
+            .expect("redaction literals fit in memory");
+        if matchers.len() == Self::CAPACITY {
+            matchers.remove(0);
+        }
+        matchers.push(LiteralMatcher {
+            needles: needles
+                .iter()
+                .map(|(needle, _)| needle.clone().into_owned())
+                .collect(),
+            case_insensitive,
+            matcher: matcher.clone(),
+        });
+        matcher
+    }
+}
+
+impl Default for LiteralCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for LiteralCache {
+    fn clone(&self) -> Self {
+        // Rebuilt on demand
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for LiteralCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LiteralCache")
+    }
+}
+
+impl PartialEq for LiteralCache {
+    fn eq(&self, _other: &Self) -> bool {
+        // A cache doesn't change what is redacted
+        true
+    }
+}
+
+impl Eq for LiteralCache {}
+
+#[cfg(feature = "json")]
+fn redacted_value_from_json(value: serde_json::Value) -> Result<RedactedValue, String> {
+    match value {
+        serde_json::Value::String(value) => Ok(value.into()),
+        serde_json::Value::Object(mut value) => {
+            if let Some(serde_json::Value::String(pattern)) = value.remove("glob") {
+                if let Some(key) = value.keys().next() {
+                    return Err(format!("unknown key `{key}`"));
+                }
+                return Ok(RedactedValue::glob(&pattern));
+            }
+            let Some(serde_json::Value::String(pattern)) = value.remove("regex") else {
+                return Err(EXPECTED_VALUE.to_owned());
+            };
+            if let Some(key) = value.keys().next() {
+                return Err(format!("unknown key `{key}`"));
+            }
+            #[cfg(feature = "regex")]
+            {
+                let regex = regex::Regex::new(&pattern).map_err(|e| e.to_string())?;
+                Ok(regex.into())
+            }
+            #[cfg(not(feature = "regex"))]
+            {
+                Err(format!("regex `{pattern}` requires feature flag `regex`"))
+            }
+        }
+        _ => Err(EXPECTED_VALUE.to_owned()),
+    }
+}
+
+#[cfg(feature = "json")]
+const EXPECTED_VALUE: &str =
+    "expected a string, `{\"regex\": \"<pattern>\"}`, or `{\"glob\": \"<pattern>\"}`";
+
+fn validate_placeholder(placeholder: &str) -> crate::assert::Result<&str> {
     if !placeholder.starts_with('[') || !placeholder.ends_with(']') {
         return Err(format!("Key `{placeholder}` is not enclosed in []").into());
     }
 
-    if placeholder[1..(placeholder.len() - 1)]
-        .find(|c: char| !c.is_ascii_uppercase() && c != '_')
-        .is_some()
+    let name = &placeholder[1..(placeholder.len() - 1)];
+    if !name.starts_with(|c: char| c.is_ascii_uppercase())
+        || name
+            .find(|c: char| !c.is_ascii_uppercase() && !c.is_ascii_digit() && c != '_')
+            .is_some()
     {
-        return Err(format!("Key `{placeholder}` can only be A-Z but ").into());
+        return Err(format!(
+            "Key `{placeholder}` can only be A-Z, 0-9, and `_`, starting with A-Z"
+        )
+        .into());
     }
 
     Ok(placeholder)
@@ -356,12 +2123,357 @@ mod test {
             ("HELLO]", false),
             ("[HELLO]", true),
             ("[HELLO_WORLD]", true),
+            ("[HELLO_1]", true),
             ("[hello]", false),
             ("[HE  O]", false),
+            ("[1HELLO]", false),
+            ("[]", false),
         ];
         for (placeholder, expected) in cases {
             let actual = validate_placeholder(placeholder).is_ok();
             assert_eq!(expected, actual, "placeholder={placeholder:?}");
         }
     }
+
+    #[test]
+    fn exe_preset_windows() {
+        let mut redactions = Redactions::new();
+        redactions.insert_exe_suffix(".exe").unwrap();
+        assert_eq!(redactions.redact("tool.exe --help"), "tool[EXE] --help");
+
+        let actual = crate::filter::NormalizeToExpected::new()
+            .redact_with(&redactions)
+            .normalize("tool.exe --help".into(), &"tool[EXE] --help".into());
+        assert_eq!(actual, crate::Data::text("tool[EXE] --help"));
+    }
+
+    #[test]
+    fn exe_preset_unix() {
+        let mut redactions = Redactions::new();
+        redactions.insert_exe_suffix("").unwrap();
+        assert_eq!(redactions.redact("tool --help"), "tool --help");
+        assert_eq!(redactions.clear_unused("tool[EXE] --help"), "tool --help");
+
+        let actual = crate::filter::NormalizeToExpected::new()
+            .redact_with(&redactions)
+            .normalize("tool --help".into(), &"tool[EXE] --help".into());
+        assert_eq!(actual, crate::Data::text("tool[EXE] --help"));
+    }
+
+    #[test]
+    fn validate_ok() {
+        let mut redactions = Redactions::with_exe();
+        redactions.insert("[LOCATION]", "World").unwrap();
+        redactions.insert("[EMPTY]", "").unwrap();
+        #[cfg(feature = "regex")]
+        redactions
+            .insert("[HASH]", regex::Regex::new("[0-9a-f]{8}").unwrap())
+            .unwrap();
+        assert_eq!(redactions.validate(), Ok(()));
+    }
+
+    #[test]
+    fn from_fn_runs_in_insertion_order() {
+        let mut redactions = Redactions::new();
+        redactions.insert("[USER]", "alice").unwrap();
+        redactions
+            .insert(
+                "[HASH]",
+                RedactedValue::from_fn(|text: &str| match text.find("sha256:") {
+                    Some(start) => {
+                        let end = text[start..]
+                            .find(char::is_whitespace)
+                            .map(|end| start + end)
+                            .unwrap_or(text.len());
+                        Cow::Owned(format!("{}[HASH]{}", &text[..start], &text[end..]))
+                    }
+                    None => Cow::Borrowed(text),
+                }),
+            )
+            .unwrap();
+        redactions
+            .insert(
+                "[SHORT]",
+                RedactedValue::from_fn(|text: &str| Cow::Owned(text.replace("[HASH]", "[SHORT]"))),
+            )
+            .unwrap();
+        assert_eq!(
+            redactions.redact("alice pushed sha256:abc123 today"),
+            "[USER] pushed [SHORT] today"
+        );
+        assert_eq!(redactions.validate(), Ok(()));
+
+        let actual = crate::filter::NormalizeToExpected::new()
+            .redact_with(&redactions)
+            .normalize(
+                "bob pushed sha256:def456\n".into(),
+                &"bob pushed [SHORT]\n".into(),
+            );
+        assert_eq!(actual, crate::Data::text("bob pushed [SHORT]\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn validate_empty_matching_regex() {
+        let mut redactions = Redactions::new();
+        redactions
+            .insert("[DIGITS]", regex::Regex::new("[0-9]*").unwrap())
+            .unwrap();
+
+        let errors = redactions.validate().unwrap_err();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(
+            errors[0].to_string(),
+            "[DIGITS]: `[0-9]*` matches an empty string"
+        );
+    }
+
+    #[test]
+    fn literals_prefer_longest() {
+        let mut subst = Redactions::new();
+        subst.insert("[ROOT]", "/home/ci").unwrap();
+        subst.insert("[PROJECT]", "/home/ci/project").unwrap();
+        subst.insert("[TARGET]", "/home/ci/project/target").unwrap();
+        subst.insert("[HOME]", "/home/ci".to_owned()).unwrap();
+        assert_eq!(
+            subst.redact("/home/ci/project/target/debug /home/ci/project/src /home/ci/.cargo"),
+            "[TARGET]/debug [PROJECT]/src [HOME]/.cargo"
+        );
+    }
+
+    #[test]
+    fn literals_reflect_changes() {
+        let mut subst = Redactions::new();
+        subst.insert("[ROOT]", "/home/ci").unwrap();
+        assert_eq!(subst.redact("/home/ci/project"), "[ROOT]/project");
+        subst.insert("[PROJECT]", "/home/ci/project").unwrap();
+        assert_eq!(subst.redact("/home/ci/project"), "[PROJECT]");
+        subst.remove("[PROJECT]").unwrap();
+        assert_eq!(subst.redact("/home/ci/project"), "[ROOT]/project");
+        assert_eq!(subst.clone().redact("/home/ci/project"), "[ROOT]/project");
+    }
+
+    #[test]
+    fn many_literals() {
+        let mut subst = Redactions::new();
+        for i in 0..100 {
+            let placeholder: &'static str = Box::leak(format!("[P_{}]", "X".repeat(i)).into());
+            subst.insert(placeholder, format!("<value {i}>")).unwrap();
+        }
+        assert_eq!(
+            subst.redact("<value 3> and <value 42>, not <value 100>"),
+            "[P_XXX] and [P_XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX], not <value 100>"
+        );
+    }
+
+    #[test]
+    fn iter_and_contains() {
+        let mut subst = Redactions::new();
+        subst.insert("[A]", "value").unwrap();
+        subst.insert("[B]", "value").unwrap();
+        subst.insert("[B]", PathBuf::from("dir")).unwrap();
+        subst.insert_if("[C]", "other", || false).unwrap();
+        subst.insert("[D]", "").unwrap();
+        subst
+            .insert(
+                "[E]",
+                RedactedValue::from_fn(|text: &str| Cow::Borrowed(text)),
+            )
+            .unwrap();
+
+        let mut actual: Vec<_> = subst.iter().collect();
+        actual.sort();
+        assert_eq!(
+            actual,
+            [
+                ("[A]", "value"),
+                ("[B]", "dir"),
+                ("[B]", "value"),
+                ("[C]", "other"),
+                ("[D]", ""),
+                ("[E]", ""),
+            ]
+        );
+        for placeholder in ["[A]", "[B]", "[C]", "[D]", "[E]"] {
+            assert!(subst.contains(placeholder), "{placeholder}");
+        }
+        assert!(!subst.contains("[F]"));
+    }
+
+    #[test]
+    fn cargo_preset() {
+        let mut subst = Redactions::new();
+        subst
+            .insert_cargo_preset(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            .unwrap();
+        let actual = format!(
+            "{} {} ({})",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            target_triple()
+        );
+        assert_eq!(subst.redact(&actual), "snapbox [CRATE_VERSION] ([TARGET])");
+        assert!(subst.contains("[PROFILE]"));
+
+        let actual = format!(
+            "target/{}/{}.d target/{}/{}-extra debug build of {}",
+            target_triple(),
+            env!("CARGO_PKG_NAME"),
+            target_triple(),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+        );
+        assert_eq!(
+            subst.redact(&actual),
+            "target/[TARGET]/[CRATE_NAME].d target/[TARGET]/snapbox-extra debug build of snapbox"
+        );
+    }
+
+    #[test]
+    fn priorities() {
+        let mut subst = Redactions::new();
+        subst.insert("[HOME]", "/home/user").unwrap();
+        subst.insert("[SHORT]", "/home/user/proj").unwrap();
+        subst.insert("[LAST]", "/home/user/project").unwrap();
+        subst.set_priority("[HOME]", 2).unwrap();
+        subst.set_priority("[LAST]", -1).unwrap();
+        assert_eq!(subst.redact("/home/user/project"), "[HOME]/project");
+
+        subst.set_priority("[HOME]", -2).unwrap();
+        assert_eq!(subst.redact("/home/user/project"), "[SHORT]ect");
+
+        subst.remove("[SHORT]").unwrap();
+        assert_eq!(subst.redact("/home/user/project"), "[LAST]");
+    }
+
+    #[test]
+    fn glob() {
+        let mut subst = Redactions::new();
+        subst
+            .insert("[LOG]", RedactedValue::glob("build-*.log"))
+            .unwrap();
+        subst.insert("[ID]", RedactedValue::glob("id-???")).unwrap();
+        assert_eq!(
+            subst.redact("wrote build-123.log: id-abc, id-ab c, mybuild-1.log"),
+            "wrote [LOG]: [ID], id-ab c, my[LOG]"
+        );
+        assert_eq!(
+            subst.redact("build-.log and build- x.log"),
+            "[LOG] and build- x.log"
+        );
+
+        let mut subst = Redactions::new();
+        subst.insert("[ANY]", RedactedValue::glob("*")).unwrap();
+        assert!(subst.validate().is_err());
+    }
+
+    #[test]
+    fn os_errors_preset() {
+        let mut subst = Redactions::new();
+        subst.insert_os_errors_preset().unwrap();
+        let err = std::fs::read("does-not-exist.txt").unwrap_err();
+        assert_eq!(
+            subst.redact(&format!("failed to read: {err}")),
+            "failed to read: [NOT_FOUND]"
+        );
+        assert_eq!(
+            subst.redact("rmdir: Directory not empty (os error 66)"),
+            "rmdir: [DIR_NOT_EMPTY]"
+        );
+        assert_eq!(subst.redact("Access is denied."), "[PERMISSION_DENIED]");
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn cargo_status_preset() {
+        let mut subst = Redactions::new();
+        subst.insert_cargo_status_preset().unwrap();
+        let cases = [
+            (
+                "    Locking 1 package to latest compatible version",
+                "    Locking [LOCKING] package to latest compatible version",
+            ),
+            (
+                "Downloading 12 crates ...",
+                "Downloading [DOWNLOADING] crates ...",
+            ),
+            (
+                "Downloaded 1 crate (18.6KiB) in 0.30s",
+                "Downloaded [DOWNLOADING] in [ELAPSED]s",
+            ),
+            (
+                "   Finished `release` profile [optimized] target(s) in 12.5s",
+                "   Finished `release` profile [optimized] target(s) in [ELAPSED]s",
+            ),
+            (
+                "   Compiling foo v0.1.0 in 0.5s",
+                "   Compiling foo v0.1.0 in 0.5s",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(subst.redact(input), expected, "input={input:?}");
+        }
+    }
+
+    #[test]
+    fn columns() {
+        let log = "12:30 INFO  ab\n12:31 WARN  abcdef\r\nshort\n12:32 ÉRROR xyz";
+
+        let mut subst = Redactions::new();
+        subst.insert_columns("[LEVEL]", 6..=10).unwrap();
+        assert_eq!(
+            subst.redact(log),
+            "12:30 [LEVEL] ab\n12:31 [LEVEL] abcdef\r\nshort\n12:32 [LEVEL] xyz"
+        );
+
+        let mut subst = Redactions::new();
+        subst.insert_columns("[REST]", 14..).unwrap();
+        assert_eq!(
+            subst.redact(log),
+            "12:30 INFO  ab\n12:31 WARN  ab[REST]\r\nshort\n12:32 ÉRROR xy[REST]"
+        );
+        assert!(subst.insert_columns("[EMPTY]", 3..3).is_err());
+
+        let mut subst = Redactions::new();
+        subst.insert_columns("[LEVEL]", 6..=10).unwrap();
+        let redacted = subst.redact(log);
+        assert_eq!(subst.redact(&redacted), redacted);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn columns_skip_json() {
+        let mut subst = Redactions::new();
+        subst.insert_columns("[TIME]", 0..5).unwrap();
+        let mut actual = serde_json::json!({"at": "12:30 done"});
+        subst.redact_json(&mut actual);
+        assert_eq!(actual, serde_json::json!({"at": "12:30 done"}));
+    }
+
+    #[test]
+    fn numbered() {
+        let mut subst = Redactions::new();
+        subst
+            .insert_numbered("[USER]", RedactedValue::glob("user-??"))
+            .unwrap();
+        assert_eq!(
+            subst.redact("user-ab -> user-cd, user-ab; user-ef"),
+            "[USER_1] -> [USER_2], [USER_1]; [USER_3]"
+        );
+        assert_eq!(subst.redact("nobody"), "nobody");
+        assert!(subst.contains("[USER_2]"));
+        assert!(!subst.contains("[USER_]"));
+        assert!(!subst.contains("[OTHER_2]"));
+        assert!(Redactions::new().insert("[USER_2]", "user-cd").is_ok());
+        assert_eq!(
+            crate::filter::lint_pattern("[USER_1] -> [USER_2]\n", &subst),
+            Ok(())
+        );
+        assert!(subst
+            .insert_numbered(
+                "[FN]",
+                RedactedValue::from_fn(|text: &str| Cow::Borrowed(text))
+            )
+            .is_err());
+    }
 }
@@ -1,7 +1,6 @@
 #[cfg(feature = "json")]
 use serde_json::json;
 
-#[cfg(feature = "json")]
 use super::*;
 
 // Tests for normalization on json
@@ -118,3 +117,696 @@ fn json_normalize_paths_and_lines_array_obj() {
     });
     assert_eq!(Data::json(new_lines), data);
 }
+
+#[test]
+fn normalize_numbers_canonical_form() {
+    let cases = [
+        ("1e3", "1000"),
+        ("1000.0", "1000"),
+        ("1000", "1000"),
+        ("1.5E+3", "1500"),
+        ("0.010", "0.01"),
+        ("25e-3", "0.025"),
+        ("-1.0", "-1"),
+        ("-0.0", "0"),
+        ("007", "7"),
+    ];
+    for (input, expected) in cases {
+        let actual = normalize_numbers(input);
+        assert_eq!(expected, actual, "input={input:?}");
+    }
+}
+
+#[test]
+fn normalize_numbers_in_text() {
+    let cases = [
+        ("took 1e3 ms", "took 1000 ms"),
+        ("took 1000.0 ms", "took 1000 ms"),
+        ("values: 1e3, 2.50, 3", "values: 1000, 2.5, 3"),
+        ("version 1.2.3", "version 1.2.3"),
+        ("v1.0", "v1.0"),
+        ("x1e3", "x1e3"),
+        ("1e3x", "1e3x"),
+        ("end.", "end."),
+        ("1e", "1e"),
+        ("2024-01-05 12:05:00", "2024-01-05 12:05:00"),
+        ("1,000", "1,000"),
+        ("id=0042", "id=0042"),
+        ("id_0042", "id_0042"),
+        ("x = 007", "x = 7"),
+        ("range: -1.0", "range: -1"),
+    ];
+    for (input, expected) in cases {
+        let actual = normalize_numbers(input);
+        assert_eq!(expected, actual, "input={input:?}");
+    }
+}
+
+#[test]
+fn filter_numbers_matches_representations() {
+    let actual = FilterNumbers.filter(Data::text("size: 1e3\nsize: 1000.0\nsize: 1000\n"));
+    assert_eq!(Data::text("size: 1000\nsize: 1000\nsize: 1000\n"), actual);
+}
+
+#[test]
+fn locale_numbers_normalized() {
+    let cases = [
+        ('.', "1,234.5", "1234.5"),
+        (',', "1.234,5", "1234.5"),
+        (',', "1 234,5", "1234.5"),
+        (',', "1\u{202f}234\u{202f}567,89", "1234567.89"),
+        ('.', "1'234'567", "1234567"),
+        ('.', "1,234,567", "1234567"),
+        ('.', "1,234", "1234"),
+        ('.', "1.234", "1.234"),
+        (',', "1.234", "1234"),
+        (',', "1,234", "1.234"),
+        (',', "1,5", "1.5"),
+        ('.', "1.5", "1.5"),
+        ('.', "1,5", "1,5"),
+        ('.', "-1,234.5 EUR", "-1234.5 EUR"),
+        ('.', "total: 1,234.", "total: 1234."),
+        ('.', "version 1.2.3", "version 1.2.3"),
+        (',', "version 1.2.3", "version 1.2.3"),
+        (',', "10.0.0.1", "10.0.0.1"),
+        ('.', "12345,678", "12345,678"),
+        (',', "1 234", "1 234"),
+        ('.', "x1,234", "x1,234"),
+        ('.', "1,234ms", "1,234ms"),
+    ];
+    for (decimal_separator, input, expected) in cases {
+        let actual = normalize_locale_numbers(input, decimal_separator);
+        assert_eq!(
+            expected, actual,
+            "input={input:?} decimal_separator={decimal_separator:?}"
+        );
+    }
+}
+
+#[test]
+fn meta_survives_filters() {
+    let data = Data::text("C:\\Users\r\n1e3 [..]\r\n").with_meta("title", "Listing");
+    let data = FilterNewlines.filter(data);
+    let data = FilterPaths.filter(data);
+    let data = FilterNumbers.filter(data);
+    let data = NormalizeToExpected::new()
+        .redact()
+        .normalize(data, &Data::text("C:/Users\n1000 [..]\n"));
+    assert_eq!(data, Data::text("C:/Users\n1000 [..]\n"));
+    assert_eq!(data.meta("title"), Some("Listing"));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_meta_survives_filters() {
+    let data = Data::text(r#"{"a": null, "b": 1}"#).with_meta("title", "Config");
+    let data = data.coerce_to(crate::data::DataFormat::Json);
+    let data = DropJsonNulls::new().filter(data);
+    let data = NormalizeToExpected::new()
+        .redact()
+        .normalize(data, &Data::json(json!({"b": "{...}"})));
+    assert_eq!(data, Data::json(json!({"b": "{...}"})));
+    assert_eq!(data.meta("title"), Some("Config"));
+}
+
+#[test]
+fn round_significant_figures() {
+    let cases = [
+        ("pi = 3.14159265", "pi = 3.14"),
+        ("pi = -3.14159265", "pi = -3.14"),
+        ("9.996 s", "10.0 s"),
+        ("123456.7 ns", "123000 ns"),
+        ("0.000123456", "0.000123"),
+        ("1.23456e-7", "1.23e-7"),
+        ("count: 123456", "count: 123456"),
+        ("version 1.2.3", "version 1.2.3"),
+        ("x1.23456", "x1.23456"),
+        ("0.0", "0.0"),
+    ];
+    for (input, expected) in cases {
+        let actual = RoundSignificantFigures(3).filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_filter_numbers_string() {
+    let data = Data::json(json!({"size": "1e3", "count": 1.0}));
+    let data = FilterNumbers.filter(data);
+    assert_eq!(Data::json(json!({"size": "1000", "count": 1.0})), data);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn strip_json_comments_line_and_block() {
+    let input = r#"{
+  // leading
+  "a": 1, /* inline */ "b": 2
+  /* multi
+     line */
+}"#;
+    let expected = "{\n  \n  \"a\": 1,  \"b\": 2\n  \n\n}";
+    assert_eq!(strip_json_comments(input), expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn strip_json_comments_ignores_strings() {
+    let input = r#"{"url": "https://example.com/*x*/", "q": "\"//"}"#;
+    assert_eq!(strip_json_comments(input), input);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn filter_json_comments_binary() {
+    let input = Data::binary(b"[1, // one\n2]".to_vec());
+    let actual = FilterJsonComments.filter(input);
+    assert_eq!(actual, Data::text("[1, \n2]"));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn strip_json_trailing_commas_cases() {
+    let cases = [
+        ("[1, 2,]", "[1, 2]"),
+        ("{\"a\": 1,\n}", "{\"a\": 1\n}"),
+        ("[[1,], {\"b\": [],},]", "[[1], {\"b\": []}]"),
+        ("[\",]\", 1]", "[\",]\", 1]"),
+        ("[1, 2]", "[1, 2]"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            strip_json_trailing_commas(input),
+            expected,
+            "input={input:?}"
+        );
+    }
+}
+
+#[test]
+fn quotes_normalized() {
+    let cases = [
+        ("say 'hi'", "say \"hi\""),
+        ("say `hi` and 'bye'", "say \"hi\" and \"bye\""),
+        ("('a', 'b')", "(\"a\", \"b\")"),
+        ("''", "\"\""),
+        ("don't won't", "don't won't"),
+        ("\"it's\"", "\"it's\""),
+        ("'unclosed\nline'", "'unclosed\nline'"),
+        ("'mixed\"", "'mixed\""),
+        ("'a'b", "'a'b"),
+        ("héllo 'wörld'", "héllo \"wörld\""),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            FilterQuotes::new().normalize(input),
+            expected,
+            "input={input:?}"
+        );
+    }
+}
+
+#[test]
+fn quotes_configurable() {
+    let quotes = FilterQuotes::new().quotes(['"']).canonical('\'');
+    assert_eq!(quotes.normalize("say \"hi\" `x`"), "say 'hi' `x`");
+}
+
+#[test]
+fn whitespace_collapsed() {
+    let cases = [
+        ("a  b\tc \t d", "a b c d"),
+        ("  indented\n\tline  \n", " indented\n line \n"),
+        ("no\n\nchange", "no\n\nchange"),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterWhitespace.filter(Data::text(input));
+        assert_eq!(actual, Data::text(expected), "input={input:?}");
+    }
+}
+
+#[test]
+fn trailing_whitespace_removed() {
+    let cases = [
+        ("a  b  \nc\t\n", "a  b\nc\n"),
+        ("crlf \r\nend  ", "crlf\r\nend"),
+        ("  indented\n\n", "  indented\n\n"),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterTrailingWhitespace.filter(Data::text(input));
+        assert_eq!(actual, Data::text(expected), "input={input:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn dedup_json_arrays_at_pointer() {
+    let filter = DedupJsonArrays::new().at("/tags");
+    let actual = Data::json(json!({
+        "tags": ["fast", "slow", "fast", {"a": 1}, {"a": 1}],
+        "log": ["retry", "retry", "done"],
+    }));
+    let expected = Data::json(json!({
+        "tags": ["fast", "slow", {"a": 1}],
+        "log": ["retry", "retry", "done"],
+    }));
+    assert_eq!(filter.filter(actual), filter.filter(expected.clone()));
+    assert_eq!(filter.filter(expected.clone()), expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn dedup_json_arrays_wildcard_jsonlines() {
+    let filter = DedupJsonArrays::new().at("/*/features");
+    let actual = Data::jsonlines(vec![
+        json!({"pkg": {"features": ["a", "a"]}, "log": [1, 1]}),
+        json!({"pkg": {"features": ["b", "c", "b"]}}),
+    ]);
+    let expected = Data::jsonlines(vec![
+        json!({"pkg": {"features": ["a"]}, "log": [1, 1]}),
+        json!({"pkg": {"features": ["b", "c"]}}),
+    ]);
+    assert_eq!(filter.filter(actual), expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn drop_json_nulls_object_field() {
+    let actual = Data::json(json!({
+        "name": "snapbox",
+        "description": null,
+        "nested": {"a": null, "b": 1},
+        "list": [null, {"c": null}],
+    }));
+    let expected = Data::json(json!({
+        "name": "snapbox",
+        "nested": {"b": 1},
+        "list": [null, {}],
+    }));
+    assert_ne!(actual, expected);
+    assert_eq!(
+        DropJsonNulls::new().filter(actual),
+        DropJsonNulls::new().filter(expected)
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn drop_json_nulls_in_arrays() {
+    let actual = Data::json(json!([null, 1, [null, 2]]));
+    assert_eq!(
+        DropJsonNulls::new().in_arrays(true).filter(actual),
+        Data::json(json!([1, [2]]))
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn drop_json_nulls_jsonlines() {
+    let actual = Data::jsonlines(vec![json!({"a": null}), json!(null)]);
+    assert_eq!(
+        DropJsonNulls::new().in_arrays(true).filter(actual),
+        Data::jsonlines(vec![json!({}), json!(null)])
+    );
+}
+
+#[test]
+fn filter_panic_short_and_full_backtraces() {
+    let short = "\
+thread 'main' panicked at src/main.rs:2:5:
+boom
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/std/src/panicking.rs:645:5
+   1: core::panicking::panic_fmt
+             at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/core/src/panicking.rs:72:14
+   2: anyhow::private::format_err
+             at ./vendor/anyhow/src/lib.rs:10:5
+   3: demo::main
+             at ./src/main.rs:2:5
+   4: core::ops::function::FnOnce::call_once
+             at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/core/src/ops/function.rs:250:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+";
+    let full = "\
+thread 'main' (4242) panicked at src/main.rs:2:5:
+boom
+stack backtrace:
+   0:     0x55d3c8a0c1a5 - std::backtrace_rs::backtrace::libunwind::trace::h9b1a2c8d3e4f5a6b
+                               at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/std/src/../../backtrace/src/backtrace/libunwind.rs:104:5
+   1:     0x55d3c8a0b2c0 - rust_begin_unwind
+                               at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/std/src/panicking.rs:645:5
+   2:     0x55d3c8a0a123 - anyhow::private::format_err::h0123456789abcdef
+                               at /home/user/demo/vendor/anyhow/src/lib.rs:10:5
+   3:     0x55d3c8a0a456 - demo::main::hfedcba9876543210
+                               at /home/user/demo/src/main.rs:2:5
+   4:     0x55d3c8a0a789 - std::rt::lang_start_internal::h00112233aabbccdd
+                               at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/std/src/rt.rs:148:48
+                           std::panicking::try::do_call::h1122334455667788
+                               at /rustc/25ef9e3d85d934b27d9dada2f9dd52b1dc63bb04/library/std/src/panicking.rs:552:40
+   5:     0x55d3c8a0a9ab - main
+   6:     0x7f0b1c229d90 - <unknown>
+   7:     0x7f0b1c229e40 - __libc_start_main
+   8:     0x55d3c8a0a0a5 - _start
+";
+    let expected = Data::text(
+        "\
+thread 'main' panicked at src/main.rs:2:5:
+boom
+stack backtrace:
+  anyhow::private::format_err
+    at vendor/anyhow/src/lib.rs:10:5
+  demo::main
+    at src/main.rs:2:5
+",
+    );
+
+    let filter = FilterPanic::new().cwd("/home/user/demo");
+    assert_eq!(filter.filter(Data::text(short)), expected);
+    assert_eq!(filter.filter(Data::text(full)), expected);
+}
+
+#[test]
+fn filter_panic_header_forms() {
+    let cases = [
+        (
+            "thread 'main' panicked at 'boom', src/main.rs:2:5\n",
+            "thread 'main' panicked at src/main.rs:2:5:\nboom\n",
+        ),
+        (
+            "thread 'worker' (17) panicked at src/lib.rs:9:1:\nboom\n",
+            "thread 'worker' panicked at src/lib.rs:9:1:\nboom\n",
+        ),
+        (
+            "thread '<unnamed>' panicked at src/lib.rs:9:1:\nboom\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\nother output\n",
+            "thread '<unnamed>' panicked at src/lib.rs:9:1:\nboom\nother output\n",
+        ),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterPanic::new().filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
+
+#[test]
+fn filter_panic_registry_paths() {
+    let input = "\
+thread 'main' panicked at src/main.rs:2:5:
+boom
+stack backtrace:
+   0: serde_json::de::from_str
+             at /home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde_json-1.0.114/src/de.rs:2676:5
+";
+    let expected = "\
+thread 'main' panicked at src/main.rs:2:5:
+boom
+stack backtrace:
+  serde_json::de::from_str
+    at [CARGO_REGISTRY]/serde_json-1.0.114/src/de.rs:2676:5
+";
+    let actual = FilterPanic::new().filter(Data::text(input));
+    assert_eq!(Data::text(expected), actual);
+}
+
+#[test]
+fn filter_hyperlinks_strip() {
+    let cases = [
+        (
+            "see \x1b]8;;https://example.com/1\x1b\\the docs\x1b]8;;\x1b\\ for more",
+            "see the docs for more",
+        ),
+        (
+            "\x1b]8;id=5;https://example.com/2\x07bel\x1b]8;;\x07 terminated",
+            "bel terminated",
+        ),
+        (
+            "unterminated \x1b]8;;https://example.com/3",
+            "unterminated \x1b]8;;https://example.com/3",
+        ),
+        ("plain \x1b[1mbold\x1b[0m", "plain \x1b[1mbold\x1b[0m"),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterHyperlinks::new().filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
+
+#[test]
+fn strip_ansi() {
+    let cases = [
+        ("\x1b[1;31merror\x1b[0m: oops", "error: oops"),
+        ("\x1b[2K\x1b[1Gdone\n", "done\n"),
+        ("\x1b[38;5;208mtext\x1b[m", "text"),
+        ("no escapes", "no escapes"),
+        ("unterminated \x1b[31", "unterminated \x1b[31"),
+    ];
+    for (input, expected) in cases {
+        let actual = StripAnsi::new().filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
+
+#[test]
+fn strip_ansi_keep_sgr() {
+    let input = "\x1b[2K\x1b[1A\x1b[32mok\x1b[0m";
+    let actual = StripAnsi::new().keep_sgr(true).filter(Data::text(input));
+    assert_eq!(Data::text("\x1b[32mok\x1b[0m"), actual);
+}
+
+#[test]
+fn filter_hyperlinks_redact_urls() {
+    let cases = [
+        (
+            "see \x1b]8;;https://example.com/1\x1b\\the docs\x1b]8;;\x1b\\ for more",
+            "see \x1b]8;;[URL]\x1b\\the docs\x1b]8;;\x1b\\ for more",
+        ),
+        (
+            "\x1b]8;id=5;https://example.com/2\x07bel\x1b]8;;\x07 terminated",
+            "\x1b]8;id=5;[URL]\x07bel\x1b]8;;\x07 terminated",
+        ),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterHyperlinks::new()
+            .redact_urls(true)
+            .filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn http_response_reordered_headers() {
+    let filter = FilterHttpResponse::new().ignore_header("Date");
+    let actual = filter.filter(Data::text(
+        "HTTP/1.1 201 Created\r
+Date: Mon, 01 Jan 2024 00:00:00 GMT\r
+X-Request-Id: 1234\r
+Content-Type: application/json\r
+Set-Cookie: a=1\r
+Set-Cookie: b=2\r
+\r
+{\"id\": 42, \"name\": \"widget\"}",
+    ));
+    let expected = filter.filter(Data::text(
+        "HTTP/1.1 201 Created
+content-type: application/json
+set-cookie: a=1
+set-cookie: b=2
+x-request-id: 1234
+
+{\"id\": \"{...}\", \"name\": \"widget\"}
+",
+    ));
+    assert_eq!(
+        expected,
+        Data::json(json!({
+            "version": "HTTP/1.1",
+            "status": 201,
+            "reason": "Created",
+            "headers": {
+                "content-type": "application/json",
+                "set-cookie": ["a=1", "b=2"],
+                "x-request-id": "1234",
+            },
+            "body": {"id": "{...}", "name": "widget"},
+        }))
+    );
+
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(actual, &expected);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn http_response_non_json_body() {
+    let actual = FilterHttpResponse::new().filter(Data::text("HTTP/2 404\n\nnot found\n"));
+    assert_eq!(
+        actual,
+        Data::json(json!({
+            "version": "HTTP/2",
+            "status": 404,
+            "reason": "",
+            "headers": {},
+            "body": "not found\n",
+        }))
+    );
+
+    let text = Data::text("hello world\n");
+    assert_eq!(FilterHttpResponse::new().filter(text.clone()), text);
+}
+
+#[test]
+fn path_separators_in_quotes() {
+    let cases = [
+        (r#"\\server\share"#, r#"\\server\share"#),
+        (r#"path: "C:\\Users\\me""#, r#"path: "C:/Users/me""#),
+        (r#""say \"hi\"\n" a\b"#, r#""say \"hi\"\n" a\b"#),
+        ("\"open\\\\\nnext\\\\line", "\"open/\nnext\\\\line"),
+    ];
+    for (input, expected) in cases {
+        let actual = PathSeparators::InQuotes.filter(Data::text(input));
+        assert_eq!(actual, Data::text(expected), "input={input:?}");
+    }
+}
+
+#[test]
+fn path_separators_never() {
+    let input = r#"\\server\share "C:\\Users""#;
+    let actual = PathSeparators::Never.filter(Data::text(input));
+    assert_eq!(actual, Data::text(input));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_path_separators_in_quotes() {
+    let data = Data::json(json!({"path": "C:\\Users"}));
+    let data = PathSeparators::InQuotes.filter(data);
+    assert_eq!(Data::json(json!({"path": "C:/Users"})), data);
+}
+
+#[test]
+fn lines_sorted() {
+    let cases = [
+        ("b\na\nc\n", "a\nb\nc\n"),
+        ("b\na", "a\nb"),
+        ("b\n\na\n", "\na\nb\n"),
+        ("", ""),
+    ];
+    for (input, expected) in cases {
+        let actual = SortLines.filter(Data::text(input));
+        assert_eq!(actual, Data::text(expected), "input={input:?}");
+    }
+}
+
+#[test]
+fn lines_truncated() {
+    let truncate = TruncateLines::new(4);
+    let cases = [
+        ("abcd\nabcdef\n", "abcd\nabcd[TRUNC]\n"),
+        ("abcd[TRUNC]\r\n", "abcd[TRUNC]\r\n"),
+        ("héllo wörld", "héll[TRUNC]"),
+    ];
+    for (input, expected) in cases {
+        let actual = truncate.filter(Data::text(input));
+        assert_eq!(actual, Data::text(expected), "input={input:?}");
+    }
+
+    let actual = TruncateLines::new(2)
+        .marker("…")
+        .filter(Data::text("abc\n"));
+    assert_eq!(actual, Data::text("ab…\n"));
+}
+
+#[test]
+#[cfg(feature = "unicode-normalization")]
+fn unicode_normalized() {
+    let composed = "caf\u{e9} \u{c5}ngstr\u{f6}m";
+    let decomposed = "cafe\u{301} A\u{30a}ngstro\u{308}m";
+    assert_eq!(
+        NormalizeUnicode::Nfc.filter(Data::text(decomposed)),
+        Data::text(composed)
+    );
+    assert_eq!(
+        NormalizeUnicode::Nfd.filter(Data::text(composed)),
+        Data::text(decomposed)
+    );
+}
+
+#[test]
+fn lint_pattern_reports_mistakes() {
+    let mut redactions = Redactions::new();
+    redactions.insert("[ROOT]", "/home/ci").unwrap();
+    let pattern = "\
+[ROOT]/src [N:0..10] [ID:a] [literal:[INFO]] [..] [..>] [...]
+[ROOTS] [N:ten] [.] [....]
+  ...
+[UNORDERED]
+a
+<<<verbatim
+[NOT_LINTED]
+>>>
+unclosed [..
+";
+    let errors = lint_pattern(pattern, &redactions)
+        .unwrap_err()
+        .into_iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            "line 2: unknown placeholder `[ROOTS]`",
+            "line 2: invalid `[N:ten]`",
+            "line 2: unknown wildcard `[.]`, expected `[..]`, `[..>]`, or `[...]`",
+            "line 2: unknown wildcard `[....]`, expected `[..]`, `[..>]`, or `[...]`",
+            "line 3: `...` must be on a line by itself, like `...` or `...{0,3}`",
+            "line 9: unclosed `[..`",
+            "line 4: `[UNORDERED]` without a `[/UNORDERED]`",
+        ]
+    );
+
+    assert_eq!(
+        lint_pattern("a [..]\n...\n...{0,3}\nb\n", &redactions),
+        Ok(())
+    );
+    // Literal text that happens to start with `...`
+    assert_eq!(
+        lint_pattern("... trailing\n...and more\n", &redactions),
+        Ok(())
+    );
+    assert_eq!(
+        lint_pattern("...{3,1}\n", &redactions)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>(),
+        ["line 1: `...` must be on a line by itself, like `...` or `...{0,3}`"]
+    );
+}
+
+#[test]
+fn absolute_paths_redacted() {
+    let cases = [
+        (
+            "error: could not read /tmp/.tmpA1b2/config.toml: not found",
+            "error: could not read [PATH]: not found",
+        ),
+        ("--> /home/ci/src/main.rs:10:5", "--> [PATH]:10:5"),
+        ("see \"/var/log/app.log\".", "see \"[PATH]\"."),
+        ("wrote /tmp/out.txt.", "wrote [PATH]."),
+        (r"C:\Users\ci\file.txt", "[PATH]"),
+        ("path=C:/Users/ci", "path=[PATH]"),
+        (r"(\\server\share\file.txt)", "([PATH])"),
+        ("https://example.com/a/b", "https://example.com/a/b"),
+        ("1/2 and/or 3/4", "1/2 and/or 3/4"),
+        ("run /help", "run /help"),
+        ("root is /", "root is /"),
+    ];
+    for (input, expected) in cases {
+        let actual = FilterAbsolutePaths.filter(Data::text(input));
+        assert_eq!(Data::text(expected), actual, "input={input:?}");
+    }
+}
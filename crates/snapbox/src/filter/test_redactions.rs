@@ -105,6 +105,28 @@ fn str_normalize_trailing_elide() {
     assert_eq!(actual, expected.into_data());
 }
 
+#[test]
+fn str_normalize_trailing_elide_after_consumed() {
+    let input = "Hello\nWorld\n";
+    let pattern = "Hello\nWorld\n...\n";
+    let expected = "Hello\nWorld\n...\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_trailing_elide_with_optional() {
+    let pattern = "Hello\n...\n[?] Goodbye\n";
+    for input in ["Hello\n", "Hello\nWorld\n", "Hello\nWorld\nGoodbye\n"] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "{input:?}");
+    }
+}
+
 #[test]
 fn str_normalize_middle_elide() {
     let input = "Hello\nWorld\nGoodbye";
@@ -116,6 +138,50 @@ fn str_normalize_middle_elide() {
     assert_eq!(actual, expected.into_data());
 }
 
+#[test]
+fn str_normalize_elide_matches_empty() {
+    let input = "";
+    let pattern = "...\n";
+    let expected = "...\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_elide_require_non_empty() {
+    let pattern = "...\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .require_non_empty()
+        .normalize("".into(), &pattern.into());
+    assert_eq!(actual.format(), crate::data::DataFormat::Error);
+    assert_ne!(actual, pattern.into_data());
+
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .require_non_empty()
+        .normalize(" \n".into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_elide_require_non_blank() {
+    let pattern = "...\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .require_non_blank()
+        .normalize(" \n".into(), &pattern.into());
+    assert_eq!(actual.format(), crate::data::DataFormat::Error);
+
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .require_non_blank()
+        .normalize("Hello\n".into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
 #[test]
 fn str_normalize_post_elide_diverge() {
     let input = "Hello\nSun\nAnd\nWorld";
@@ -458,6 +524,118 @@ fn json_normalize_glob_for_array_mismatch() {
     }
 }
 
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_glob_for_array_head_and_tail() {
+    let expected = Data::json(json!([1, 2, "{...}", 9, 10]));
+    let actual = json!([1, 2, 3, 4, 5, 9, 10]);
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_glob_for_array_tail_repeats_anchor() {
+    let expected = Data::json(json!([1, "{...}", 9, 10]));
+    let actual = json!([1, 9, 5, 9, 10]);
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_glob_for_array_tail_mismatch() {
+    let expected = Data::json(json!([1, 2, "{...}", 9, 10]));
+    let actual = json!([1, 2, 3, 9, 4, 10]);
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(Data::json(json!([1, 2, "{...}", 9, 4, 10])), actual);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_typed_wildcards() {
+    let expected = Data::json(json!({
+        "id": "{number}",
+        "name": "{string}",
+        "active": "{bool}",
+        "tags": "{array}",
+        "owner": "{object}",
+    }));
+    let actual = json!({
+        "id": 42,
+        "name": "alice",
+        "active": true,
+        "tags": ["a"],
+        "owner": {"id": 1},
+    });
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(expected, actual);
+
+    let actual = json!({
+        "id": "42",
+        "name": null,
+        "active": 1,
+        "tags": {},
+        "owner": [],
+    });
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual.clone()), &expected);
+    assert_eq!(
+        Data::json(json!({
+            "id": "42",
+            "name": null,
+            "active": 1,
+            "tags": {},
+            "owner": [],
+        })),
+        actual
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_unordered_marker() {
+    let expected = Data::json(json!({
+        "tags": {"$unordered": ["b", {"id": "[..]"}, "{...}", "a"]}
+    }));
+    let actual = json!({
+        "tags": ["a", {"id": "x1"}, "c", "b"]
+    });
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(expected, actual);
+
+    let actual = json!({
+        "tags": ["z", "a", {"id": "x1"}, "b", "c"]
+    });
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(actual), &expected);
+    assert_eq!(
+        Data::json(json!({
+            "tags": {"$unordered": ["b", {"id": "[..]"}, "{...}", "a", "c"]}
+        })),
+        actual
+    );
+
+    // Wildcards don't take elements that literals need
+    let expected = Data::json(json!({"$unordered": ["[..]", "x"]}));
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(json!(["x", "y"])), &expected);
+    assert_eq!(expected, actual);
+}
+
 #[test]
 #[cfg(feature = "json")]
 fn json_normalize_bad_order() {
@@ -565,3 +743,1328 @@ fn json_normalize_glob_obj_key() {
     let expected_actual = Data::json(expected_actual);
     assert_eq!(actual, expected_actual);
 }
+
+#[test]
+#[cfg(feature = "semver")]
+fn str_normalize_semver() {
+    let pattern = "cargo [SEMVER] (built [..])\n";
+    for input in [
+        "cargo 1.4.2 (built today)\n",
+        "cargo 1.0.0-beta.1+abc (built today)\n",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+
+    let input = "cargo 1.4 (built today)\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+#[cfg(feature = "semver")]
+fn str_normalize_semver_req() {
+    let pattern = "Installed v[SEMVER:^1.2].\n";
+
+    let input = "Installed v1.4.2.\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "Installed v2.0.0.\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+#[cfg(feature = "semver")]
+fn str_normalize_semver_invalid_req_is_literal() {
+    let pattern = "v[SEMVER:not a req]\n";
+    let input = "v1.0.0\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+#[cfg(all(feature = "semver", feature = "json"))]
+fn json_normalize_semver() {
+    let input = json!({"name": "snapbox", "version": "0.6.21"});
+    let pattern = json!({"name": "snapbox", "version": "[SEMVER:0.6]"});
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern));
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_inline_regex() {
+    let pattern = "[regex:\\d+ tests? passed]; finished in [..]\n";
+    for input in [
+        "1 test passed; finished in 0.1s\n",
+        "42 tests passed; finished in 2.0s\n",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+
+    let input = "no tests passed; finished in 0.1s\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_inline_regex_brackets() {
+    let pattern = "version = [regex:[0-9]+\\.[0-9]+]\n";
+    let input = "version = 1.2\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_double_brackets_are_literal() {
+    let pattern = "[[package]] # [..]\nx[[1]] = [..]\n";
+    let input = "[[package]] # a\nx[[1]] = 5\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "package # a\nx1 = 5\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_bracketed_placeholders() {
+    let pattern = "[[..]] [[EXE]] [[N:0..10]]\n";
+    let input = format!("[ab] [{}] [5]\n", std::env::consts::EXE_SUFFIX);
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::with_exe())
+        .normalize(input.as_str().into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_inline_regex_invalid_is_literal() {
+    let pattern = "value: [regex:(unclosed]\n";
+    let input = "value: (unclosed\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_backref_matches_same_text() {
+    let pattern = "Created job [ID:job] ([..])
+Started job [ID:job]
+Finished job [ID:job] in [ID:job]
+";
+    let input = "Created job a1b2 (queued)
+Started job a1b2
+Finished job a1b2 in a1b2
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_backref_rejects_different_text() {
+    let pattern = "Created job [ID:job]
+Started job [ID:job]
+Finished job [ID:other]
+";
+    let input = "Created job a1b2
+Started job c3d4
+Finished job c3d4
+";
+    let expected = "Created job [ID:job]
+Started job c3d4
+Finished job [ID:other]
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_number_tolerance() {
+    let pattern = "mean: [NUM:1.5±0.05]s, rate: [NUM:200~5%]/s\n";
+    for input in [
+        "mean: 1.5s, rate: 200/s\n",
+        "mean: 1.46s, rate: 209.9/s\n",
+        "mean: 1.55s, rate: 1.9e2/s\n",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+
+    for input in ["mean: 1.6s, rate: 200/s\n", "mean: 1.5s, rate: 189/s\n"] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, input.into_data(), "input={input:?}");
+    }
+}
+
+#[test]
+fn str_normalize_bounded_elide() {
+    let pattern = "Compiling
+...{0,2}
+Finished
+";
+    for input in ["Compiling\nFinished\n", "Compiling\nfoo\nbar\nFinished\n"] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+
+    let input = "Compiling
+foo
+bar
+error: oops
+Finished
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_exact_elide() {
+    let pattern = "Compiling
+...{2}
+";
+    let input = "Compiling
+foo
+bar
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "Compiling
+foo
+bar
+error: oops
+";
+    let expected = "Compiling
+...{2}
+error: oops
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+
+    let input = "Compiling
+foo
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_negative_line() {
+    let pattern = "Running tests
+...
+[!CONTAINS] panicked at
+test result: ok
+";
+    let input = "Running tests
+test a ... ok
+test b ... ok
+test result: ok
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "Running tests
+test a ... ok
+thread 'b' panicked at src/lib.rs:10:5
+test result: ok
+";
+    let expected = "Running tests
+...
+thread 'b' panicked at src/lib.rs:10:5
+test result: ok
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_resync_after_inserted_line() {
+    let pattern = "Compiling a v[..]
+Compiling b v[..]
+Compiling c v[..]
+Finished in [..]s
+";
+    let input = "Compiling a v1.0.0
+warning: unused import
+Compiling b v1.0.0
+Compiling c v1.0.0
+Finished in 1.2s
+";
+    let expected = "Compiling a v[..]
+warning: unused import
+Compiling b v[..]
+Compiling c v[..]
+Finished in [..]s
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_resync_after_missing_line() {
+    let pattern = "Compiling a v[..]
+Compiling b v[..]
+Compiling c v[..]
+Finished in [..]s
+";
+    let input = "Compiling a v1.0.0
+Compiling c v1.0.0
+Finished in 1.2s
+";
+    let expected = "Compiling a v[..]
+Compiling c v[..]
+Finished in [..]s
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_resync_prefers_longest_alignment() {
+    let pattern = "a
+note: [..]
+b
+c
+";
+    let input = "note: x
+note: y
+a
+note: z
+b
+c
+";
+    let expected = "note: x
+note: y
+a
+note: [..]
+b
+c
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_resync_large_divergent_input() {
+    let input: String = (0..2000).map(|i| format!("actual {i}\n")).collect();
+    let pattern: String = (0..2000).map(|i| format!("expected {i} [..]\n")).collect();
+    let start = std::time::Instant::now();
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.as_str().into(), &pattern.as_str().into());
+    assert_eq!(actual, input.as_str().into_data());
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(30),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn str_normalize_case_insensitive() {
+    let pattern = "Compiling foo v[..] (C:/Users/[..]/foo)
+[!ONE_OF] Finished|Done
+";
+    let input = "Compiling foo v1.0.0 (c:/users/me/foo)
+DONE
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_verbatim_matches() {
+    let input = "Running example:
+fn main() {
+    println!(\"[..]\");
+}
+...
+Done in 1.5s
+";
+    let pattern = "Running [..]:
+<<<verbatim
+fn main() {
+    println!(\"[..]\");
+}
+...
+>>>
+Done in [..]s
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_verbatim_is_literal() {
+    let input = "Running example:
+fn main() {
+  println!(\"Hello\");
+}
+Done in 1.5s
+";
+    let pattern = "Running [..]:
+<<<verbatim
+fn main() {
+    println!(\"[..]\");
+}
+>>>
+Done in [..]s
+";
+    let expected = "Running [..]:
+<<<verbatim
+fn main() {
+  println!(\"Hello\");
+}
+>>>
+Done in [..]s
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_verbatim_skips_redactions() {
+    let input = "Running /home/me/example:
+cat /home/me/example/main.rs
+Done
+";
+    let pattern = "Running [ROOT]/example:
+<<<verbatim
+cat /home/me/example/main.rs
+>>>
+Done
+";
+    let mut redactions = Redactions::new();
+    redactions.insert("[ROOT]", "/home/me").unwrap();
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let pattern = "Running [ROOT]/example:
+<<<verbatim
+cat [ROOT]/example/main.rs
+>>>
+Done
+";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_ne!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_elide_before_verbatim() {
+    let input = "Compiling
+Building
+fn main() {}
+Done
+";
+    let pattern = "...
+<<<verbatim
+fn main() {}
+>>>
+Done
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_unordered_block_matches() {
+    let input = "Starting 3 workers
+worker 2: done in 12ms
+worker 0: done in 3ms
+worker 1: done in 8ms
+Finished
+";
+    let pattern = "Starting [..] workers
+[UNORDERED]
+worker 0: done in [..]ms
+worker 1: done in [..]ms
+worker 2: done in [..]ms
+[/UNORDERED]
+Finished
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_unordered_block_mismatch() {
+    let input = "worker 2: done
+worker 0: failed
+worker 1: done
+Finished
+";
+    let pattern = "[UNORDERED]
+worker 0: done
+worker 1: done
+worker 2: done
+[/UNORDERED]
+Finished
+";
+    let expected = "[UNORDERED]
+worker 1: done
+worker 2: done
+worker 0: failed
+[/UNORDERED]
+Finished
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_elide_before_unordered_block() {
+    let input = "Compiling
+Building
+b
+a
+Done
+";
+    let pattern = "...
+[UNORDERED]
+a
+b
+[/UNORDERED]
+Done
+";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_integer_range() {
+    let pattern = "progress: [N:0..100]%\n";
+
+    for input in ["progress: 42%\n", "progress: 0%\n", "progress: 99%\n"] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+
+    for input in [
+        "progress: 200%\n",
+        "progress: 100%\n",
+        "progress: -1%\n",
+        "progress: many%\n",
+        "progress: 4.2%\n",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, input.into_data(), "input={input:?}");
+    }
+}
+
+#[test]
+fn str_normalize_integer_range_inclusive() {
+    let pattern = "exit code [N:-1..=1]\n";
+    for input in ["exit code -1\n", "exit code 1\n"] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+    let input = "exit code 2\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+#[cfg(all(feature = "json", feature = "regex"))]
+fn json_normalize_regex_value() {
+    let pattern = json!({"code": "{regex:^err-[0-9]+$}", "message": "[..]"});
+
+    let input = json!({"code": "err-42", "message": "Not found"});
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern.clone()));
+
+    let input = json!({"code": "ok", "message": "Found"});
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(json!({"code": "ok", "message": "[..]"})));
+
+    let input = json!({"code": 42, "message": "Found"});
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(Data::json(input), &Data::json(pattern));
+    assert_eq!(actual, Data::json(json!({"code": 42, "message": "[..]"})));
+}
+
+#[test]
+#[cfg(all(feature = "json", feature = "regex"))]
+fn json_normalize_regex_value_unordered() {
+    let pattern = json!(["{regex:^v[0-9]+$}", "stable"]);
+    let input = json!(["stable", "v2"]);
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .unordered()
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_duration_value() {
+    let pattern = json!({"name": "build", "elapsed": "{duration}"});
+
+    for elapsed in [json!(2), json!(1.234), json!({"secs": 1, "nanos": 234})] {
+        let input = json!({"name": "build", "elapsed": elapsed});
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(Data::json(input), &Data::json(pattern.clone()));
+        assert_eq!(actual, Data::json(pattern.clone()), "{elapsed}");
+    }
+
+    for elapsed in [
+        json!(-1),
+        json!("1s"),
+        json!({"secs": 1}),
+        json!({"secs": 1, "nanos": 1_000_000_000}),
+    ] {
+        let input = json!({"name": "build", "elapsed": elapsed.clone()});
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(Data::json(input.clone()), &Data::json(pattern.clone()));
+        assert_eq!(actual, Data::json(input), "{elapsed}");
+    }
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_epoch_preset() {
+    let mut redactions = Redactions::new();
+    redactions.insert_epoch_preset().unwrap();
+
+    let input = "started 1700000000, finished 1700000000123, id 9123456789012, size 17000000001\n";
+    let pattern = "started [EPOCH], finished [EPOCH], id 9123456789012, size 17000000001\n";
+    assert_eq!(redactions.redact(input), pattern);
+
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "took 1706659200.5s, ratio 0.1706659200, at 1706659200 1706659201.\n";
+    let pattern = "took 1706659200.5s, ratio 0.1706659200, at [EPOCH] [EPOCH].\n";
+    assert_eq!(redactions.redact(input), pattern);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_iso_week_preset() {
+    let mut redactions = Redactions::new();
+    redactions.insert_iso_week_preset().unwrap();
+
+    let input = "week 2024-W05, day 2024-W52-7, not 2024-W54 or 2024-W05-8\n";
+    let pattern = "week [DATE], day [DATE], not 2024-W54 or 2024-W05-8\n";
+    assert_eq!(redactions.redact(input), pattern);
+
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn str_normalize_common_presets() {
+    let redactions = Redactions::with_common();
+    assert_eq!(redactions.validate(), Ok(()));
+
+    let input = "\
+[2024-01-31T12:30:45.123Z] request 67E55044-10b1-426f-9247-bb680e5fe0c8 started
+[2024-01-31 12:30:46+01:00] finished in 1.23s (150ms waiting, 12µs parsing)
+freed 0x7ffd5e8c, flags 0x1
+3 sets, 2024-01-31, version 1.2.3, id 67e55044-10b1
+";
+    let pattern = "\
+[[TIMESTAMP]] request [UUID] started
+[[TIMESTAMP]] finished in [DURATION] ([DURATION] waiting, [DURATION] parsing)
+freed [HEXADDR], flags 0x1
+3 sets, 2024-01-31, version 1.2.3, id 67e55044-10b1
+";
+    assert_eq!(redactions.redact(input), pattern);
+
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+#[cfg(all(feature = "json", feature = "regex"))]
+fn json_normalize_scoped_redactions() {
+    let mut event = Redactions::new();
+    event
+        .insert("[TIMESTAMP]", regex::Regex::new("[0-9]{10}").unwrap())
+        .unwrap();
+    event.insert("[USER]", "alice").unwrap();
+    let mut redactions = Redactions::new();
+    redactions.merge(event.scoped("/events/*").unwrap());
+
+    let input = json!({
+        "owner": "alice",
+        "created": "1700000000",
+        "events": [
+            {"at": "1700000001", "by": "alice"},
+            {"at": "1700000002", "by": "bob"},
+        ],
+    });
+    let pattern = json!({
+        "owner": "alice",
+        "created": "1700000000",
+        "events": [
+            {"at": "[TIMESTAMP]", "by": "[USER]"},
+            {"at": "[TIMESTAMP]", "by": "bob"},
+        ],
+    });
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern));
+
+    // Scoping again nests under the new prefix
+    let mut nested = Redactions::new();
+    nested.merge(redactions.scoped("/runs/*").unwrap());
+    let mut actual = json!({"runs": [{"events": [{"at": "1700000003"}]}], "at": "1700000004"});
+    nested.redact_json(&mut actual);
+    assert_eq!(
+        actual,
+        json!({"runs": [{"events": [{"at": "[TIMESTAMP]"}]}], "at": "1700000004"})
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_numbered_redactions() {
+    let mut redactions = Redactions::new();
+    redactions
+        .insert_numbered("[USER]", RedactedValue::glob("user-??"))
+        .unwrap();
+
+    let input = json!({
+        "owner": "user-ab",
+        "events": [{"by": "user-cd"}, {"by": "user-ab", "for": "user-cd"}],
+    });
+    let pattern = json!({
+        "owner": "[USER_2]",
+        "events": [{"by": "[USER_1]"}, {"by": "[USER_2]", "for": "[USER_1]"}],
+    });
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_pointer_redactions() {
+    let mut redactions = Redactions::new();
+    redactions
+        .insert_json_pointer("[TIMESTAMP]", "/items/*/created_at")
+        .unwrap();
+    redactions.insert_json_pointer("[META]", "/meta").unwrap();
+
+    let input = json!({
+        "created_at": "2024-01-31",
+        "meta": {"host": "ci-1", "pid": 4321},
+        "items": [
+            {"name": "2024-01-31", "created_at": "2024-01-31"},
+            {"name": "b", "created_at": 1706659200},
+        ],
+    });
+    let pattern = json!({
+        "created_at": "2024-01-31",
+        "meta": "[META]",
+        "items": [
+            {"name": "2024-01-31", "created_at": "[TIMESTAMP]"},
+            {"name": "b", "created_at": "[TIMESTAMP]"},
+        ],
+    });
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern));
+
+    // Pointers are relative to each line
+    let input = Data::jsonlines([json!({"at": 1}), json!({"at": 2, "id": "x"})]);
+    let pattern = Data::jsonlines([json!({"at": "[AT]"}), json!({"at": "[AT]", "id": "x"})]);
+    let mut redactions = Redactions::new();
+    redactions.insert_json_pointer("[AT]", "/at").unwrap();
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input, &pattern);
+    assert_eq!(actual, pattern);
+
+    // Scoped pointers nest under the prefix
+    let mut nested = Redactions::new();
+    nested.merge(redactions.scoped("/runs/*").unwrap());
+    let mut actual = json!({"runs": [{"at": 3}], "at": 4});
+    nested.redact_json(&mut actual);
+    assert_eq!(actual, json!({"runs": [{"at": "[AT]"}], "at": 4}));
+
+    assert!(Redactions::new()
+        .insert_json_pointer("[ITEM]", "items/0")
+        .is_err());
+    assert!(Redactions::new().scoped("runs/*").is_err());
+}
+
+#[test]
+fn str_normalize_conditional_redaction() {
+    static ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+    let mut redactions = Redactions::new();
+    redactions
+        .insert_if("[PORT]", "54321", || {
+            ENABLED.load(std::sync::atomic::Ordering::SeqCst)
+        })
+        .unwrap();
+
+    let input = "listening on 54321\n";
+    let redacted = "listening on [PORT]\n";
+    let normalize = |pattern: &str| {
+        NormalizeToExpected::new()
+            .redact_with(&redactions)
+            .normalize(input.into(), &pattern.into())
+    };
+
+    assert_eq!(normalize(redacted), redacted.into_data());
+    assert_eq!(normalize(input), redacted.into_data());
+
+    // A skipped redaction is as if it was never inserted
+    ENABLED.store(false, std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(normalize(redacted), input.into_data());
+    assert_eq!(normalize(input), input.into_data());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_normalize_whole_numbers() {
+    let pattern = json!({"a": 1, "b": [2.0, 3], "c": {"d": 4}});
+
+    let input = json!({"a": 1.0, "b": [2, 3.0], "c": {"d": 4.0}});
+    let actual = NormalizeToExpected::new()
+        .equate_whole_numbers()
+        .normalize(Data::json(input.clone()), &Data::json(pattern.clone()));
+    assert_eq!(actual, Data::json(pattern.clone()));
+
+    // Opt-in
+    let actual =
+        NormalizeToExpected::new().normalize(Data::json(input), &Data::json(pattern.clone()));
+    assert_ne!(actual, Data::json(pattern));
+
+    let pattern = json!([1, 9007199254740993_u64]);
+    let input = json!([1.5, 9007199254740992.0]);
+    let actual = NormalizeToExpected::new()
+        .equate_whole_numbers()
+        .normalize(Data::json(input.clone()), &Data::json(pattern));
+    assert_eq!(actual, Data::json(input));
+}
+
+#[test]
+fn str_normalize_optional_line() {
+    let pattern = "Compiling foo
+[?] warning: unused variable `[..]`
+Finished in [..]s
+";
+
+    for input in [
+        "Compiling foo
+warning: unused variable `x`
+Finished in 1.5s
+",
+        "Compiling foo
+Finished in 1.5s
+",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+}
+
+#[test]
+fn str_normalize_optional_line_with_redactions() {
+    let mut redactions = Redactions::new();
+    redactions
+        .insert("[TARGET]", "x86_64-pc-windows-msvc")
+        .unwrap();
+    let pattern = "Compiling foo
+[?] note: building for [TARGET]
+Finished
+";
+
+    let input = "Compiling foo
+note: building for x86_64-pc-windows-msvc
+Finished
+";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    // Present but not matching is reported as extra content
+    let input = "Compiling foo
+note: building for aarch64-apple-darwin
+Finished
+";
+    let expected = "Compiling foo
+[?] note: building for [TARGET]
+note: building for aarch64-apple-darwin
+Finished
+";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, expected.into_data());
+}
+
+#[test]
+fn str_normalize_optional_line_after_elide() {
+    let pattern = "...
+[?] warning: deprecated
+Done
+";
+
+    for input in [
+        "Compiling
+warning: deprecated
+Done
+",
+        "Compiling
+Done
+",
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "input={input:?}");
+    }
+}
+
+#[test]
+fn str_normalize_trim_elide_anchors() {
+    let input = "
+Hello
+World
+Goodbye   
+Moon
+";
+    let pattern = "
+Hello
+...
+Goodbye
+Moon
+";
+    let expected = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_ne!(expected, pattern.into_data());
+
+    let expected = NormalizeToExpected::new()
+        .redact()
+        .trim_elide_anchors()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(expected, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_trim_elide_anchors_after_optional() {
+    let input = "
+Hello
+World
+Goodbye\t
+Moon
+";
+    let pattern = "
+Hello
+...
+[?] Sun
+Goodbye
+Moon
+";
+    let expected = NormalizeToExpected::new()
+        .redact()
+        .trim_elide_anchors()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(expected, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_trim_elide_anchors_only_anchor() {
+    let input = "
+Hello
+World
+Goodbye
+Moon  
+";
+    let pattern = "
+Hello
+...
+Goodbye
+Moon
+";
+    let expected = NormalizeToExpected::new()
+        .redact()
+        .trim_elide_anchors()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(
+        expected,
+        "
+Hello
+...
+Goodbye
+Moon  
+"
+        .into_data()
+    );
+}
+
+#[test]
+fn str_normalize_tokens_reflowed() {
+    let input = "The quick brown fox
+jumps over the lazy dog,
+again and again.
+";
+    let pattern = "The quick [..] fox jumps ... lazy dog,\nagain and again.";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .tokens()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_tokens_diverge() {
+    let input = "The quick brown fox\njumps over the lazy cat.\n";
+    let pattern = "The quick [..] fox jumps ... lazy dog.\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .tokens()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(
+        actual,
+        "The quick [..] fox jumps ... lazy cat.\n".into_data()
+    );
+}
+
+#[test]
+fn str_normalize_tokens_without_redactions() {
+    let input = "Hello   [..]\nworld\n";
+    let pattern = "Hello [..] world";
+    let actual = NormalizeToExpected::new()
+        .tokens()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "Hello   everyone\nworld\n";
+    let actual = NormalizeToExpected::new()
+        .tokens()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_alternatives() {
+    let pattern = "Status:\n[!ONE_OF] ok|warn[..]|error\nDone\n";
+
+    let input = "Status:\nwarn: slow disk\nDone\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "Status:\nfatal\nDone\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, "Status:\nfatal\nDone\n".into_data());
+}
+
+#[test]
+fn str_normalize_alternatives_escaped_pipe() {
+    let pattern = "[!ONE_OF] a\\|b|c\n";
+    for (input, matches) in [
+        ("a|b\n", true),
+        ("c\n", true),
+        ("a\n", false),
+        ("b\n", false),
+    ] {
+        let actual = NormalizeToExpected::new()
+            .redact()
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual == pattern.into_data(), matches, "{input:?}");
+    }
+}
+
+#[test]
+fn str_normalize_braces_are_literal() {
+    // Only the marker starts an alternation, not braces around `|`
+    let pattern = "{a|b}\n";
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize("a\n".into(), &pattern.into());
+    assert_eq!(actual, "a\n".into_data());
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .normalize("{a|b}\n".into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_multiline_literal() {
+    let input = "start\nline1\nline2\nend\n";
+    let pattern = "start\n[BLOCK]\nend\n";
+    let mut sub = Redactions::new();
+    sub.insert("[BLOCK]", "line1\r\nline2").unwrap();
+    assert_eq!(sub.redact(input), pattern);
+    assert_eq!(
+        sub.redact("start\r\nline1\r\nline2\r\nend\r\n"),
+        "start\r\n[BLOCK]\r\nend\r\n"
+    );
+    let actual = NormalizeToExpected::new()
+        .redact_with(&sub)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
+
+#[test]
+fn str_normalize_escaped_wildcards() {
+    let input = "loading...\n...\nlist [..] [EXE] [N:1..2] [literal:]\n";
+    let pattern =
+        "loading...\n[literal:...]\nlist [literal:[..]] [literal:[EXE]] [literal:[N:1..2]] [literal:[literal:]]\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::with_exe())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "list [ab]\nmore\n";
+    let pattern = "list [literal:[..]]\n[literal:...]\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::with_exe())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_multiline_wildcard() {
+    let input = "\
+error: could not read config: the file
+is missing
+help: create it
+";
+    let pattern = "\
+error: could not read config: [...] missing
+help: create it
+";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "error: could not read config: the file is missing\nhelp: create it\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "error: could not read config: the file\nis gone\nhelp: create it\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_multiline_wildcard_stops_at_next_line() {
+    let pattern = "\
+error: could not read config: [...] missing
+help: create it
+";
+    let input = "\
+error: could not read config: the file
+is gone
+help: create it
+note: still missing
+";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_greedy_wildcard() {
+    let input = "copied path/to/path/to/file\n";
+    let pattern = "copied [..>]/to/file\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let input = "copied path/to/path/to/dir\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&Redactions::new())
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_windows_path_wildcards() {
+    let cases = [
+        ("C:\\Users\\[..]\\foo.txt\n", "C:\\Users\\me\\foo.txt\n"),
+        ("target\\debug\\[..]\n", "target\\debug\\foo.exe\n"),
+        ("[ROOT]\\[..]\\foo.txt\n", "D:\\ci\\me\\foo.txt\n"),
+        ("C:\\[N:0..10]\\[literal:[..]]\n", "C:\\5\\[..]\n"),
+    ];
+    let mut redactions = Redactions::new();
+    redactions.insert("[ROOT]", "D:\\ci").unwrap();
+    for (pattern, input) in cases {
+        let actual = NormalizeToExpected::new()
+            .redact_with(&redactions)
+            .normalize(input.into(), &pattern.into());
+        assert_eq!(actual, pattern.into_data(), "pattern={pattern:?}");
+    }
+}
+
+#[test]
+fn str_normalize_case_insensitive_literal_redactions() {
+    let mut redactions = Redactions::new();
+    redactions.insert("[ROOT]", "C:\\Users\\me").unwrap();
+    let pattern = "[ROOT]\\foo\n";
+    let input = "c:\\users\\me\\foo\n";
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let actual = NormalizeToExpected::new()
+        .redact_with(&redactions)
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, input.into_data());
+}
+
+#[test]
+fn str_normalize_case_insensitive_all_modes() {
+    let pattern = "Finished
+Compiling foo
+";
+    let input = "compiling FOO
+FINISHED
+";
+    let actual = NormalizeToExpected::new()
+        .unordered()
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let actual = NormalizeToExpected::new()
+        .redact()
+        .unordered()
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let pattern = "Compiling foo
+Finished
+";
+    let input = "compiling FOO
+FINISHED
+";
+    let actual = NormalizeToExpected::new()
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+
+    let actual = NormalizeToExpected::new()
+        .tokens()
+        .case_insensitive()
+        .normalize(input.into(), &pattern.into());
+    assert_eq!(actual, pattern.into_data());
+}
@@ -0,0 +1,472 @@
+/// A wildcard within a line that only matches text of a specific shape, like `[SEMVER]`
+#[derive(Clone, Debug)]
+pub(crate) enum Token {
+    /// `[N:<range>]`
+    Integer(IntegerRange),
+    /// `[NUM:<value>±<tolerance>]` or `[NUM:<value>~<percent>%]`
+    Number(Tolerance),
+    /// `[ID:<name>]`, matching the same text as other uses of `name`
+    Backref(String),
+    #[cfg(feature = "semver")]
+    Semver(Option<semver::VersionReq>),
+    /// `[regex:<regex>]`, anchored to the text it matches
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Token {
+    /// Parse `token`, including the enclosing `[` and `]`
+    pub(crate) fn parse(token: &str) -> Option<Self> {
+        let inner = token.strip_prefix('[')?.strip_suffix(']')?;
+        let (name, arg) = match inner.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (inner, None),
+        };
+        match name {
+            "N" => IntegerRange::parse(arg?).map(Self::Integer),
+            "NUM" => Tolerance::parse(arg?).map(Self::Number),
+            "ID" => {
+                let name = arg?;
+                let is_name =
+                    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                is_name.then(|| Self::Backref(name.to_owned()))
+            }
+            #[cfg(feature = "semver")]
+            "SEMVER" => {
+                let req = match arg {
+                    Some(arg) => Some(semver::VersionReq::parse(arg).ok()?),
+                    None => None,
+                };
+                Some(Self::Semver(req))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse a `[regex:<regex>]` token at the start of `expected`, returning it and its length
+    ///
+    /// The regex may contain `]`, so the first closing `]` that leaves a valid regex is used.
+    /// See [`cached_regex`].
+    #[cfg(feature = "regex")]
+    pub(crate) fn parse_regex(expected: &str) -> Option<(Self, usize)> {
+        const PREFIX: &str = "[regex:";
+        let rest = expected.strip_prefix(PREFIX)?;
+        rest.match_indices(']').find_map(|(end, _)| {
+            let regex = cached_regex(&format!("^(?:{})$", &rest[..end]))?;
+            Some((Self::Regex(regex), PREFIX.len() + end + 1))
+        })
+    }
+
+    /// Lengths of the prefixes of `actual` this token can match, longest first
+    fn match_lens(&self, actual: &str) -> Vec<usize> {
+        match self {
+            Self::Integer(range) => {
+                let digits_start = usize::from(actual.starts_with('-'));
+                let len = actual[digits_start..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map(|len| digits_start + len)
+                    .unwrap_or(actual.len());
+                match actual[..len].parse::<i128>() {
+                    Ok(value) if range.contains(value) => vec![len],
+                    _ => Vec::new(),
+                }
+            }
+            Self::Number(tolerance) => {
+                let max = actual
+                    .find(|c: char| {
+                        !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+                    })
+                    .unwrap_or(actual.len());
+                (1..=max)
+                    .rev()
+                    .filter(|len| {
+                        actual[..*len]
+                            .parse::<f64>()
+                            .map(|value| tolerance.contains(value))
+                            .unwrap_or(false)
+                    })
+                    .collect()
+            }
+            // Handled by `segments_match` as it depends on earlier matches
+            Self::Backref(_) => Vec::new(),
+            #[cfg(feature = "semver")]
+            Self::Semver(req) => {
+                let max = actual
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+                    .unwrap_or(actual.len());
+                (1..=max)
+                    .rev()
+                    .filter(|len| {
+                        let Ok(version) = semver::Version::parse(&actual[..*len]) else {
+                            return false;
+                        };
+                        req.as_ref()
+                            .map(|req| req.matches(&version))
+                            .unwrap_or(true)
+                    })
+                    .collect()
+            }
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => actual
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([actual.len()])
+                .rev()
+                .filter(|len| regex.is_match(&actual[..*len]))
+                .collect(),
+        }
+    }
+}
+
+/// Compile `pattern`, reusing it if it was compiled before
+///
+/// Returns `None` if `pattern` is invalid.  Classes like `\d` fall back to ASCII when Unicode
+/// support isn't compiled in.
+#[cfg(feature = "regex")]
+pub(crate) fn cached_regex(pattern: &str) -> Option<regex::Regex> {
+    static REGEXES: RegexCache = RegexCache::new();
+    REGEXES.compile(pattern)
+}
+
+/// Compiled regexes for `[regex:]` and `{regex:}` tokens, looked up by their pattern
+///
+/// Lines and values are matched many times while resyncing and searching, so each token would
+/// otherwise be recompiled per attempt.  Invalid patterns are cached too as `parse_regex` tries
+/// each `]`.
+#[cfg(feature = "regex")]
+struct RegexCache {
+    regexes: std::sync::Mutex<Vec<(String, Option<regex::Regex>)>>,
+}
+
+#[cfg(feature = "regex")]
+impl RegexCache {
+    /// Regexes kept at once, enough for the tokens in a few snapshots
+    const CAPACITY: usize = 64;
+
+    const fn new() -> Self {
+        Self {
+            regexes: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn compile(&self, pattern: &str) -> Option<regex::Regex> {
+        let mut regexes = match self.regexes.lock() {
+            Ok(regexes) => regexes,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some((_, regex)) = regexes.iter().find(|(cached, _)| cached == pattern) {
+            return regex.clone();
+        }
+
+        let regex = regex::Regex::new(pattern)
+            .or_else(|_| regex::RegexBuilder::new(pattern).unicode(false).build())
+            .ok();
+        if regexes.len() == Self::CAPACITY {
+            regexes.remove(0);
+        }
+        regexes.push((pattern.to_owned(), regex.clone()));
+        regex
+    }
+}
+
+/// Integer bounds, using Rust's syntax: `0..100`, `0..=100`, `10..`, `..100`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct IntegerRange {
+    start: Option<i128>,
+    end: std::ops::Bound<i128>,
+}
+
+impl IntegerRange {
+    fn parse(range: &str) -> Option<Self> {
+        let (start, end) = range.split_once("..")?;
+        let start = if start.is_empty() {
+            None
+        } else {
+            Some(start.parse().ok()?)
+        };
+        let end = if let Some(end) = end.strip_prefix('=') {
+            std::ops::Bound::Included(end.parse().ok()?)
+        } else if end.is_empty() {
+            std::ops::Bound::Unbounded
+        } else {
+            std::ops::Bound::Excluded(end.parse().ok()?)
+        };
+        Some(Self { start, end })
+    }
+
+    fn contains(&self, value: i128) -> bool {
+        let above_start = self.start.map(|start| start <= value).unwrap_or(true);
+        let below_end = match self.end {
+            std::ops::Bound::Included(end) => value <= end,
+            std::ops::Bound::Excluded(end) => value < end,
+            std::ops::Bound::Unbounded => true,
+        };
+        above_start && below_end
+    }
+}
+
+/// A number and how far from it a match may be: `1.5±0.05` or `1.5~5%`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Tolerance {
+    value: f64,
+    delta: f64,
+}
+
+impl Tolerance {
+    fn parse(tolerance: &str) -> Option<Self> {
+        let (value, delta) = if let Some((value, delta)) = tolerance.split_once('±') {
+            (value.parse::<f64>().ok()?, delta.parse::<f64>().ok()?)
+        } else {
+            let (value, percent) = tolerance.split_once('~')?;
+            let value = value.parse::<f64>().ok()?;
+            let percent = percent.strip_suffix('%')?.parse::<f64>().ok()?;
+            (value, (value * percent / 100.0).abs())
+        };
+        (value.is_finite() && delta.is_finite() && 0.0 <= delta).then_some(Self { value, delta })
+    }
+
+    fn contains(&self, value: f64) -> bool {
+        // Allow for rounding so `1.55` is within `1.5±0.05`
+        let scale = value.abs().max(self.value.abs()).max(self.delta);
+        (value - self.value).abs() <= self.delta + 4.0 * f64::EPSILON * scale
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Segment<'e> {
+    Literal(&'e str),
+    /// `[..]`
+    Any,
+    /// `[..>]`, preferring the longest match
+    AnyGreedy,
+    Token(Token),
+}
+
+/// Split a line pattern into [`Segment`]s
+///
+/// `[literal:<text>]` matches `<text>` exactly, see [`parse_literal`].
+///
+/// Returns `None` if there are no [`Token`]s or escapes so callers can use a simpler matcher.
+pub(crate) fn parse_segments(expected: &str) -> Option<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut has_token = false;
+    let mut literal_start = 0;
+    let mut index = 0;
+    while let Some(offset) = expected[index..].find('[') {
+        let start = index + offset;
+        if let Some((literal, len)) = parse_literal(&expected[start..]) {
+            has_token = true;
+            if literal_start < start {
+                segments.push(Segment::Literal(&expected[literal_start..start]));
+            }
+            segments.push(Segment::Literal(literal));
+            index = start + len;
+            literal_start = index;
+            continue;
+        }
+        #[cfg(feature = "regex")]
+        if let Some((token, len)) = Token::parse_regex(&expected[start..]) {
+            has_token = true;
+            if literal_start < start {
+                segments.push(Segment::Literal(&expected[literal_start..start]));
+            }
+            segments.push(Segment::Token(token));
+            index = start + len;
+            literal_start = index;
+            continue;
+        }
+        let Some(end) = expected[start..].find(']').map(|end| start + end + 1) else {
+            break;
+        };
+        let raw = &expected[start..end];
+        // `[...]` is `[..]` that can also span lines, see `multiline_wildcard_span`
+        let segment = if raw == "[..]" || raw == "[...]" {
+            Some(Segment::Any)
+        } else if raw == "[..>]" {
+            Some(Segment::AnyGreedy)
+        } else {
+            Token::parse(raw).map(Segment::Token)
+        };
+        if let Some(segment) = segment {
+            has_token |=
+                matches!(segment, Segment::Token(_) | Segment::AnyGreedy) || raw == "[...]";
+            if literal_start < start {
+                segments.push(Segment::Literal(&expected[literal_start..start]));
+            }
+            segments.push(segment);
+            index = end;
+            literal_start = end;
+        } else {
+            index = start + 1;
+        }
+    }
+    if literal_start < expected.len() {
+        segments.push(Segment::Literal(&expected[literal_start..]));
+    }
+    has_token.then_some(segments)
+}
+
+/// Parse a `[literal:<text>]` escape at the start of `expected`, returning `<text>` and its length
+///
+/// Brackets within `<text>` must be balanced, so `[literal:[..]]` is the literal text `[..]`.
+pub(crate) fn parse_literal(expected: &str) -> Option<(&str, usize)> {
+    const PREFIX: &str = "[literal:";
+    let rest = expected.strip_prefix(PREFIX)?;
+    let mut depth = 0_usize;
+    for (index, c) in rest.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some((&rest[..index], PREFIX.len() + index + 1)),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Text matched by each `[ID:<name>]` so far
+pub(crate) type Backrefs = Vec<(String, String)>;
+
+/// Match `actual` against `segments`, binding `[ID:<name>]`s in `backrefs`
+///
+/// Names already in `backrefs` must match the same text.  On a mismatch, `backrefs` is unchanged.
+pub(crate) fn segments_match(
+    actual: &str,
+    segments: &[Segment<'_>],
+    backrefs: &mut Backrefs,
+    case_insensitive: bool,
+) -> bool {
+    let Some((first, rest)) = segments.split_first() else {
+        return actual.is_empty();
+    };
+    match first {
+        Segment::Literal(literal) => strip_prefix(actual, literal, case_insensitive)
+            .map(|remainder| segments_match(remainder, rest, backrefs, case_insensitive))
+            .unwrap_or(false),
+        Segment::Any => actual
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([actual.len()])
+            .any(|index| segments_match(&actual[index..], rest, backrefs, case_insensitive)),
+        Segment::AnyGreedy => actual
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([actual.len()])
+            .rev()
+            .any(|index| segments_match(&actual[index..], rest, backrefs, case_insensitive)),
+        Segment::Token(Token::Backref(name)) => {
+            if let Some((_, bound)) = backrefs.iter().find(|(bound_name, _)| bound_name == name) {
+                let bound = bound.clone();
+                return strip_prefix(actual, &bound, case_insensitive)
+                    .map(|remainder| segments_match(remainder, rest, backrefs, case_insensitive))
+                    .unwrap_or(false);
+            }
+            actual
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([actual.len()])
+                .rev()
+                .any(|len| {
+                    backrefs.push((name.clone(), actual[..len].to_owned()));
+                    if segments_match(&actual[len..], rest, backrefs, case_insensitive) {
+                        true
+                    } else {
+                        backrefs.pop();
+                        false
+                    }
+                })
+        }
+        Segment::Token(token) => token
+            .match_lens(actual)
+            .into_iter()
+            .any(|len| segments_match(&actual[len..], rest, backrefs, case_insensitive)),
+    }
+}
+
+/// [`str::strip_prefix`], optionally ignoring ASCII case
+pub(crate) fn strip_prefix<'a>(
+    text: &'a str,
+    prefix: &str,
+    case_insensitive: bool,
+) -> Option<&'a str> {
+    if case_insensitive {
+        let head = text.get(..prefix.len())?;
+        head.eq_ignore_ascii_case(prefix)
+            .then(|| &text[prefix.len()..])
+    } else {
+        text.strip_prefix(prefix)
+    }
+}
+
+/// [`str::find`], optionally ignoring ASCII case
+pub(crate) fn find(text: &str, needle: &str, case_insensitive: bool) -> Option<usize> {
+    if case_insensitive {
+        text.char_indices()
+            .map(|(index, _)| index)
+            .find(|index| strip_prefix(&text[*index..], needle, true).is_some())
+    } else {
+        text.find(needle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tolerance_parse() {
+        let cases = [
+            ("1.5±0.05", Some((1.45, 1.55, 1.56))),
+            ("200~5%", Some((190.0, 210.0, 211.0))),
+            ("-10~10%", Some((-11.0, -9.0, -8.0))),
+            ("1.5", None),
+            ("1.5±-1", None),
+            ("1.5~5", None),
+            ("a±1", None),
+        ];
+        for (tolerance, expected) in cases {
+            let actual = Tolerance::parse(tolerance);
+            match (actual, expected) {
+                (Some(actual), Some((min, max, outside))) => {
+                    assert!(actual.contains(min), "tolerance={tolerance:?}");
+                    assert!(actual.contains(max), "tolerance={tolerance:?}");
+                    assert!(!actual.contains(outside), "tolerance={tolerance:?}");
+                }
+                (None, None) => {}
+                (actual, expected) => {
+                    panic!("tolerance={tolerance:?}: {actual:?} != {expected:?}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn integer_range_parse() {
+        let cases = [
+            ("0..100", Some((0, 99, 100))),
+            ("0..=100", Some((0, 100, 101))),
+            ("-5..5", Some((-5, 4, 5))),
+            ("..", Some((i128::MIN, i128::MAX, i128::MAX))),
+            ("0", None),
+            ("a..b", None),
+            ("0..=", None),
+        ];
+        for (range, expected) in cases {
+            let actual = IntegerRange::parse(range);
+            match (actual, expected) {
+                (Some(actual), Some((min, max, excluded))) => {
+                    assert!(actual.contains(min), "range={range:?}");
+                    assert!(actual.contains(max), "range={range:?}");
+                    if excluded != max {
+                        assert!(!actual.contains(excluded), "range={range:?}");
+                    }
+                    if min != i128::MIN {
+                        assert!(!actual.contains(min - 1), "range={range:?}");
+                    }
+                }
+                (None, None) => {}
+                (actual, expected) => panic!("range={range:?}: {actual:?} != {expected:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use unicode_normalization::UnicodeNormalization as _;
+
+use super::Filter;
+use crate::Data;
+
+/// Normalize text to a Unicode normalization form
+///
+/// The same text can be encoded differently, like `é` as one code point or as `e` followed by a
+/// combining accent.  They render the same, so a mismatch is confusing to debug.  For example,
+/// macOS returns file names decomposed (NFD) while text written on Linux is usually composed
+/// (NFC).
+///
+/// Usually enabled through [`Assert::normalize_unicode`][crate::Assert::normalize_unicode].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::NormalizeUnicode::Nfc.filter("cafe\u{301}".into());
+/// assert_eq!(actual, snapbox::Data::text("caf\u{e9}"));
+/// ```
+///
+/// Note: Requires feature flag `unicode-normalization`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NormalizeUnicode {
+    /// Canonical composition, like `é` as one code point
+    Nfc,
+    /// Canonical decomposition, like `é` as `e` and a combining accent
+    Nfd,
+}
+
+impl NormalizeUnicode {
+    fn normalize(&self, text: &str) -> String {
+        match self {
+            Self::Nfc => text.nfc().collect(),
+            Self::Nfd => text.nfd().collect(),
+        }
+    }
+}
+
+impl Filter for NormalizeUnicode {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &|text| self.normalize(text))
+    }
+}
@@ -0,0 +1,82 @@
+use super::Filter;
+use crate::Data;
+
+/// Collapse runs of spaces and tabs into a single space
+///
+/// Column-aligned output, like tables, changes its padding whenever the width of a value changes.
+/// Collapsing the padding lets snapshots focus on the content.  Line breaks are left as-is.
+///
+/// Usually enabled through [`Assert::collapse_whitespace`][crate::Assert::collapse_whitespace].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterWhitespace.filter("name    size\nfoo\t\t 10\n".into());
+/// assert_eq!(actual, snapbox::Data::text("name size\nfoo 10\n"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterWhitespace;
+
+impl FilterWhitespace {
+    pub(crate) fn normalize(text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        let mut in_run = false;
+        for c in text.chars() {
+            if c == ' ' || c == '\t' {
+                if !in_run {
+                    normalized.push(' ');
+                }
+                in_run = true;
+            } else {
+                normalized.push(c);
+                in_run = false;
+            }
+        }
+        normalized
+    }
+}
+
+impl Filter for FilterWhitespace {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &Self::normalize)
+    }
+}
+
+/// Remove spaces and tabs from the end of each line
+///
+/// Editors and formatters often strip trailing whitespace from committed snapshots while tools
+/// pad columns with it.  Removing it from both sides keeps these from failing.
+///
+/// Usually enabled through
+/// [`Assert::ignore_trailing_whitespace`][crate::Assert::ignore_trailing_whitespace].
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::filter::Filter as _;
+///
+/// let actual = snapbox::filter::FilterTrailingWhitespace.filter("name  size  \nfoo   10\t\r\n".into());
+/// assert_eq!(actual, snapbox::Data::text("name  size\nfoo   10\r\n"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterTrailingWhitespace;
+
+impl FilterTrailingWhitespace {
+    pub(crate) fn normalize(text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        for line in crate::utils::LinesWithTerminator::new(text) {
+            let content = line.trim_end_matches(['\n', '\r']);
+            normalized.push_str(content.trim_end_matches([' ', '\t']));
+            normalized.push_str(&line[content.len()..]);
+        }
+        normalized
+    }
+}
+
+impl Filter for FilterTrailingWhitespace {
+    fn filter(&self, data: Data) -> Data {
+        super::filter_text(data, &Self::normalize)
+    }
+}
@@ -76,6 +76,8 @@ pub mod data;
 pub mod dir;
 pub mod filter;
 pub mod report;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 pub mod utils;
 
 pub use assert::Assert;
@@ -2,10 +2,32 @@
 ///
 /// By default [`filters`][crate::filter] are applied, including:
 /// - `...` is a line-wildcard when on a line by itself
+/// - `...{2}`, `...{0,3}`, and `...{1,}` limit how many lines `...` matches
 /// - `[..]` is a character-wildcard when inside a line
+/// - `[..>]` is `[..]` that matches as much as it can, resuming at the last occurrence of what
+///   follows it, like `[..>]/file` for `path/to/path/to/file`
+/// - `[...]` is a character-wildcard that can span lines, like for wrapped text, up to the line
+///   matching the next expected line
+/// - Lines between `<<<verbatim` and `>>>` must match exactly, without redactions
+/// - Lines between `[UNORDERED]` and `[/UNORDERED]` may match in any order
+/// - `[?] ` at the start of a line makes the rest of the line optional
+/// - `[!CONTAINS] ` at the start of a line fails if any line contains the rest of the line
+/// - `[!ONE_OF] A|B|C` matches a line against any one of the alternatives (`\|` for a
+///   literal `|`)
 /// - `[EXE]` matches `.exe` on Windows
+/// - `[N:0..100]` matches an integer in a range (`..=` for an inclusive end)
+/// - `[NUM:1.5±0.05]` and `[NUM:200~5%]` match a number within a tolerance
+/// - `[ID:<name>]` matches any text, the same each time `name` is used
+/// - `[SEMVER]` and `[SEMVER:^1.2]` match a semantic version (requires feature `semver`)
+/// - `[regex:<regex>]` matches text against a regex (requires feature `regex`)
+/// - `[literal:<text>]` matches `<text>` exactly, like `[literal:[..]]` or `[literal:...]`
 /// - `"{...}"` is a JSON value wildcard
+/// - `"{number}"`, `"{string}"`, `"{bool}"`, `"{array}"`, and `"{object}"` match any JSON value
+///   of that type
+/// - `"{regex:<pattern>}"` matches a JSON string against a regex (requires feature `regex`)
+/// - `"{duration}"` matches a JSON number or `{"secs": ..., "nanos": ...}` duration
 /// - `"...": "{...}"` is a JSON key-value wildcard
+/// - `{"$unordered": [...]}` matches a JSON array with the same elements in any order
 /// - `\` to `/`
 /// - Newlines
 ///
@@ -47,6 +69,53 @@ macro_rules! assert_data_eq {
     }};
 }
 
+/// Check that an expected value appears somewhere in a value
+///
+/// See [`Assert::contains`][crate::Assert::contains] for how `expected` is matched.
+///
+/// # Examples
+///
+/// ```rust
+/// # use snapbox::assert_data_contains;
+/// let log = "starting\nerror: disk full\nexiting\n";
+/// assert_data_contains!(log, "error: [..]\n");
+/// ```
+#[macro_export]
+macro_rules! assert_data_contains {
+    ($actual: expr, $expected: expr $(,)?) => {{
+        let actual = $crate::IntoData::into_data($actual);
+        let expected = $crate::IntoData::into_data($expected);
+        $crate::Assert::new()
+            .action_env($crate::assert::DEFAULT_ACTION_ENV)
+            .contains(actual, expected);
+    }};
+}
+
+/// [`Redactions`][crate::Redactions] for the package being tested and how it was built
+///
+/// See [`Redactions::insert_cargo_preset`][crate::Redactions::insert_cargo_preset].
+///
+/// # Examples
+///
+/// ```rust
+/// let subst = snapbox::cargo_redactions!();
+/// let version = format!("bin/{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+/// assert_eq!(subst.redact(&version), "bin/[CRATE_NAME] [CRATE_VERSION]");
+/// ```
+#[macro_export]
+macro_rules! cargo_redactions {
+    () => {{
+        let mut redactions = $crate::Redactions::new();
+        redactions
+            .insert_cargo_preset(
+                ::std::env!("CARGO_PKG_NAME"),
+                ::std::env!("CARGO_PKG_VERSION"),
+            )
+            .unwrap();
+        redactions
+    }};
+}
+
 /// Find the directory for your source file
 #[doc(hidden)] // forced to be visible in intended location
 #[macro_export]
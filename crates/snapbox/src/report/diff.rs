@@ -70,6 +70,102 @@ pub fn write_diff(
     Ok(())
 }
 
+/// A line in the diff between `expected` and `actual`, see [`diff_ops`]
+///
+/// Lines include their line terminator, if any.
+///
+/// Note: Requires feature flag `diff`
+#[cfg(feature = "diff")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffOp<'s> {
+    /// The line is in both `expected` and `actual`
+    Equal(&'s str),
+    /// The line is only in `expected`
+    Delete(&'s str),
+    /// The line is only in `actual`
+    Insert(&'s str),
+}
+
+/// Line-by-line diff of `expected` and `actual`
+///
+/// This is the diff that [`write_diff`] renders, for building custom reports.
+///
+/// # Examples
+///
+/// ```rust
+/// use snapbox::report::DiffOp;
+///
+/// let ops: Vec<_> = snapbox::report::diff_ops("a\nb\n", "a\nc\n").collect();
+/// assert_eq!(
+///     ops,
+///     [DiffOp::Equal("a\n"), DiffOp::Delete("b\n"), DiffOp::Insert("c\n")]
+/// );
+/// ```
+///
+/// Note: Requires feature flag `diff`
+#[cfg(feature = "diff")]
+pub fn diff_ops<'s>(expected: &'s str, actual: &'s str) -> impl Iterator<Item = DiffOp<'s>> {
+    let changes = text_diff(expected, actual);
+    let ops: Vec<_> = changes
+        .iter_all_changes()
+        .map(|change| match change.tag() {
+            similar::ChangeTag::Equal => DiffOp::Equal(change.value()),
+            similar::ChangeTag::Delete => DiffOp::Delete(change.value()),
+            similar::ChangeTag::Insert => DiffOp::Insert(change.value()),
+        })
+        .collect();
+    ops.into_iter()
+}
+
+/// Number of lines that differ between `expected` and `actual`
+///
+/// A changed line is counted once, rather than as both a removal and an addition.
+#[cfg(feature = "diff")]
+pub(crate) fn count_diff_lines(expected: &str, actual: &str) -> usize {
+    let mut count = 0;
+    let mut deleted = 0;
+    let mut inserted = 0;
+    for op in diff_ops(expected, actual) {
+        match op {
+            DiffOp::Equal(_) => {
+                count += deleted.max(inserted);
+                deleted = 0;
+                inserted = 0;
+            }
+            DiffOp::Delete(_) => deleted += 1,
+            DiffOp::Insert(_) => inserted += 1,
+        }
+    }
+    count + deleted.max(inserted)
+}
+
+/// Render a unified diff with 3 lines of context, empty if there are no changes
+///
+/// Unlike [`write_diff`], this is meant to be stored so it is uncolored and doesn't time out.
+#[cfg(feature = "diff")]
+pub(crate) fn unified_diff(baseline: &str, actual: &str) -> String {
+    if baseline == actual {
+        return String::new();
+    }
+    similar::TextDiff::configure()
+        .algorithm(similar::Algorithm::Patience)
+        .diff_lines(baseline, actual)
+        .unified_diff()
+        .context_radius(3)
+        .header("baseline", "actual")
+        .to_string()
+}
+
+#[cfg(feature = "diff")]
+fn text_diff<'s>(expected: &'s str, actual: &'s str) -> similar::TextDiff<'s, 's, 's, str> {
+    let timeout = std::time::Duration::from_millis(500);
+    similar::TextDiff::configure()
+        .algorithm(similar::Algorithm::Patience)
+        .timeout(timeout)
+        .newline_terminated(false)
+        .diff_lines(expected, actual)
+}
+
 #[cfg(feature = "diff")]
 #[allow(clippy::too_many_arguments)]
 fn write_diff_inner(
@@ -82,15 +178,10 @@ fn write_diff_inner(
     expected_line_offset: usize,
     actual_line_offset: usize,
 ) -> Result<(), std::fmt::Error> {
-    let timeout = std::time::Duration::from_millis(500);
     let min_elide = 20;
     let context = 5;
 
-    let changes = similar::TextDiff::configure()
-        .algorithm(similar::Algorithm::Patience)
-        .timeout(timeout)
-        .newline_terminated(false)
-        .diff_lines(expected, actual);
+    let changes = text_diff(expected, actual);
 
     writeln!(writer)?;
     if let Some(expected_name) = expected_name {
@@ -258,6 +349,26 @@ fn write_change(
 mod test {
     use super::*;
 
+    #[cfg(feature = "diff")]
+    #[test]
+    fn diff_ops_sequence() {
+        let expected = "Hello\nWorld\nGoodbye\n";
+        let actual = "Hello\nMoon\nGoodbye\nagain";
+
+        let ops: Vec<_> = diff_ops(expected, actual).collect();
+        assert_eq!(
+            ops,
+            [
+                DiffOp::Equal("Hello\n"),
+                DiffOp::Delete("World\n"),
+                DiffOp::Insert("Moon\n"),
+                DiffOp::Equal("Goodbye\n"),
+                DiffOp::Insert("again"),
+            ]
+        );
+        assert_eq!(count_diff_lines(expected, actual), 2);
+    }
+
     #[cfg(feature = "diff")]
     #[test]
     fn diff_eq() {
@@ -0,0 +1,175 @@
+use crate::assert::Comparison;
+
+/// Write [`Comparison`]s as a `JUnit` XML `<testsuite>`, for CI systems
+///
+/// Each comparison is a `<testcase>` named by its [key][Comparison::key].  Mismatches have a
+/// `<failure>` with the diff.  [Metadata][Comparison::meta] is written as `<properties>`.
+///
+/// # Examples
+///
+/// ```rust
+/// let assert = snapbox::Assert::new();
+/// let comparisons = [
+///     assert.clone().label("pass").compare("hello", "hello"),
+///     assert.clone().label("fail").compare("hello", "goodbye"),
+/// ];
+/// let mut xml = String::new();
+/// snapbox::report::write_junit(&mut xml, "snapshots", &comparisons).unwrap();
+/// assert!(xml.contains(r#"<testsuite name="snapshots" tests="2" failures="1">"#));
+/// ```
+///
+/// Note: Requires feature flag `junit`
+pub fn write_junit(
+    writer: &mut dyn std::fmt::Write,
+    name: &str,
+    comparisons: &[Comparison],
+) -> Result<(), std::fmt::Error> {
+    let failures = comparisons.iter().filter(|c| !c.is_match()).count();
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuite name="{}" tests="{}" failures="{}">"#,
+        escape(name),
+        comparisons.len(),
+        failures
+    )?;
+    for comparison in comparisons {
+        let name = escape(&comparison.key());
+        let meta = comparison.meta_entries();
+        if comparison.is_match() && meta.is_empty() {
+            writeln!(writer, r#"  <testcase name="{name}"/>"#)?;
+            continue;
+        }
+
+        writeln!(writer, r#"  <testcase name="{name}">"#)?;
+        if !meta.is_empty() {
+            writeln!(writer, "    <properties>")?;
+            for (key, value) in meta {
+                writeln!(
+                    writer,
+                    r#"      <property name="{}" value="{}"/>"#,
+                    escape(key),
+                    escape(value)
+                )?;
+            }
+            writeln!(writer, "    </properties>")?;
+        }
+        if !comparison.is_match() {
+            let mut diff = String::new();
+            crate::report::write_diff(
+                &mut diff,
+                comparison.expected(),
+                comparison.actual(),
+                Some(&"expected"),
+                Some(&"actual"),
+                crate::report::Palette::plain(),
+            )?;
+            writeln!(
+                writer,
+                r#"    <failure message="snapshot mismatch">{}</failure>"#,
+                escape(&diff)
+            )?;
+        }
+        writeln!(writer, "  </testcase>")?;
+    }
+    writeln!(writer, "</testsuite>")?;
+    Ok(())
+}
+
+/// Escape text for XML attributes and content
+///
+/// Control characters aren't allowed in XML 1.0, even escaped, so they are replaced.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn comparison(label: &str, actual: &str, expected: &str) -> Comparison {
+        Comparison {
+            label: Some(label.to_owned()),
+            location: "tests/cli.rs".to_owned(),
+            actual: actual.into(),
+            expected: expected.into(),
+            is_match: actual == expected,
+            bindings: Vec::new(),
+            #[cfg(feature = "diff")]
+            rendered: Some((expected.to_owned(), actual.to_owned())),
+        }
+    }
+
+    #[test]
+    fn pass_and_fail() {
+        let comparisons = [
+            comparison("pass", "hello\n", "hello\n"),
+            comparison("fail", "a < b\n", "a & b\n"),
+        ];
+        let mut actual = String::new();
+        write_junit(&mut actual, "cli", &comparisons).unwrap();
+
+        let mut diff = String::new();
+        crate::report::write_diff(
+            &mut diff,
+            &"a & b\n".into(),
+            &"a < b\n".into(),
+            Some(&"expected"),
+            Some(&"actual"),
+            crate::report::Palette::plain(),
+        )
+        .unwrap();
+        let diff = escape(&diff);
+        let expected = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="cli" tests="2" failures="1">
+  <testcase name="tests/cli.rs#pass"/>
+  <testcase name="tests/cli.rs#fail">
+    <failure message="snapshot mismatch">{diff}</failure>
+  </testcase>
+</testsuite>
+"#
+        );
+        assert_eq!(actual, expected);
+        assert!(actual.contains("a &amp; b"), "{actual}");
+        assert!(actual.contains("a &lt; b"), "{actual}");
+    }
+
+    #[test]
+    fn meta_properties() {
+        let mut comparison = comparison("pass", "hello\n", "hello\n");
+        comparison.expected = comparison.expected.with_meta("title", "Say \"hello\"");
+        let mut actual = String::new();
+        write_junit(&mut actual, "cli", &[comparison]).unwrap();
+        assert_eq!(
+            actual,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="cli" tests="1" failures="0">
+  <testcase name="tests/cli.rs#pass">
+    <properties>
+      <property name="title" value="Say &quot;hello&quot;"/>
+    </properties>
+  </testcase>
+</testsuite>
+"#
+        );
+    }
+
+    #[test]
+    fn escape_control_characters() {
+        assert_eq!(escape("\x1b[1m\"'\n"), "\u{FFFD}[1m&quot;&apos;\n");
+    }
+}
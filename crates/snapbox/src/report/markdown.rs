@@ -0,0 +1,177 @@
+use crate::report::DiffOp;
+
+/// Diffs with more lines than this are collapsed
+const MAX_INLINE_LINES: usize = 25;
+
+/// Write the diff between `expected` and `actual` as GitHub-flavored Markdown
+///
+/// This is for CI bots that post snapshot failures to pull requests.  The diff is a fenced
+/// ` ```diff ` block, collapsed in a `<details>` element when it is more than 25 lines.  The fence
+/// is made longer than any run of backticks in the content so the content can't end it early.
+///
+/// # Examples
+///
+/// ````rust
+/// let mut buffer = String::new();
+/// snapbox::report::write_markdown(
+///     &mut buffer,
+///     &"Hello\nWorld\n".into(),
+///     &"Hello\nMoon\n".into(),
+///     Some(&"tests/hello.txt"),
+///     Some(&"stdout"),
+/// )
+/// .unwrap();
+/// assert_eq!(buffer, "\
+/// ```diff
+/// --- tests/hello.txt
+/// +++ stdout
+///  Hello
+/// -World
+/// +Moon
+/// ```
+/// ");
+/// ````
+///
+/// Note: Requires feature flag `diff`
+pub fn write_markdown(
+    writer: &mut dyn std::fmt::Write,
+    expected: &crate::Data,
+    actual: &crate::Data,
+    expected_name: Option<&dyn std::fmt::Display>,
+    actual_name: Option<&dyn std::fmt::Display>,
+) -> Result<(), std::fmt::Error> {
+    let expected_name = expected_name.unwrap_or(&"expected").to_string();
+    let actual_name = actual_name.unwrap_or(&"actual").to_string();
+    let expected = expected.render().unwrap_or_else(|| expected.to_string());
+    let actual = actual.render().unwrap_or_else(|| actual.to_string());
+
+    let mut body = String::new();
+    let mut changed = 0;
+    for op in crate::report::diff_ops(&expected, &actual) {
+        let (marker, line) = match op {
+            DiffOp::Equal(line) => (' ', line),
+            DiffOp::Delete(line) => {
+                changed += 1;
+                ('-', line)
+            }
+            DiffOp::Insert(line) => {
+                changed += 1;
+                ('+', line)
+            }
+        };
+        body.push(marker);
+        body.push_str(line.strip_suffix('\n').unwrap_or(line));
+        body.push('\n');
+    }
+    let fence = "`".repeat(
+        longest_backtick_run(&body)
+            .max(longest_backtick_run(&expected_name))
+            .max(longest_backtick_run(&actual_name))
+            .max(2)
+            + 1,
+    );
+
+    let collapse = MAX_INLINE_LINES < body.lines().count();
+    if collapse {
+        writeln!(writer, "<details>")?;
+        writeln!(
+            writer,
+            "<summary>{} changed lines in {}</summary>",
+            changed,
+            escape_html(&expected_name)
+        )?;
+        writeln!(writer)?;
+    }
+    writeln!(writer, "{fence}diff")?;
+    writeln!(writer, "--- {expected_name}")?;
+    writeln!(writer, "+++ {actual_name}")?;
+    write!(writer, "{body}")?;
+    writeln!(writer, "{fence}")?;
+    if collapse {
+        writeln!(writer)?;
+        writeln!(writer, "</details>")?;
+    }
+    Ok(())
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_diff_with_backticks() {
+        let mut actual = String::new();
+        write_markdown(
+            &mut actual,
+            &"run `cargo test`\n```\ndone\n".into(),
+            &"run `cargo nextest`\n```\ndone\n".into(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            actual,
+            "\
+````diff
+--- expected
++++ actual
+-run `cargo test`
++run `cargo nextest`
+ ```
+ done
+````
+"
+        );
+    }
+
+    #[test]
+    fn large_diff_is_collapsed() {
+        let expected: String = (0..30).map(|i| format!("line {i}\n")).collect();
+        let actual = expected.replace("line 7\n", "line seven\n");
+        let mut rendered = String::new();
+        write_markdown(
+            &mut rendered,
+            &expected.into(),
+            &actual.into(),
+            Some(&"tests/<big>.txt"),
+            None,
+        )
+        .unwrap();
+        assert!(
+            rendered.starts_with(
+                "\
+<details>
+<summary>2 changed lines in tests/&lt;big&gt;.txt</summary>
+
+```diff
+--- tests/<big>.txt
++++ actual
+ line 0
+"
+            ),
+            "{rendered}"
+        );
+        assert!(rendered.contains("\n-line 7\n+line seven\n"), "{rendered}");
+        assert!(
+            rendered.ends_with(
+                "\
+ line 29
+```
+
+</details>
+"
+            ),
+            "{rendered}"
+        );
+    }
+}
@@ -2,8 +2,26 @@
 
 mod color;
 mod diff;
+#[cfg(feature = "junit")]
+mod junit;
+#[cfg(feature = "diff")]
+mod markdown;
+mod resolved;
 
 pub use color::Palette;
 pub(crate) use color::Style;
 pub use color::Styled;
+#[cfg(feature = "diff")]
+pub(crate) use diff::count_diff_lines;
+#[cfg(feature = "diff")]
+pub use diff::diff_ops;
+#[cfg(feature = "diff")]
+pub(crate) use diff::unified_diff;
 pub use diff::write_diff;
+#[cfg(feature = "diff")]
+pub use diff::DiffOp;
+#[cfg(feature = "junit")]
+pub use junit::write_junit;
+#[cfg(feature = "diff")]
+pub use markdown::write_markdown;
+pub use resolved::write_resolved;
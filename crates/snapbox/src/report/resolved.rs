@@ -0,0 +1,141 @@
+/// Render `expected` alongside what each of its patterns matched in `actual`
+///
+/// Each `[..]` and redaction placeholder is replaced with the text it matched and each `...` is
+/// expanded to the lines it elided.  This is useful for sanity-checking a heavily redacted
+/// snapshot that passes.
+///
+/// `actual` should not yet have redactions applied.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut redactions = snapbox::Redactions::new();
+/// redactions.insert("[NAME]", "World").unwrap();
+///
+/// let mut buffer = String::new();
+/// snapbox::report::write_resolved(
+///     &mut buffer,
+///     &"Hello [NAME]!\n[..] you?\n".into(),
+///     &"Hello World!\nHow are you?\n".into(),
+///     &redactions,
+///     snapbox::report::Palette::plain(),
+/// )
+/// .unwrap();
+/// assert_eq!(buffer, "\
+/// Hello [NAME]! | Hello World!
+/// [..] you?     | How are you?
+/// ");
+/// ```
+pub fn write_resolved(
+    writer: &mut dyn std::fmt::Write,
+    expected: &crate::Data,
+    actual: &crate::Data,
+    redactions: &crate::Redactions,
+    palette: crate::report::Palette,
+) -> Result<(), std::fmt::Error> {
+    let (Some(expected), Some(actual)) = (expected.render(), actual.render()) else {
+        writeln!(writer, "{}", palette.error("Only text can be resolved"))?;
+        return Ok(());
+    };
+
+    let resolved = crate::filter::resolve_str_to_redactions(&actual, &expected, redactions);
+    let width = resolved
+        .iter()
+        .map(|(pattern, _)| trim_terminator(pattern).chars().count())
+        .max()
+        .unwrap_or(0);
+    for (pattern, resolved) in resolved {
+        let pattern = trim_terminator(pattern);
+        write!(writer, "{pattern:<width$} {} ", palette.hint("|"))?;
+        match resolved {
+            Some(resolved) if resolved.is_empty() => {
+                writeln!(writer, "{}", palette.hint("(nothing)"))?;
+            }
+            Some(resolved) => {
+                let mut lines = crate::utils::LinesWithTerminator::new(&resolved);
+                let first = lines.next().unwrap_or_default();
+                writeln!(writer, "{}", palette.info(trim_terminator(first)))?;
+                for line in lines {
+                    writeln!(
+                        writer,
+                        "{:<width$} {} {}",
+                        "",
+                        palette.hint("|"),
+                        palette.info(trim_terminator(line))
+                    )?;
+                }
+            }
+            None => {
+                writeln!(writer, "{}", palette.error("(no match)"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn trim_terminator(line: &str) -> &str {
+    line.strip_suffix('\n').unwrap_or(line)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolved_wildcard_and_redaction() {
+        let mut redactions = crate::Redactions::new();
+        redactions.insert("[ROOT]", "/home/user").unwrap();
+        let expected = "\
+Compiling [..] v1.0.0 ([ROOT]/foo)
+...
+Finished
+";
+        let actual = "\
+Compiling foo v1.0.0 (/home/user/foo)
+warning: unused
+warning: unused
+Finished
+";
+
+        let mut actual_resolved = String::new();
+        write_resolved(
+            &mut actual_resolved,
+            &expected.into(),
+            &actual.into(),
+            &redactions,
+            crate::report::Palette::plain(),
+        )
+        .unwrap();
+        let expected_resolved = "\
+Compiling [..] v1.0.0 ([ROOT]/foo) | Compiling foo v1.0.0 (/home/user/foo)
+...                                | warning: unused
+                                   | warning: unused
+Finished                           | Finished
+";
+        assert_eq!(expected_resolved, actual_resolved);
+    }
+
+    #[test]
+    fn resolved_mismatch() {
+        let redactions = crate::Redactions::new();
+        let expected = "Hello [..]\n...\nGoodbye\n";
+        let actual = "Hello World\nHi\n";
+
+        let mut actual_resolved = String::new();
+        write_resolved(
+            &mut actual_resolved,
+            &expected.into(),
+            &actual.into(),
+            &redactions,
+            crate::report::Palette::plain(),
+        )
+        .unwrap();
+        let expected_resolved = "\
+Hello [..] | Hello World
+...        | (no match)
+Goodbye    | (no match)
+";
+        assert_eq!(expected_resolved, actual_resolved);
+    }
+}
@@ -0,0 +1,214 @@
+//! Capture [`tracing`](https://docs.rs/tracing) events for snapshotting
+//!
+//! Note: Requires feature flag `tracing`
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tracing_core::field::Field;
+use tracing_core::field::Visit;
+use tracing_core::span;
+use tracing_core::Event;
+use tracing_core::Metadata;
+
+/// Capture the events emitted on this thread while running `f`
+///
+/// Events are captured as [json lines][crate::data::DataFormat::JsonLines], one object per event
+/// with its `level`, `target`, and `fields`.
+///
+/// Volatile field values are redacted:
+/// - [`ThreadId`][std::thread::ThreadId]s become `[THREAD_ID]`
+/// - Timestamps, like RFC 3339 or [`SystemTime`][std::time::SystemTime], become `[TIMESTAMP]`
+///
+/// # Examples
+///
+/// ```rust
+/// # use snapbox::prelude::*;
+/// let actual = snapbox::tracing::capture(|| {
+///     tracing::info!(user = "alice", "logged in");
+/// });
+/// snapbox::assert_data_eq!(actual, snapbox::str![[r#"
+/// {"fields":{"message":"logged in","user":"alice"},"level":"INFO","target":"[..]"}
+///
+/// "#]].is_jsonlines());
+/// ```
+pub fn capture(f: impl FnOnce()) -> crate::Data {
+    let collector = Collector::default();
+    let events = collector.events.clone();
+    let dispatch = tracing_core::Dispatch::new(collector);
+    tracing_core::dispatcher::with_default(&dispatch, f);
+    let events = std::mem::take(&mut *events.lock().unwrap_or_else(|err| err.into_inner()));
+    crate::Data::jsonlines(events)
+}
+
+#[derive(Default)]
+struct Collector {
+    events: Arc<Mutex<Vec<serde_json::Value>>>,
+    next_span: AtomicU64,
+}
+
+impl tracing_core::Subscriber for Collector {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        // Ids must be non-zero
+        span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = FieldVisitor::default();
+        event.record(&mut fields);
+        let metadata = event.metadata();
+        let event = serde_json::json!({
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "fields": fields.fields,
+        });
+        self.events
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(event);
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: impl Into<serde_json::Value>) {
+        self.fields.insert(field.name().to_owned(), value.into());
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, redact_volatile(value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, redact_volatile(format!("{value:?}")));
+    }
+}
+
+fn redact_volatile(value: String) -> String {
+    if is_thread_id(&value) {
+        "[THREAD_ID]".to_owned()
+    } else if is_timestamp(&value) {
+        "[TIMESTAMP]".to_owned()
+    } else {
+        value
+    }
+}
+
+/// Whether `value` is the `Debug` output of a [`ThreadId`][std::thread::ThreadId]
+fn is_thread_id(value: &str) -> bool {
+    value
+        .strip_prefix("ThreadId(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
+/// Whether `value` looks like an RFC 3339 timestamp or the `Debug` output of a `SystemTime`
+fn is_timestamp(value: &str) -> bool {
+    if value.starts_with("SystemTime {") {
+        return true;
+    }
+
+    // `YYYY-MM-DDTHH:MM:SS`, allowing a space for the `T`
+    let shape = b"0000-00-00T00:00:00";
+    let bytes = value.as_bytes();
+    if bytes.len() < shape.len() {
+        return false;
+    }
+    let date_time_matches = shape
+        .iter()
+        .zip(bytes)
+        .all(|(expected, actual)| match expected {
+            b'0' => actual.is_ascii_digit(),
+            b'T' => matches!(actual, b'T' | b't' | b' '),
+            _ => expected == actual,
+        });
+    if !date_time_matches {
+        return false;
+    }
+
+    let mut rest = &value[shape.len()..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if digits == 0 {
+            return false;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = rest.trim_start();
+    matches!(offset, "" | "Z" | "z" | "UTC")
+        || (offset.len() == 6
+            && matches!(offset.as_bytes()[0], b'+' | b'-')
+            && offset[1..].bytes().enumerate().all(|(i, b)| {
+                if i == 2 {
+                    b == b':'
+                } else {
+                    b.is_ascii_digit()
+                }
+            }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn volatile_values() {
+        let cases = [
+            ("ThreadId(12)", "[THREAD_ID]"),
+            ("ThreadId()", "ThreadId()"),
+            ("2024-01-02T03:04:05Z", "[TIMESTAMP]"),
+            ("2024-01-02 03:04:05.123456+01:00", "[TIMESTAMP]"),
+            ("2024-01-02T03:04:05", "[TIMESTAMP]"),
+            ("SystemTime { tv_sec: 1, tv_nsec: 2 }", "[TIMESTAMP]"),
+            ("2024-01-02", "2024-01-02"),
+            ("2024-01-02T03:04:05 later", "2024-01-02T03:04:05 later"),
+            ("hello", "hello"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                redact_volatile(input.to_owned()),
+                expected,
+                "input={input:?}"
+            );
+        }
+    }
+}
@@ -53,3 +53,910 @@ fn actual_expected_formats_differ() {
 "#]].is_json().against_jsonlines(),
     );
 }
+
+#[test]
+fn is_sorted_lexicographic() {
+    snapbox::Assert::new().is_sorted("apple\nbanana\ncherry\n");
+}
+
+#[test]
+fn is_sorted_reports_first_out_of_order_pair() {
+    let err = snapbox::Assert::new()
+        .palette(snapbox::report::Palette::plain())
+        .try_is_sorted_by(
+            None,
+            "apple\ncherry\nbanana\nadvocado\n".into(),
+            &|left, right| left.cmp(right),
+        )
+        .unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+actual: lines 2 and 3 are out of order
+   2 | cherry
+   3 | banana
+...
+"#]]
+    );
+}
+
+#[test]
+fn is_sorted_by_numeric() {
+    let assert = snapbox::Assert::new();
+    let numeric = |left: &str, right: &str| {
+        let left = left.parse::<usize>().unwrap();
+        let right = right.parse::<usize>().unwrap();
+        left.cmp(&right)
+    };
+    assert.is_sorted_by("2\n10\n100\n", numeric);
+    assert!(assert
+        .try_is_sorted_by(None, "2\n10\n100\n".into(), &|left, right| left.cmp(right))
+        .is_err());
+}
+
+#[test]
+fn is_deterministic_pass() {
+    snapbox::Assert::new().is_deterministic(5, || "Hello world!\n");
+}
+
+#[test]
+fn is_deterministic_uses_eq_filters() {
+    let mut count = 0;
+    snapbox::Assert::new()
+        .sort_lines(true)
+        .collapse_whitespace(true)
+        .is_deterministic(4, || {
+            count += 1;
+            if count % 2 == 0 {
+                "b  2\na 1\n"
+            } else {
+                "a 1\nb 2\n"
+            }
+        });
+}
+
+#[test]
+fn is_deterministic_reports_divergent_run() {
+    let mut count = 0;
+    let err = snapbox::Assert::new()
+        .palette(snapbox::report::Palette::plain())
+        .try_is_deterministic(5, &mut || {
+            count += 1;
+            let value = if count < 3 { "first" } else { "later" };
+            format!("Hello {value}!\n").into()
+        })
+        .unwrap_err();
+    assert_eq!(count, 3);
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+run 3 of 5 differs from run 1
+...
+"#]]
+    );
+}
+
+#[test]
+#[cfg(feature = "diff")]
+fn eq_with_case_insensitive() {
+    let case_insensitive = |actual: &snapbox::Data, expected: &snapbox::Data| {
+        actual.to_string().to_lowercase() == expected.to_string().to_lowercase()
+    };
+    snapbox::Assert::new().eq_with("Hello\nWORLD\n", "hello\nworld\n", case_insensitive);
+
+    let err = snapbox::Assert::new()
+        .palette(snapbox::report::Palette::plain())
+        .try_eq_with(
+            None,
+            "Hello\nMoon\n".into(),
+            "hello\nworld\n".into(),
+            &case_insensitive,
+        )
+        .unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+
+--- Expected
++++ Actual
+   1      - hello
+   2      - world
+        1 + Hello
+        2 + Moon
+
+...
+"#]]
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn preprocess_jsonc_before_json() {
+    let actual = r#"{
+  // The crate
+  "name": "snapbox",
+  /* Not stable */
+  "version": "0.6.0"
+}
+"#;
+    let expected = str![[r#"
+{
+  "name": "snapbox",
+  "version": "[..]"
+}
+"#]]
+    .is_json();
+
+    snapbox::Assert::new()
+        .preprocess(
+            snapbox::data::DataFormat::Json,
+            snapbox::filter::FilterJsonComments,
+        )
+        .eq(actual, expected.clone());
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, actual.into_data(), expected)
+        .is_err());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn preprocess_only_applies_to_registered_format() {
+    let actual = "// not a comment\n";
+    snapbox::Assert::new()
+        .preprocess(
+            snapbox::data::DataFormat::Json,
+            snapbox::filter::FilterJsonComments,
+        )
+        .eq(actual, str![[r#"
+// not a comment
+
+"#]]);
+}
+
+#[test]
+fn compare_label_propagates() {
+    let comparison = snapbox::Assert::new()
+        .label("greeting")
+        .compare("Hello world\n", str!["Hello [..]"]);
+    assert!(comparison.is_match());
+    assert_eq!(comparison.label(), Some("greeting"));
+    assert!(
+        comparison.location().contains("assert.rs:"),
+        "{}",
+        comparison.location()
+    );
+    assert_eq!(
+        comparison.key(),
+        format!("{}#greeting", comparison.location())
+    );
+
+    let comparison = snapbox::Assert::new().compare("Goodbye\n", "Hello [..]\n");
+    assert!(!comparison.is_match());
+    assert_eq!(comparison.label(), None);
+    assert_eq!(comparison.key(), comparison.location());
+    assert_eq!(comparison.actual(), &"Goodbye\n".into_data());
+}
+
+#[test]
+fn track_usage_marks_compared_goldens() {
+    let usage = snapbox::assert::GoldenUsage::new();
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/usage");
+
+    snapbox::Assert::new()
+        .track_usage(&usage)
+        .eq("used\n", file!["usage/used.txt"]);
+    snapbox::Assert::new().eq("unused\n", file!["usage/unused.txt"]);
+
+    assert!(usage.is_used(root.join("used.txt")));
+    assert!(!usage.is_used(root.join("unused.txt")));
+    assert_eq!(usage.used().len(), 1);
+    #[cfg(feature = "dir")]
+    assert_eq!(
+        usage.unused_in(&root),
+        vec![root.join("unused.txt")]
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn lenient_json_trailing_commas_and_comments() {
+    let actual = r#"{
+  "name": "snapbox", // the crate
+  "features": ["json", "regex",],
+}
+"#;
+    let expected = str![[r#"
+{
+  "features": [
+    "json",
+    "regex"
+  ],
+  "name": "snapbox"
+}
+"#]]
+    .is_json();
+
+    snapbox::Assert::new()
+        .lenient_json()
+        .eq(actual, expected.clone());
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, actual.into_data(), expected)
+        .is_err());
+}
+
+#[test]
+fn normalize_quotes() {
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .normalize_quotes(Some(snapbox::filter::FilterQuotes::new()));
+    assert.eq("say 'hi'\n", "say \"hi\"\n");
+    assert.eq("say \"hi\" to '[..]'\n", "say 'hi' to \"[..]\"\n");
+    assert.eq("don't say 'hi'\n", "don't say `hi`\n");
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, "say 'hi'\n".into(), "say \"hi\"\n".into())
+        .is_err());
+    assert!(assert
+        .normalize_quotes(None)
+        .try_eq(None, "say 'hi'\n".into(), "say \"hi\"\n".into())
+        .is_err());
+}
+
+#[test]
+fn collapse_whitespace() {
+    let actual = "\
+name      size  modified
+foo.txt   10    yesterday
+bar.txt   2000  today
+";
+    let expected = "\
+name size modified
+foo.txt 10 [..]
+bar.txt 2000 today
+";
+    snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .collapse_whitespace(true)
+        .eq(actual, expected);
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, actual.into(), expected.into())
+        .is_err());
+}
+
+#[test]
+fn contains() {
+    let log = "\
+Compiling foo v0.1.0
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+Finished in 1.2s";
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .palette(snapbox::report::Palette::plain());
+    assert.contains(log, "warning: unused variable: [..]\n --> src/main.rs:[..]\n");
+    assert.contains(log, "Finished in [..]s");
+    assert.contains(log, snapbox::Data::text("unused variable").raw());
+    assert.contains(
+        "error: a\nnote: x\nerror: a\nnote: y\n",
+        "error: a\nnote: y\n",
+    );
+
+    let err = assert
+        .try_contains(None, log.into(), "warning: unused import\n".into())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("actual does not contain:\nwarning: unused import"),
+        "{err}"
+    );
+    assert!(assert
+        .try_contains(None, log.into(), "Compiling foo [..]\nFinished[..]\n".into())
+        .is_err());
+}
+
+#[test]
+fn contains_large_input() {
+    let mut log: String = (0..20000).map(|i| format!("line {i}\n")).collect();
+    log.push_str("warning: unused variable: `x`\n --> src/main.rs:2:9\n");
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Verify);
+    assert.contains(
+        log.as_str(),
+        "warning: unused variable: [..]\n --> src/main.rs:[..]\n",
+    );
+    assert.contains(log.as_str(), "line 19999\n...\n --> [..]\n");
+}
+
+#[test]
+fn ignore_trailing_whitespace() {
+    let actual = "name      size    \nfoo.txt   10      \n";
+    let expected = "name      size\nfoo.txt   [..]\n";
+    snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .ignore_trailing_whitespace(true)
+        .eq(actual, expected);
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, actual.into(), expected.into())
+        .is_err());
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn bindings_thread_captured_id() {
+    let mut redactions = snapbox::Redactions::new();
+    redactions
+        .insert("[ID]", regex::Regex::new("[0-9]+").unwrap())
+        .unwrap();
+
+    let created = snapbox::Assert::new()
+        .redact_with(redactions.clone())
+        .compare("created user 1234", str!["created user [ID]"]);
+    assert!(created.is_match());
+    assert_eq!(created.bindings(), [("[ID]", "1234".to_owned())]);
+
+    redactions.insert_bindings(created.bindings().to_vec()).unwrap();
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .redact_with(redactions);
+    assert.eq("deleted user 1234", str!["deleted user [ID]"]);
+    assert!(assert
+        .try_eq(
+            None,
+            "deleted user 9999".into(),
+            str!["deleted user [ID]"].into()
+        )
+        .is_err());
+}
+
+#[test]
+fn dump_actual_on_failure() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("dump_actual_on_failure.txt");
+    let _ = std::fs::remove_file(&path);
+    let mut redactions = snapbox::Redactions::new();
+    redactions.insert("[NAME]", "world").unwrap();
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .redact_with(redactions)
+        .dump_actual_to(&path);
+
+    assert.eq("Hello world!\n", "Hello [NAME]!\n");
+    assert!(!path.exists());
+
+    assert!(assert
+        .try_eq(None, "Goodbye world!\n".into(), "Hello [NAME]!\n".into())
+        .is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Goodbye [NAME]!\n");
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn unordered_at_pointer() {
+    use snapbox::IntoJson as _;
+
+    let expected = serde_json::json!({
+        "tags": ["fast", "slow", "[..]"],
+        "steps": ["build", "test"],
+    });
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .unordered_at("/tags");
+
+    assert.eq(
+        serde_json::json!({
+            "tags": ["flaky", "slow", "fast"],
+            "steps": ["build", "test"],
+        })
+        .into_json(),
+        expected.clone().into_json(),
+    );
+    assert!(assert
+        .try_eq(
+            None,
+            serde_json::json!({
+                "tags": ["fast", "slow", "flaky"],
+                "steps": ["test", "build"],
+            })
+            .into_json(),
+            expected.clone().into_json(),
+        )
+        .is_err());
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(
+            None,
+            serde_json::json!({
+                "tags": ["slow", "fast", "flaky"],
+                "steps": ["build", "test"],
+            })
+            .into_json(),
+            expected.into_json(),
+        )
+        .is_err());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn unordered_at_pointer_rejects_elide() {
+    use snapbox::IntoJson as _;
+
+    let err = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .unordered_at("/tags")
+        .try_eq(
+            None,
+            serde_json::json!({"tags": ["a", "b"]}).into_json(),
+            serde_json::json!({"tags": ["a", "{...}"]}).into_json(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`{...}` is not supported in unordered array `/tags`"),
+        "{err}"
+    );
+}
+
+#[test]
+#[cfg(feature = "diff")]
+fn max_diff_lines() {
+    let expected = "one\ntwo\nthree\nfour\n...\n";
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .max_diff_lines(2);
+
+    // Below
+    assert.eq("one\n2\nthree\nfour\nfive\nsix\n", expected);
+    // At
+    assert.eq("one\n2\nthree\n4\nfive\nsix\n", expected);
+    // Above
+    let err = assert
+        .try_eq(
+            None,
+            "1\n2\nthree\n4\nfive\nsix\n".into(),
+            expected.into(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("3 lines differ, more than the 2 allowed\n"),
+        "{err}"
+    );
+
+    // Custom comparisons aren't tolerated
+    assert!(assert
+        .try_eq_with(
+            None,
+            "one\n2\nthree\nfour\n".into(),
+            expected.into(),
+            &|actual, expected| actual == expected
+        )
+        .is_err());
+}
+
+#[test]
+#[cfg(feature = "diff")]
+fn max_diff_lines_overwrites() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("max_diff_lines_overwrites");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("expected.txt");
+    std::fs::write(&path, "one\ntwo\n").unwrap();
+
+    snapbox::Assert::new()
+        .action(snapbox::assert::Action::Overwrite)
+        .max_diff_lines(1)
+        .eq("one\n2\n", snapbox::Data::read_from(&path, None));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\n2\n");
+}
+
+#[test]
+#[cfg(feature = "diff")]
+fn diff_against_baseline() {
+    let baseline = "[package]\nname = \"foo\"\nversion = \"1.0.0\"\nedition = \"2021\"\n";
+    let actual = "[package]\nname = \"foo\"\nversion = \"1.1.0\"\nedition = \"2021\"\n";
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .palette(snapbox::report::Palette::plain());
+
+    assert.diff_against(baseline).eq(
+        actual,
+        str![[r#"
+--- baseline
++++ actual
+@@ -1,4 +1,4 @@
+ [package]
+ name = "foo"
+-version = "1.0.0"
++version = "1.1.[..]"
+ edition = "2021"
+
+"#]],
+    );
+    assert.diff_against(baseline).eq(baseline, "");
+
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        assert.diff_against(baseline).eq(
+            actual,
+            "\
+--- baseline
++++ actual
+@@ -1,4 +1,4 @@
+ [package]
+-name = \"foo\"
++name = \"bar\"
+ version = \"1.0.0\"
+ edition = \"2021\"
+",
+        );
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("+name = \"bar\""), "{message}");
+    assert!(message.contains("+version = \"1.1.0\""), "{message}");
+}
+
+#[test]
+fn eq_generated_caches_expected() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("eq_generated_caches_expected");
+    let _ = std::fs::remove_dir_all(&dir);
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .generated_cache_dir(&dir);
+
+    let calls = std::cell::Cell::new(0);
+    let check = |version_key: &str, expected: &'static str| {
+        assert.eq_generated("Hello world!", version_key, || {
+            calls.set(calls.get() + 1);
+            expected
+        });
+    };
+    check("v1", "Hello [..]!");
+    check("v1", "Hello [..]!");
+    assert_eq!(calls.get(), 1);
+
+    // A new version key regenerates
+    check("v2", "Hello world!");
+    check("v2", "Hello world!");
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn eq_generated_concurrent_threads() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("eq_generated_concurrent");
+    let _ = std::fs::remove_dir_all(&dir);
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .generated_cache_dir(&dir);
+
+    // Every thread shares the same entry and process
+    let check = || {
+        for _ in 0..20 {
+            assert.eq_generated("Hello world!", "v1", || "Hello [..]!");
+        }
+    };
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(check);
+        }
+    });
+}
+
+#[test]
+fn snapshot_path_by_convention() {
+    let snapshot = snapbox::snapshot!("help");
+    let expected = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/testsuite/snapshots/testsuite__assert__help.txt");
+    assert_eq!(
+        snapshot.source().and_then(|s| s.as_path()),
+        Some(expected.as_path())
+    );
+}
+
+#[test]
+fn snapshot_created_on_missing() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("snapshot_created_on_missing");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = snapbox::data::generate_named_snapshot_path(&dir, module_path!(), "greeting", None);
+    assert!(path.ends_with("snapshots/testsuite__assert__greeting.txt"));
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Overwrite);
+    assert.eq("Hello world!\n", snapbox::Data::read_from(&path, None));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Hello world!\n");
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Verify);
+    assert.eq("Hello world!\n", snapbox::Data::read_from(&path, None));
+}
+
+#[derive(Debug, Default)]
+struct MemorySource {
+    snapshots: std::sync::Mutex<std::collections::BTreeMap<String, String>>,
+}
+
+impl snapbox::data::SnapshotSource for MemorySource {
+    fn load(&self, key: &str) -> std::io::Result<snapbox::Data> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|s| snapbox::Data::text(s.clone()))
+            .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+    }
+
+    fn store(&self, key: &str, data: snapbox::Data) -> std::io::Result<()> {
+        let data = data.render().unwrap_or_default();
+        self.snapshots.lock().unwrap().insert(key.to_owned(), data);
+        Ok(())
+    }
+}
+
+#[test]
+fn snapshot_source_load_and_store() {
+    let source = std::sync::Arc::new(MemorySource::default());
+    source
+        .snapshots
+        .lock()
+        .unwrap()
+        .insert("greeting".to_owned(), "Hello [..]!\n".to_owned());
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Verify);
+    assert.eq(
+        "Hello world!\n",
+        snapbox::Data::read_from_source(source.clone(), "greeting"),
+    );
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Overwrite);
+    assert.eq(
+        "Goodbye world!\n",
+        snapbox::Data::read_from_source(source.clone(), "greeting"),
+    );
+    assert.eq(
+        "New\n",
+        snapbox::Data::read_from_source(source.clone(), "missing"),
+    );
+    assert_eq!(
+        *source.snapshots.lock().unwrap(),
+        [
+            ("greeting".to_owned(), "Goodbye world!\n".to_owned()),
+            ("missing".to_owned(), "New\n".to_owned()),
+        ]
+        .into_iter()
+        .collect()
+    );
+}
+
+#[test]
+fn file_source_relative_to_root() {
+    let root = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("file_source_relative_to_root");
+    let _ = std::fs::remove_dir_all(&root);
+    let source = std::sync::Arc::new(snapbox::data::FileSource::new(&root));
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Overwrite);
+    assert.eq(
+        "Hello world!\n",
+        snapbox::Data::read_from_source(source.clone(), "nested/greeting.txt"),
+    );
+    assert_eq!(
+        std::fs::read_to_string(root.join("nested/greeting.txt")).unwrap(),
+        "Hello world!\n"
+    );
+
+    let assert = snapbox::Assert::new().action(snapbox::assert::Action::Verify);
+    assert.eq(
+        "Hello world!\n",
+        snapbox::Data::read_from_source(source, "nested/greeting.txt"),
+    );
+}
+
+#[test]
+#[cfg(all(feature = "json", feature = "regex"))]
+fn redactions_from_file() {
+    let path =
+        std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("redactions_from_file.json");
+    std::fs::write(
+        &path,
+        r#"{
+  "[HOST]": "build-server-01",
+  "[ID]": {"regex": "[0-9]{6}"},
+  "[LOG]": {"glob": "/tmp/*.log"},
+  "[ROOT]": ["/home/ci", "/Users/ci"]
+}"#,
+    )
+    .unwrap();
+    let redactions = snapbox::Redactions::from_file(&path).unwrap();
+    assert_eq!(
+        redactions.redact("job 123456 on build-server-01 in /Users/ci/repo"),
+        "job [ID] on [HOST] in [ROOT]/repo"
+    );
+    assert_eq!(redactions.redact("see /tmp/build.log"), "see [LOG]");
+
+    std::fs::write(&path, r#"{"[ID]": {"regex": "[0-9", "flags": "i"}}"#).unwrap();
+    assert!(snapbox::Redactions::from_file(&path).is_err());
+    std::fs::write(&path, r#"{"ID": "1234"}"#).unwrap();
+    assert!(snapbox::Redactions::from_file(&path).is_err());
+}
+
+#[test]
+fn custom_filters_run_in_order() {
+    struct Replace(&'static str, &'static str);
+    impl snapbox::filter::Filter for Replace {
+        fn filter(&self, data: snapbox::Data) -> snapbox::Data {
+            match data.render() {
+                Some(text) => snapbox::Data::text(text.replace(self.0, self.1)),
+                None => data,
+            }
+        }
+    }
+
+    let boxed: Box<dyn snapbox::filter::Filter + Send + Sync> = Box::new(Replace("b", "c"));
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .filter(Replace("a", "b"))
+        .filter(boxed);
+    assert.eq("a-b-c\n", "c-c-c\n");
+    assert!(assert
+        .try_eq(None, "a-b-c\n".into(), "b-c-c\n".into())
+        .is_err());
+}
+
+#[test]
+fn absolute_paths_filter_runs_before_redactions() {
+    let mut redactions = snapbox::Redactions::new();
+    redactions.insert("[ROOT]", "/home/ci").unwrap();
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .redact_with(redactions)
+        .filter(snapbox::filter::FilterAbsolutePaths);
+    assert.eq("read /home/ci/src/lib.rs\n", "read [PATH]\n");
+    assert!(assert
+        .try_eq(
+            None,
+            "read /home/ci/src/lib.rs\n".into(),
+            "read [ROOT]/src/lib.rs\n".into()
+        )
+        .is_err());
+}
+
+#[test]
+fn sort_lines() {
+    let actual = "worker 3: ok\nworker 1: ok\nworker 2: failed\n";
+    let expected = "worker 1: ok\nworker 2: [..]\nworker 3: ok\n";
+    snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .sort_lines(true)
+        .eq(actual, expected);
+
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .try_eq(None, actual.into(), expected.into())
+        .is_err());
+    assert!(snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .sort_lines(true)
+        .sort_lines(false)
+        .try_eq(None, actual.into(), expected.into())
+        .is_err());
+}
+
+#[test]
+fn truncate_lines() {
+    let actual = "token: aGVsbG8gd29ybGQ=\n";
+    let expected = "token: aGV[TRUNC]\n";
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .truncate_lines(Some(snapbox::filter::TruncateLines::new(10)));
+    assert.eq(actual, expected);
+
+    assert!(assert
+        .truncate_lines(None)
+        .try_eq(None, actual.into(), expected.into())
+        .is_err());
+}
+
+#[test]
+fn report_redactions_on_failure() {
+    let mut redactions = snapbox::Redactions::new();
+    redactions.insert("[ROOT]", "/home/ci").unwrap();
+    redactions.insert("[TMP]", "/tmp/build").unwrap();
+    redactions.insert("[USER]", "alice").unwrap();
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .palette(snapbox::report::Palette::plain())
+        .redact_with(redactions)
+        .report_redactions(true);
+
+    let err = assert
+        .try_eq(
+            None,
+            "alice built /home/ci/project\nfailed\n".into(),
+            "[USER] built [ROOT]/project\nok\n".into(),
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(
+            "Redactions:
+  [ROOT]: \"/home/ci\"
+  [TMP]: no match
+  [USER]: \"alice\"
+"
+        ),
+        "{err}"
+    );
+
+    assert
+        .try_eq(None, "alice\n".into(), "[USER]\n".into())
+        .unwrap();
+}
+
+#[test]
+fn insert_env_hides_secret() {
+    std::env::set_var("SNAPBOX_TEST_INSERT_ENV", "s3cr3t-t0ken");
+    let mut redactions = snapbox::Redactions::new();
+    redactions
+        .insert_env("[TOKEN]", "SNAPBOX_TEST_INSERT_ENV")
+        .unwrap();
+    redactions
+        .insert_env("[MISSING]", "SNAPBOX_TEST_INSERT_ENV_UNSET")
+        .unwrap();
+    assert_eq!(redactions.redact("auth s3cr3t-t0ken"), "auth [TOKEN]");
+
+    let err = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .palette(snapbox::report::Palette::plain())
+        .redact_with(redactions)
+        .report_redactions(true)
+        .try_eq(
+            None,
+            "auth s3cr3t-t0ken\nfailed\n".into(),
+            "auth [TOKEN]\nok\n".into(),
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("  [TOKEN]: <hidden>\n"), "{err}");
+    assert!(!err.contains("s3cr3t-t0ken"), "{err}");
+}
+
+#[test]
+fn lint_patterns_before_comparing() {
+    let assert = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .lint_patterns(true);
+
+    let err = assert
+        .try_eq(None, "bin.exe\n".into(), "bin[EXEC]\n".into())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Malformed patterns in expected:\n  line 1: unknown placeholder `[EXEC]`\n"),
+        "{err}"
+    );
+
+    assert
+        .try_eq(None, "Hello world\n".into(), "Hello [..]\n".into())
+        .unwrap();
+}
+
+#[test]
+fn standard_redactions() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .filter(|dir| dir.join("Cargo.toml").exists())
+        .last()
+        .unwrap();
+    let actual = format!(
+        "{}\n{}\n",
+        root.join("src").display(),
+        std::env::temp_dir().join("out.txt").display()
+    );
+    snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .with_standard_redactions()
+        .eq(actual, "[ROOT]/src\n[TEMP]/out.txt\n");
+}
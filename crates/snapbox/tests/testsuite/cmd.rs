@@ -33,3 +33,206 @@ fn large_stdout_single() {
         .assert()
         .success();
 }
+
+#[test]
+fn stderr_empty_pass() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stdout", "hello")
+        .assert()
+        .success()
+        .stderr_empty();
+}
+
+#[test]
+fn stderr_empty_fail_shows_content() {
+    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stderr", "oh no")
+        .assert()
+        .with_assert(snapbox::Assert::new().palette(snapbox::report::Palette::plain()));
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        assert.stderr_empty();
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    // With the `debug` feature, a backtrace follows
+    let message = message.split("\nBacktrace:").next().unwrap();
+    snapbox::assert_data_eq!(
+        message,
+        snapbox::str![[r#"
+Expected empty stderr, was
+```
+oh no
+
+```
+
+"#]]
+    );
+}
+
+#[test]
+fn stderr_empty_follows_action() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stderr", "oh no")
+        .with_assert(snapbox::Assert::new().action(snapbox::assert::Action::Ignore))
+        .assert()
+        .stderr_empty();
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stderr", "oh no")
+        .with_assert(snapbox::Assert::new().action(snapbox::assert::Action::Skip))
+        .assert()
+        .stderr_empty();
+}
+
+#[test]
+fn stderr_empty_after_preprocess() {
+    struct DropWarnings;
+    impl snapbox::filter::Filter for DropWarnings {
+        fn filter(&self, data: snapbox::Data) -> snapbox::Data {
+            let text = data.render().unwrap_or_default();
+            let text: String = text
+                .lines()
+                .filter(|line| !line.starts_with("warning:"))
+                .collect();
+            snapbox::Data::text(text)
+        }
+    }
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stderr", "warning: deprecated")
+        .assert()
+        .with_assert(
+            snapbox::Assert::new().preprocess(snapbox::data::DataFormat::Text, DropWarnings),
+        )
+        .stderr_empty();
+}
+
+#[test]
+fn stream_stdout_eq_pass() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stdout", "Compiling foo\nCompiling bar\nRunning foo\nfinished in 2s")
+        .stream_stdout_eq("Compiling foo\n...\nRunning [..]\n[?] warning: slow\nfinished in [..]\n")
+        .success();
+}
+
+#[test]
+fn stream_stdout_eq_fails_fast() {
+    let start = std::time::Instant::now();
+    let err = std::panic::catch_unwind(|| {
+        snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+            .env("stdout", "one\nthree")
+            .env("sleep", "30")
+            .with_assert(snapbox::Assert::new().palette(snapbox::report::Palette::plain()))
+            .stream_stdout_eq("one\ntwo\n...\n");
+    })
+    .unwrap_err();
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(20),
+        "waited for the command to finish"
+    );
+    let message = err.downcast_ref::<String>().unwrap();
+    snapbox::assert_data_eq!(
+        message.as_str(),
+        snapbox::str![[r#"
+stdout line 2 does not match
+expected: two
+actual:   three
+...
+"#]]
+    );
+}
+
+#[test]
+#[cfg(all(feature = "pty", unix))]
+fn tty_colors_only_under_tty() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("color_if_tty", "1")
+        .assert()
+        .success()
+        .stdout_eq("green\n");
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("color_if_tty", "1")
+        .env("stderr", "oh no")
+        .tty(true)
+        .assert()
+        .success()
+        .stdout_eq("oh no\n\x1b[32mgreen\x1b[0m\n")
+        .stderr_empty();
+}
+
+#[test]
+#[cfg(all(feature = "pty", unix))]
+fn tty_stdin_is_subject_to_timeout() {
+    // More than the terminal buffers, for a command that never reads it
+    let stdin = "y\n".repeat(1024 * 1024);
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("sleep", "10")
+        .stdin(stdin)
+        .tty(true)
+        .timeout(std::time::Duration::from_millis(500))
+        .assert()
+        .interrupted();
+}
+
+#[test]
+#[cfg(all(feature = "pty", unix))]
+fn tty_size() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("echo_tty_size", "1")
+        .tty(true)
+        .assert()
+        .success()
+        .stdout_eq("80x24\n");
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("echo_tty_size", "1")
+        .tty_size(120, 40)
+        .assert()
+        .success()
+        .stdout_eq("120x40\n");
+}
+
+#[test]
+fn try_cargo_bin_finds_built_binary() {
+    let actual = snapbox::cmd::try_cargo_bin("snap-fixture").unwrap();
+    let expected = snapbox::cmd::cargo_bin!("snap-fixture");
+    assert_eq!(
+        actual.canonicalize().unwrap(),
+        expected.canonicalize().unwrap()
+    );
+    // Cached
+    assert_eq!(snapbox::cmd::try_cargo_bin("snap-fixture").unwrap(), actual);
+}
+
+#[test]
+fn try_cargo_bin_reports_missing_binary() {
+    let err = snapbox::cmd::try_cargo_bin("snap-missing").unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with(
+            "Could not find binary `snap-missing`; is it a `[[bin]]` target of this package?"
+        ),
+        "{message}"
+    );
+    assert!(message.contains("Looked in:"), "{message}");
+}
+
+#[test]
+fn redact_also_layers_onto_assert() {
+    let mut base = snapbox::Redactions::new();
+    base.insert("[GREETING]", "hello").unwrap();
+    let config = snapbox::Assert::new()
+        .action(snapbox::assert::Action::Verify)
+        .redact_with(base);
+    let mut extra = snapbox::Redactions::new();
+    extra.insert("[NAME]", "alice").unwrap();
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snap-fixture"))
+        .env("stdout", "hello alice")
+        .with_assert(config.clone())
+        .assert()
+        .redact_also(extra)
+        .stdout_eq("[GREETING] [NAME]\n");
+    assert_eq!(config.redactions().redact("hello alice"), "[GREETING] alice");
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "tracing")]
+
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+#[test]
+fn capture_events() {
+    let actual = snapbox::tracing::capture(|| {
+        tracing::info!(request_id = 42, path = "/health", "request started");
+        tracing::warn!(
+            elapsed_ms = 13,
+            thread = ?std::thread::current().id(),
+            "request finished"
+        );
+    });
+    assert_data_eq!(
+        actual,
+        str![[r#"
+{"fields":{"message":"request started","path":"/health","request_id":42},"level":"INFO","target":"testsuite::tracing"}
+{"fields":{"elapsed_ms":"{...}","message":"request finished","thread":"[THREAD_ID]"},"level":"WARN","target":"testsuite::tracing"}
+
+"#]]
+        .is_jsonlines()
+    );
+}
+
+#[test]
+fn capture_is_scoped() {
+    let actual = snapbox::tracing::capture(|| {});
+    tracing::info!("not captured");
+    assert_data_eq!(actual, str![""].is_jsonlines());
+}